@@ -0,0 +1,51 @@
+//! Compares building an IRI with `IriBuf::from_parts` and with a sequence of setters.
+//!
+//! Run with `cargo run --release --example from_parts`.
+extern crate iref;
+
+use iref::IriBuf;
+use std::convert::TryInto;
+use std::time::Instant;
+
+const N: usize = 100_000;
+
+fn with_setters() -> Result<IriBuf, iref::Error> {
+	let mut iri = IriBuf::new("https://www.rust-lang.org")?;
+
+	iri.authority_mut()
+		.unwrap()
+		.set_port(Some("40".try_into()?));
+	iri.set_path("/foo/bar".try_into()?);
+	iri.set_query(Some("query".try_into()?));
+	iri.set_fragment(Some("fragment".try_into()?));
+
+	Ok(iri)
+}
+
+fn with_from_parts() -> Result<IriBuf, iref::Error> {
+	IriBuf::from_parts(
+		Some("https"),
+		Some("www.rust-lang.org:40"),
+		"/foo/bar",
+		Some("query"),
+		Some("fragment"),
+	)
+}
+
+fn main() -> Result<(), iref::Error> {
+	assert_eq!(with_setters()?, with_from_parts()?);
+
+	let start = Instant::now();
+	for _ in 0..N {
+		std::hint::black_box(with_setters()?);
+	}
+	println!("setters:    {:?}", start.elapsed() / N as u32);
+
+	let start = Instant::now();
+	for _ in 0..N {
+		std::hint::black_box(with_from_parts()?);
+	}
+	println!("from_parts: {:?}", start.elapsed() / N as u32);
+
+	Ok(())
+}
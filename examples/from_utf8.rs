@@ -0,0 +1,49 @@
+//! Compares parsing IRIs from bytes with `Iri::from_utf8` and with `str::from_utf8` followed
+//! by `Iri::new`.
+//!
+//! Run with `cargo run --release --example from_utf8`.
+extern crate iref;
+
+use iref::Iri;
+use std::time::Instant;
+
+const N: usize = 100_000;
+
+const INPUTS: &[&[u8]] = &[
+	b"https://www.rust-lang.org/en-US/learn/get-started?ref=nav#installation",
+	b"http://user:password@[2001:db8::1]:8080/a/b/c;p?q=1&r=2",
+	b"urn:isbn:0451450523",
+	"https://ja.wikipedia.org/wiki/日本語?検索=言語#概要".as_bytes(),
+	b"mailto:someone@example.org",
+];
+
+fn main() {
+	for input in INPUTS {
+		let s = std::str::from_utf8(input).unwrap();
+		assert_eq!(Iri::from_utf8(input).unwrap(), Iri::new(s).unwrap());
+	}
+	assert!(Iri::from_utf8(b"http://example.org/\xC3").is_err());
+
+	let start = Instant::now();
+	for _ in 0..N {
+		for input in INPUTS {
+			let s = std::str::from_utf8(std::hint::black_box(input)).unwrap();
+			std::hint::black_box(Iri::new(s).unwrap());
+		}
+	}
+	println!(
+		"str::from_utf8 + Iri::new: {:?}",
+		start.elapsed() / (N * INPUTS.len()) as u32
+	);
+
+	let start = Instant::now();
+	for _ in 0..N {
+		for input in INPUTS {
+			std::hint::black_box(Iri::from_utf8(std::hint::black_box(input)).unwrap());
+		}
+	}
+	println!(
+		"Iri::from_utf8:            {:?}",
+		start.elapsed() / (N * INPUTS.len()) as u32
+	);
+}
@@ -0,0 +1,51 @@
+//! Compares resolving many references against the same base with a `Resolver`
+//! and with `IriRef::resolved`.
+//!
+//! Run with `cargo run --release --example resolver`.
+extern crate iref;
+
+use iref::{Iri, IriRef};
+use std::time::Instant;
+
+const N: usize = 10_000;
+
+const LINKS: &[&str] = &[
+	"g",
+	"../g",
+	"./a/b/c/index.html",
+	"../../static/style.css?v=2",
+	"h;x=1/./y/../z#s",
+	"images/photo.jpg",
+	"#top",
+	"/about",
+];
+
+fn main() {
+	let base = Iri::new("https://www.example.org/docs/guide/chapter/section/page.html").unwrap();
+	let links: Vec<IriRef> = LINKS
+		.iter()
+		.map(|link| IriRef::new(link).unwrap())
+		.collect();
+
+	let resolver = base.resolver();
+	for link in &links {
+		assert!(resolver.resolve(*link).eq_exact(&link.resolved(base)));
+	}
+
+	let start = Instant::now();
+	for _ in 0..N {
+		for link in &links {
+			std::hint::black_box(link.resolved(std::hint::black_box(base)));
+		}
+	}
+	println!("resolved: {:?}", start.elapsed() / (N * links.len()) as u32);
+
+	let start = Instant::now();
+	for _ in 0..N {
+		let resolver = std::hint::black_box(base).resolver();
+		for iri in resolver.resolve_all(links.iter().copied()) {
+			std::hint::black_box(iri);
+		}
+	}
+	println!("resolver: {:?}", start.elapsed() / (N * links.len()) as u32);
+}
@@ -0,0 +1,33 @@
+//! Sets many paths in a row on the same IRI, counting the reallocations of its buffer.
+//!
+//! Run with `cargo run --release --example set_path`.
+extern crate iref;
+
+use iref::{IriBuf, Path};
+use std::convert::TryFrom;
+use std::time::Instant;
+
+const N: usize = 10_000;
+
+fn main() -> Result<(), iref::Error> {
+	let paths: Vec<String> = (0..N).map(|i| format!("/users/{}/posts", i)).collect();
+	let mut iri = IriBuf::new("https://www.rust-lang.org/?query#fragment")?;
+
+	let mut reallocations = 0;
+	let mut capacity = iri.capacity();
+	let start = Instant::now();
+	for path in &paths {
+		iri.set_path(Path::try_from(std::hint::black_box(path.as_str()))?);
+		if iri.capacity() != capacity {
+			capacity = iri.capacity();
+			reallocations += 1;
+		}
+	}
+	let elapsed = start.elapsed();
+
+	assert_eq!(iri.path(), paths[N - 1].as_str());
+	println!("set_path:      {:?}", elapsed / N as u32);
+	println!("reallocations: {}", reallocations);
+
+	Ok(())
+}
@@ -0,0 +1,45 @@
+//! Compares checking the validity of IRIs with `is_valid_iri` and with `Iri::new`.
+//!
+//! Run with `cargo run --release --example validate`.
+extern crate iref;
+
+use iref::Iri;
+use std::time::Instant;
+
+const N: usize = 100_000;
+
+const INPUTS: &[&str] = &[
+	"https://www.rust-lang.org/en-US/learn/get-started?ref=nav#installation",
+	"http://user:password@[2001:db8::1]:8080/a/b/c;p?q=1&r=2",
+	"urn:isbn:0451450523",
+	"https://ja.wikipedia.org/wiki/日本語?検索=言語#概要",
+	"mailto:someone@example.org",
+];
+
+fn main() {
+	for input in INPUTS {
+		assert_eq!(iref::is_valid_iri(input), Iri::new(input).is_ok());
+	}
+
+	let start = Instant::now();
+	for _ in 0..N {
+		for input in INPUTS {
+			std::hint::black_box(Iri::new(std::hint::black_box(input)).is_ok());
+		}
+	}
+	println!(
+		"Iri::new:     {:?}",
+		start.elapsed() / (N * INPUTS.len()) as u32
+	);
+
+	let start = Instant::now();
+	for _ in 0..N {
+		for input in INPUTS {
+			std::hint::black_box(iref::is_valid_iri(std::hint::black_box(input)));
+		}
+	}
+	println!(
+		"is_valid_iri: {:?}",
+		start.elapsed() / (N * INPUTS.len()) as u32
+	);
+}
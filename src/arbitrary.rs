@@ -0,0 +1,322 @@
+//! [Arbitrary](https://docs.rs/arbitrary) support, for fuzzing.
+//!
+//! The [`IriBuf`] and [`IriRefBuf`] implementations of [`Arbitrary`] only generate valid
+//! IRIs (references), covering the whole grammar: schemes, authorities with user info,
+//! registered names, IPv4 and IPv6 hosts (with zone identifiers) and ports, paths with dot
+//! and empty segments, queries, fragments, non-ASCII and percent-encoded characters.
+//! Percent-encoded octets always encode valid UTF-8 characters.
+//!
+//! To exercise the error paths of a parser, [`Malformed`] generates byte strings that are
+//! *not* valid IRI references.
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt::Write;
+use core::net::{Ipv4Addr, Ipv6Addr};
+
+use ::arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+use crate::{IriBuf, IriRefBuf};
+
+const UNRESERVED: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+
+const SUB_DELIMS: &[u8] = b"!$&'()*+,;=";
+
+/// Some non-ASCII characters allowed in every component (`ucschar`).
+const UCSCHARS: &[char] = &['é', 'ß', 'Ω', '例', '日', '\u{10000}', '😀'];
+
+/// Push a random character of an IRI component.
+///
+/// `extra` is the set of ASCII characters allowed in the component, on top of the
+/// unreserved characters, sub-delimiters and percent-encoded characters.
+fn push_char(u: &mut Unstructured, extra: &[u8], output: &mut String) -> Result<()> {
+	match u.int_in_range(0..=9u8)? {
+		0 => {
+			let c: char = u.arbitrary()?;
+			let lowercase = u.arbitrary()?;
+			let mut buffer = [0; 4];
+			for b in c.encode_utf8(&mut buffer).bytes() {
+				if lowercase {
+					write!(output, "%{:02x}", b).unwrap()
+				} else {
+					write!(output, "%{:02X}", b).unwrap()
+				}
+			}
+		}
+		1 => output.push(*u.choose(UCSCHARS)?),
+		2 => output.push(*u.choose(SUB_DELIMS)? as char),
+		3 if !extra.is_empty() => output.push(*u.choose(extra)? as char),
+		_ => output.push(*u.choose(UNRESERVED)? as char),
+	}
+
+	Ok(())
+}
+
+/// Push up to `max` random characters of an IRI component.
+fn push_chars(u: &mut Unstructured, extra: &[u8], max: usize, output: &mut String) -> Result<()> {
+	for _ in 0..u.int_in_range(0..=max)? {
+		push_char(u, extra, output)?
+	}
+
+	Ok(())
+}
+
+fn push_scheme(u: &mut Unstructured, output: &mut String) -> Result<()> {
+	output.push(*u.choose(b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ")? as char);
+	for _ in 0..u.int_in_range(0..=6)? {
+		output.push(*u.choose(b"abcdefghijklmnopqrstuvwxyz0123456789+-.")? as char)
+	}
+
+	Ok(())
+}
+
+fn push_host(u: &mut Unstructured, output: &mut String) -> Result<()> {
+	match u.int_in_range(0..=4u8)? {
+		0 => write!(output, "{}", Ipv4Addr::from(u.arbitrary::<u32>()?)).unwrap(),
+		1 => write!(output, "[{}]", Ipv6Addr::from(u.arbitrary::<u128>()?)).unwrap(),
+		2 => {
+			write!(output, "[{}%25", Ipv6Addr::from(u.arbitrary::<u128>()?)).unwrap();
+			for _ in 0..u.int_in_range(1..=4)? {
+				output.push(*u.choose(UNRESERVED)? as char)
+			}
+			output.push(']')
+		}
+		_ => push_chars(u, b"", 12, output)?,
+	}
+
+	Ok(())
+}
+
+fn push_authority(u: &mut Unstructured, output: &mut String) -> Result<()> {
+	output.push_str("//");
+
+	if u.ratio(1, 4)? {
+		push_chars(u, b":", 8, output)?;
+		output.push('@')
+	}
+
+	push_host(u, output)?;
+
+	if u.ratio(1, 3)? {
+		output.push(':');
+		for _ in 0..u.int_in_range(0..=5)? {
+			output.push(*u.choose(b"0123456789")? as char)
+		}
+	}
+
+	Ok(())
+}
+
+/// Push a random path.
+///
+/// With an authority, the path is empty or absolute.
+/// Without authority, it never starts with `//`, and if `rootless_colon` is `false`
+/// the first segment of a rootless path contains no `:`.
+fn push_path(
+	u: &mut Unstructured,
+	has_authority: bool,
+	rootless_colon: bool,
+	output: &mut String,
+) -> Result<()> {
+	let absolute: bool = u.arbitrary()?;
+	if has_authority && !absolute {
+		return Ok(());
+	}
+
+	let len = u.int_in_range(0..=5)?;
+	for i in 0..len {
+		if absolute || i > 0 {
+			output.push('/')
+		}
+
+		match u.int_in_range(0..=5u8)? {
+			0 => output.push('.'),
+			1 => output.push_str(".."),
+			2 if i > 0 || has_authority => (),
+			_ => {
+				let extra: &[u8] = if i == 0 && !absolute && !rootless_colon {
+					b"@"
+				} else {
+					b":@"
+				};
+
+				// The first segment must not be empty, to avoid a leading `//`.
+				push_char(u, extra, output)?;
+				push_chars(u, extra, 6, output)?
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Push a random IRI reference, with a scheme if `scheme` is `true`.
+fn push_iri_ref(u: &mut Unstructured, scheme: bool, output: &mut String) -> Result<()> {
+	if scheme {
+		push_scheme(u, output)?;
+		output.push(':')
+	}
+
+	let has_authority = u.arbitrary()?;
+	if has_authority {
+		push_authority(u, output)?
+	}
+
+	push_path(u, has_authority, scheme, output)?;
+
+	if u.ratio(1, 3)? {
+		output.push('?');
+		push_chars(u, b":@/?", 12, output)?;
+		if u.ratio(1, 8)? {
+			output.push('\u{E000}')
+		}
+	}
+
+	if u.ratio(1, 3)? {
+		output.push('#');
+		push_chars(u, b":@/?", 12, output)?
+	}
+
+	Ok(())
+}
+
+impl<'a> Arbitrary<'a> for IriRefBuf {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let mut buffer = String::new();
+		let scheme = u.arbitrary()?;
+		push_iri_ref(u, scheme, &mut buffer)?;
+		IriRefBuf::try_from(buffer).map_err(|_| Error::IncorrectFormat)
+	}
+}
+
+impl<'a> Arbitrary<'a> for IriBuf {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let mut buffer = String::new();
+		push_iri_ref(u, true, &mut buffer)?;
+		IriBuf::try_from(buffer).map_err(|_| Error::IncorrectFormat)
+	}
+}
+
+/// Malformed IRI reference.
+///
+/// Its [`Arbitrary`] implementation generates byte strings that fail to parse as IRI
+/// references, obtained by corrupting valid ones: inserting forbidden characters,
+/// truncating percent-encoded characters or UTF-8 sequences, etc.
+#[derive(Debug, Clone)]
+pub struct Malformed(pub Vec<u8>);
+
+impl Malformed {
+	/// Returns the malformed bytes.
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.0
+	}
+
+	/// Consumes the value and returns the malformed bytes.
+	#[inline]
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.0
+	}
+}
+
+impl<'a> Arbitrary<'a> for Malformed {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		const CORRUPTIONS: &[&[u8]] = &[
+			b" ",
+			b"<",
+			b">",
+			b"\"",
+			b"{",
+			b"}",
+			b"|",
+			b"\\",
+			b"^",
+			b"`",
+			b"[",
+			b"]",
+			b"#",
+			b"%",
+			b"%4",
+			b"%g0",
+			b"\x00",
+			b"\x7F",
+			b"\xFF",
+			b"\xC3",
+			b"\xE4\xBE",
+			b"\xC0\xAF",
+			b"\xED\xA0\x80",
+			b"\xE2\x80\x8F",
+		];
+
+		let mut buffer = String::new();
+		let scheme = u.arbitrary()?;
+		push_iri_ref(u, scheme, &mut buffer)?;
+		let mut bytes = buffer.into_bytes();
+
+		for _ in 0..u.int_in_range(1..=3)? {
+			let corruption = u.choose(CORRUPTIONS)?;
+			let i = u.int_in_range(0..=bytes.len())?;
+			crate::replace(&mut bytes, i..i, corruption)
+		}
+
+		// Some corruptions may still give a valid IRI reference (for instance `#`
+		// or a right-to-left mark in a query), or may have been inserted in the middle of
+		// a UTF-8 sequence that they complete.
+		// An invalid UTF-8 byte makes sure it does not.
+		if IriRefBuf::new(&bytes).is_ok() {
+			bytes.push(0xFF)
+		}
+
+		Ok(Malformed(bytes))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Malformed;
+	use crate::{IriBuf, IriRefBuf};
+	use arbitrary::{Arbitrary, Unstructured};
+
+	/// Deterministic pseudo-random fuzzer input.
+	fn data(seed: u64) -> Vec<u8> {
+		let mut state = seed;
+		(0..1024)
+			.map(|_| {
+				state = state
+					.wrapping_mul(6364136223846793005)
+					.wrapping_add(1442695040888963407);
+				(state >> 33) as u8
+			})
+			.collect()
+	}
+
+	#[test]
+	fn valid() {
+		let mut authorities = 0;
+		let mut ipv6 = 0;
+		let mut pct = 0;
+		for seed in 0..1000 {
+			let data = data(seed);
+			let mut u = Unstructured::new(&data);
+			let iri_ref = IriRefBuf::arbitrary(&mut u).expect("invalid IRI reference");
+			assert!(IriRefBuf::new(iri_ref.as_str()).is_ok());
+
+			let iri = IriBuf::arbitrary(&mut u).expect("invalid IRI");
+			authorities += iri.authority().is_some() as usize;
+			ipv6 += iri.as_str().contains('[') as usize;
+			pct += iri.as_str().contains('%') as usize;
+		}
+
+		assert!(authorities > 100);
+		assert!(ipv6 > 10);
+		assert!(pct > 100);
+	}
+
+	#[test]
+	fn malformed() {
+		for seed in 0..1000 {
+			let data = data(seed);
+			let malformed = Malformed::arbitrary(&mut Unstructured::new(&data)).unwrap();
+			assert!(IriRefBuf::new(malformed.as_bytes()).is_err());
+		}
+	}
+}
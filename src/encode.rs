@@ -0,0 +1,98 @@
+//! Percent-encoding of arbitrary strings into IRI components.
+use alloc::string::String;
+use pct_str::Encoder;
+
+use crate::parsing;
+
+/// Encode the characters not accepted by the given predicate.
+struct Outside(fn(char) -> bool);
+
+impl Encoder for Outside {
+	#[inline]
+	fn encode(&self, c: char) -> bool {
+		!(self.0)(c)
+	}
+}
+
+#[inline]
+fn encode(s: &str, allowed: fn(char) -> bool) -> String {
+	let mut output = String::with_capacity(s.len());
+	crate::pct_encode(s, Outside(allowed), &mut output);
+	output
+}
+
+/// Percent-encode a string for use as a path segment.
+///
+/// Every character not allowed in a path segment (`isegment` in RFC 3987) is
+/// percent-encoded, including `/`, `?`, `#` and `%`.
+/// Non-ASCII characters allowed in IRIs are kept as is.
+///
+/// Note that a first segment containing a `:` must be preceded by `./` in a relative
+/// reference without scheme, which [`PathMut::push`](crate::PathMut::push) takes care of.
+///
+/// # Example
+/// ```
+/// use iref::encode_path_segment;
+///
+/// assert_eq!(encode_path_segment("a/b?c d"), "a%2Fb%3Fc%20d");
+/// assert_eq!(encode_path_segment("café:1"), "café:1");
+/// ```
+pub fn encode_path_segment(s: &str) -> String {
+	encode(s, parsing::is_segment_char)
+}
+
+/// Percent-encode a string for use as a query.
+///
+/// Every character not allowed in a query (`iquery` in RFC 3987) is percent-encoded,
+/// including `#` and `%`, but `/`, `?`, `&` and `=` are kept as is.
+/// Use [`IriRefBuf::append_query_param`](crate::IriRefBuf::append_query_param) to also encode
+/// `&` and `=` in the keys and values of `key=value` pairs.
+///
+/// # Example
+/// ```
+/// use iref::encode_query;
+///
+/// assert_eq!(encode_query("a=1&b=/x?#y"), "a=1&b=/x?%23y");
+/// ```
+pub fn encode_query(s: &str) -> String {
+	encode(s, parsing::is_query_char)
+}
+
+/// Percent-encode a string for use as a fragment.
+///
+/// Every character not allowed in a fragment (`ifragment` in RFC 3987) is percent-encoded,
+/// including `#` and `%`.
+///
+/// # Example
+/// ```
+/// use iref::encode_fragment;
+///
+/// assert_eq!(encode_fragment("section 1#2"), "section%201%232");
+/// ```
+pub fn encode_fragment(s: &str) -> String {
+	encode(s, parsing::is_fragment_char)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Fragment, Query, Segment};
+	use core::convert::TryFrom;
+
+	#[test]
+	fn encode_components() {
+		let input = "a b&c=d/e?f#g%h:é\u{E000}";
+
+		let segment = encode_path_segment(input);
+		assert_eq!(segment, "a%20b&c=d%2Fe%3Ff%23g%25h:é%EE%80%80");
+		assert!(Segment::try_from(segment.as_str()).is_ok());
+
+		let query = encode_query(input);
+		assert_eq!(query, "a%20b&c=d/e?f%23g%25h:é\u{E000}");
+		assert!(Query::try_from(query.as_str()).is_ok());
+
+		let fragment = encode_fragment(input);
+		assert_eq!(fragment, "a%20b&c=d/e?f%23g%25h:é%EE%80%80");
+		assert!(Fragment::try_from(fragment.as_str()).is_ok());
+	}
+}
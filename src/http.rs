@@ -0,0 +1,178 @@
+//! Conversions with the [`http`](https://docs.rs/http) crate.
+//!
+//! An [`http::Uri`] is either an absolute URI with a scheme and an authority,
+//! or one of the request target forms of HTTP (`/path?query`, `host:port` or `*`).
+//! Only the former can be converted into an [`IriBuf`].
+//!
+//! A [`Uri`] cannot hold a fragment, and would silently drop it when parsed.
+//! Instead, converting an IRI with a fragment fails with [`ToUriError::Fragment`]:
+//! use [`Iri::with_fragment`] to explicitly remove the fragment first.
+//! Non-ASCII characters are percent-encoded during the conversion (see [`Iri::to_uri`]),
+//! but a [`Uri`] host must be ASCII: convert internationalized hosts first with
+//! `IriBuf::host_to_ascii` (`idna` feature).
+//! IRIs without authority, such as `urn:isbn:0451450523`, are rejected.
+//!
+//! Note that a [`Uri`] never has an empty path: `http://example.com` is converted into
+//! `http://example.com/`.
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::error::Error as StdError;
+use core::fmt;
+
+use ::http::uri::{InvalidUri, Uri};
+
+use crate::{Error, Iri, IriBuf};
+
+/// Error returned when converting an IRI into a [`Uri`].
+#[derive(Debug)]
+pub enum ToUriError {
+	/// The IRI has a fragment, that cannot be represented by a [`Uri`].
+	Fragment,
+
+	/// The IRI is rejected by [`Uri`], for instance because it has no authority.
+	Invalid(InvalidUri),
+}
+
+impl fmt::Display for ToUriError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ToUriError::Fragment => write!(f, "fragments are not supported by `http::Uri`"),
+			ToUriError::Invalid(e) => e.fmt(f),
+		}
+	}
+}
+
+impl StdError for ToUriError {
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
+		match self {
+			ToUriError::Fragment => None,
+			ToUriError::Invalid(e) => Some(e),
+		}
+	}
+}
+
+impl From<InvalidUri> for ToUriError {
+	#[inline]
+	fn from(e: InvalidUri) -> ToUriError {
+		ToUriError::Invalid(e)
+	}
+}
+
+/// Convert a [`Uri`] into an IRI.
+///
+/// Fails with [`Error::MissingScheme`] if the URI has no scheme,
+/// such as the request targets `/path?query` or `*`.
+/// Characters of the path and query not allowed in an IRI (such as `|`) are percent-encoded.
+impl<'a> TryFrom<&'a Uri> for IriBuf {
+	type Error = Error;
+
+	fn try_from(uri: &'a Uri) -> Result<IriBuf, Error> {
+		let scheme = uri.scheme_str().ok_or(Error::MissingScheme)?;
+		let authority = uri.authority().ok_or(Error::InvalidAuthority)?.as_str();
+
+		let path = uri.path();
+		let query_len = uri.query().map(|q| q.len() + 1).unwrap_or(0);
+
+		let mut buffer =
+			String::with_capacity(scheme.len() + 3 + authority.len() + path.len() + query_len);
+		buffer.push_str(scheme);
+		buffer.push_str("://");
+		buffer.push_str(authority);
+		crate::pct_encode_lenient(path, &mut buffer);
+
+		if let Some(query) = uri.query() {
+			buffer.push('?');
+			crate::pct_encode_lenient(query, &mut buffer)
+		}
+
+		IriBuf::try_from(buffer)
+	}
+}
+
+/// Convert an IRI into a [`Uri`].
+///
+/// See the [module documentation](self) for the conversion policy.
+impl<'a> TryFrom<Iri<'a>> for Uri {
+	type Error = ToUriError;
+
+	#[inline]
+	fn try_from(iri: Iri<'a>) -> Result<Uri, ToUriError> {
+		if iri.fragment().is_some() {
+			return Err(ToUriError::Fragment);
+		}
+
+		Ok(Uri::try_from(iri.to_uri().as_str())?)
+	}
+}
+
+/// Convert an IRI into a [`Uri`].
+///
+/// See the [module documentation](self) for the conversion policy.
+impl<'a> TryFrom<&'a IriBuf> for Uri {
+	type Error = ToUriError;
+
+	#[inline]
+	fn try_from(iri: &'a IriBuf) -> Result<Uri, ToUriError> {
+		Uri::try_from(iri.as_iri())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ToUriError;
+	use crate::{Error, Iri, IriBuf};
+	use http::Uri;
+	use std::convert::TryFrom;
+
+	#[test]
+	fn round_trip() {
+		let inputs = [
+			"https://example.com/path?x=1",
+			"http://user@[::1]:8080/a/b?",
+			"foo://example.com/",
+		];
+
+		for input in &inputs {
+			let uri = Uri::try_from(Iri::new(input).unwrap()).unwrap();
+			assert_eq!(uri.to_string(), *input);
+			assert_eq!(IriBuf::try_from(&uri).unwrap().as_str(), *input);
+		}
+	}
+
+	#[test]
+	fn to_uri() {
+		let uri = Uri::try_from(&IriBuf::new("http://example.com").unwrap()).unwrap();
+		assert_eq!(uri.to_string(), "http://example.com/");
+
+		let uri = Uri::try_from(Iri::new("http://example.jp/日本?é").unwrap()).unwrap();
+		assert_eq!(uri.path(), "/%E6%97%A5%E6%9C%AC");
+		assert_eq!(uri.query(), Some("%C3%A9"));
+
+		let iri = Iri::new("http://例え.jp/").unwrap();
+		assert!(matches!(Uri::try_from(iri), Err(ToUriError::Invalid(_))));
+
+		let iri = Iri::new("https://example.com/path?x=1#section").unwrap();
+		assert!(matches!(Uri::try_from(iri), Err(ToUriError::Fragment)));
+
+		let uri = Uri::try_from(iri.with_fragment(None).unwrap().as_iri()).unwrap();
+		assert_eq!(uri.to_string(), "https://example.com/path?x=1");
+
+		let iri = Iri::new("urn:isbn:0451450523").unwrap();
+		assert!(matches!(Uri::try_from(iri), Err(ToUriError::Invalid(_))));
+	}
+
+	#[test]
+	fn from_uri() {
+		let uri: Uri = "http://example.com/a|b?c^d".parse().unwrap();
+		assert_eq!(
+			IriBuf::try_from(&uri).unwrap().as_str(),
+			"http://example.com/a%7Cb?c%5Ed"
+		);
+
+		let uri: Uri = "/path?query".parse().unwrap();
+		assert!(matches!(IriBuf::try_from(&uri), Err(Error::MissingScheme)));
+
+		let uri: Uri = "*".parse().unwrap();
+		assert!(matches!(IriBuf::try_from(&uri), Err(Error::MissingScheme)));
+	}
+}
@@ -0,0 +1,117 @@
+use crate::parsing::ParsedIriRef;
+use crate::{Iri, IriBuf, IriRefBuf, Path};
+
+/// Iterator over the ancestors of an IRI.
+///
+/// Returned by [`Iri::ancestors`].
+#[derive(Clone)]
+pub struct Ancestors<'a> {
+	iri: Iri<'a>,
+
+	/// Length of the path of the last ancestor, or `None` once the root is reached.
+	path_len: Option<usize>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+	type Item = IriBuf;
+
+	fn next(&mut self) -> Option<IriBuf> {
+		let path_offset = self.iri.p.path_offset();
+		let path = Path {
+			data: &self.iri.0.data[path_offset..(path_offset + self.path_len?)],
+		};
+
+		let path_len = match path.parent() {
+			Some(parent) => parent.len(),
+			None if path.is_relative() && !path.as_bytes().is_empty() => 0,
+			None => {
+				self.path_len = None;
+				return None;
+			}
+		};
+
+		self.path_len = Some(path_len);
+
+		// The ancestor paths are prefixes of the path, which never start with `//`
+		// without an authority.
+		let p = ParsedIriRef {
+			path_len,
+			query_len: None,
+			fragment_len: None,
+			..self.iri.p
+		};
+
+		Some(IriBuf(IriRefBuf {
+			p,
+			data: self.iri.0.data[..(path_offset + path_len)].to_vec(),
+		}))
+	}
+}
+
+impl<'a> Iri<'a> {
+	/// Iterate over the ancestors of this IRI, by successively removing the last path segment.
+	///
+	/// Each ancestor keeps the scheme and authority, but has no query nor fragment.
+	/// The ancestor of a path ending with a `/` is the parent directory,
+	/// so `/a/b/` and `/a/b` both have `/a/` as first ancestor.
+	/// The iteration ends with the root path `/`, or the empty path for
+	/// rootless paths.
+	/// The IRI itself is not included, and no dot segment is resolved: normalize the IRI
+	/// beforehand if it may contain some.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let iri = Iri::new("http://h/a/b/c?q#f").unwrap();
+	/// let ancestors: Vec<_> = iri.ancestors().collect();
+	/// assert_eq!(ancestors, ["http://h/a/b/", "http://h/a/", "http://h/"]);
+	///
+	/// let iri = Iri::new("foo:a/b").unwrap();
+	/// let ancestors: Vec<_> = iri.ancestors().collect();
+	/// assert_eq!(ancestors, ["foo:a/", "foo:"]);
+	/// ```
+	#[inline]
+	pub fn ancestors(&self) -> Ancestors<'a> {
+		Ancestors {
+			iri: *self,
+			path_len: Some(self.p.path_len),
+		}
+	}
+}
+
+impl IriBuf {
+	/// Iterate over the ancestors of this IRI, by successively removing the last path segment.
+	///
+	/// See [`Iri::ancestors`].
+	#[inline]
+	pub fn ancestors(&self) -> Ancestors<'_> {
+		self.as_iri().ancestors()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Iri, IriBuf};
+
+	#[test]
+	fn ancestors() {
+		let ancestors = |iri| -> Vec<IriBuf> { Iri::new(iri).unwrap().ancestors().collect() };
+
+		assert_eq!(ancestors("http://h/a/b/"), ["http://h/a/", "http://h/"]);
+		assert_eq!(ancestors("http://u@h:8/a?q"), ["http://u@h:8/"]);
+		assert_eq!(ancestors("http://h//a"), ["http://h//", "http://h/"]);
+		assert!(ancestors("http://h/").is_empty());
+		assert!(ancestors("http://h").is_empty());
+		assert_eq!(ancestors("foo:/a/b"), ["foo:/a/", "foo:/"]);
+		assert_eq!(ancestors("foo:a"), ["foo:"]);
+		assert!(ancestors("foo:").is_empty());
+		assert!(ancestors("foo:?q").is_empty());
+
+		let iri = IriBuf::new("file:/a/b.txt#f").unwrap();
+		let ancestors: Vec<_> = iri.ancestors().collect();
+		assert_eq!(ancestors, ["file:/a/", "file:/"]);
+		for ancestor in &ancestors {
+			assert!(ancestor.eq_exact(&IriBuf::new(ancestor.as_str()).unwrap()));
+		}
+	}
+}
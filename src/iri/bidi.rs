@@ -0,0 +1,255 @@
+use core::fmt::{self, Write};
+
+use crate::{Iri, IriBuf};
+
+/// Characters delimiting the components of an IRI, in the sense of
+/// [RFC 3987 section 4.2](https://tools.ietf.org/html/rfc3987#section-4.2):
+/// the generic and sub-delimiters, and the `.` separating the labels of a host.
+const DELIMITERS: &str = ":/?#[]@!$&'()*+,;=.";
+
+/// Left-to-right isolate.
+const LRI: char = '\u{2066}';
+
+/// First strong isolate.
+const FSI: char = '\u{2068}';
+
+/// Pop directional isolate.
+const PDI: char = '\u{2069}';
+
+/// Display of an IRI for a user interface, isolating bidirectional components.
+///
+/// Returned by [`Iri::bidi_display`].
+/// The IRI is wrapped in a left-to-right isolate, so that it is laid out from left to
+/// right whatever the surrounding text, and each component containing right-to-left
+/// characters is wrapped in a first strong isolate, so that its characters cannot be
+/// reordered with the delimiters or the neighbouring components.
+/// Bidirectional formatting characters of the IRI itself are written percent-encoded.
+///
+/// The result contains Unicode bidirectional controls: it is meant to be rendered,
+/// and is not a valid IRI.
+#[derive(Clone, Copy)]
+pub struct BidiDisplay<'a> {
+	data: &'a str,
+}
+
+impl<'a> fmt::Display for BidiDisplay<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_char(LRI)?;
+
+		let mut start = 0;
+		for (i, c) in self.data.char_indices() {
+			if DELIMITERS.contains(c) {
+				write_component(f, &self.data[start..i])?;
+				f.write_char(c)?;
+				start = i + 1;
+			}
+		}
+
+		write_component(f, &self.data[start..])?;
+		f.write_char(PDI)
+	}
+}
+
+/// Write a component, isolated if it contains right-to-left characters.
+fn write_component(f: &mut fmt::Formatter, component: &str) -> fmt::Result {
+	let isolate = displayed_chars(component).any(is_rtl);
+	if isolate {
+		f.write_char(FSI)?;
+	}
+
+	for c in component.chars() {
+		if is_bidi_control(c) {
+			let mut buffer = [0; 4];
+			for byte in c.encode_utf8(&mut buffer).bytes() {
+				write!(f, "%{:02X}", byte)?;
+			}
+		} else {
+			f.write_char(c)?;
+		}
+	}
+
+	if isolate {
+		f.write_char(PDI)?;
+	}
+
+	Ok(())
+}
+
+/// Characters of the given component, without the percent-encoded octets,
+/// which are displayed as ASCII characters with no direction.
+fn displayed_chars(component: &str) -> impl Iterator<Item = char> + '_ {
+	let mut chars = component.chars();
+	core::iter::from_fn(move || loop {
+		match chars.next()? {
+			'%' => {
+				chars.next();
+				chars.next();
+			}
+			c => return Some(c),
+		}
+	})
+}
+
+/// Checks if the given component breaks the rules of
+/// [RFC 3987 section 4.2](https://tools.ietf.org/html/rfc3987#section-4.2).
+fn is_hazardous(component: &str) -> bool {
+	let mut rtl = false;
+	let mut ltr = false;
+	let mut first = None;
+	let mut last = None;
+	for c in displayed_chars(component) {
+		if is_bidi_control(c) {
+			return true;
+		}
+
+		rtl |= is_rtl(c);
+		ltr |= is_ltr(c);
+		first.get_or_insert(c);
+		last = Some(c);
+	}
+
+	rtl && (ltr || !first.is_some_and(is_rtl) || !last.is_some_and(is_rtl))
+}
+
+/// Checks if the given character is a bidirectional formatting character,
+/// forbidden in IRIs by [RFC 3987 section 4.1](https://tools.ietf.org/html/rfc3987#section-4.1).
+fn is_bidi_control(c: char) -> bool {
+	matches!(
+		c,
+		'\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+	)
+}
+
+/// Checks if the given character belongs to a right-to-left script.
+///
+/// This is approximated by the Unicode blocks of the Hebrew, Arabic, Syriac, Thaana, NKo,
+/// Samaritan and Mandaic scripts, their presentation forms, and the right-to-left
+/// supplementary planes ranges.
+fn is_rtl(c: char) -> bool {
+	matches!(
+		c as u32,
+		0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+	) && !is_bidi_control(c)
+}
+
+/// Checks if the given character is a letter of a left-to-right script.
+fn is_ltr(c: char) -> bool {
+	c.is_alphabetic() && !is_rtl(c)
+}
+
+impl<'a> Iri<'a> {
+	/// Checks if this IRI may be displayed in a confusing order, because of right-to-left
+	/// characters.
+	///
+	/// Following [RFC 3987 section 4](https://tools.ietf.org/html/rfc3987#section-4),
+	/// the IRI is split into components at the delimiters (including the `.` between host
+	/// labels), and this returns `true` if it contains bidirectional formatting characters,
+	/// or if a component with right-to-left characters also has left-to-right letters,
+	/// or does not start and end with a right-to-left character.
+	/// Percent-encoded octets are ignored, as they are displayed as ASCII characters.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// assert!(!Iri::new("http://example.org/a").unwrap().has_bidi_hazard());
+	/// assert!(!Iri::new("http://ab.עברית.il/a").unwrap().has_bidi_hazard());
+	/// assert!(Iri::new("http://ab.עבabרית.il/a").unwrap().has_bidi_hazard());
+	/// assert!(Iri::new("http://ab.עברית1.il/a").unwrap().has_bidi_hazard());
+	/// ```
+	pub fn has_bidi_hazard(&self) -> bool {
+		self.as_str()
+			.split(|c| DELIMITERS.contains(c))
+			.any(is_hazardous)
+	}
+
+	/// Returns a display of this IRI for user interfaces, isolating its bidirectional
+	/// components with Unicode controls.
+	///
+	/// See [`BidiDisplay`].
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let iri = Iri::new("http://مثال.example/a").unwrap();
+	/// assert_eq!(
+	///   iri.bidi_display().to_string(),
+	///   "\u{2066}http://\u{2068}مثال\u{2069}.example/a\u{2069}"
+	/// );
+	/// ```
+	#[inline]
+	pub fn bidi_display(&self) -> BidiDisplay<'a> {
+		let data: &'a [u8] = self.0.data;
+		BidiDisplay {
+			data: unsafe { core::str::from_utf8_unchecked(data) },
+		}
+	}
+}
+
+impl IriBuf {
+	/// Checks if this IRI may be displayed in a confusing order, because of right-to-left
+	/// characters.
+	///
+	/// See [`Iri::has_bidi_hazard`].
+	#[inline]
+	pub fn has_bidi_hazard(&self) -> bool {
+		self.as_iri().has_bidi_hazard()
+	}
+
+	/// Returns a display of this IRI for user interfaces, isolating its bidirectional
+	/// components with Unicode controls.
+	///
+	/// See [`Iri::bidi_display`].
+	#[inline]
+	pub fn bidi_display(&self) -> BidiDisplay<'_> {
+		self.as_iri().bidi_display()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Iri, IriBuf};
+
+	#[test]
+	fn bidi_hazard() {
+		let hazard = |iri| Iri::new(iri).unwrap().has_bidi_hazard();
+
+		assert!(!hazard("http://example.org/a/b?q#f"));
+		assert!(!hazard("http://例え.jp/é"));
+		assert!(!hazard("http://עברית.example/a"));
+		assert!(!hazard("http://ab.מבחן123דף.il/a"));
+		assert!(!hazard("http://مثال.إختبار/صفحة?q=مفتاح#جزء"));
+		assert!(!hazard("http://example.org/%D7%90b"));
+
+		assert!(hazard("http://exampleעברית.il/a"));
+		assert!(hazard("http://ab.123עברית.il/a"));
+		assert!(hazard("http://ab.עברית1.il/a"));
+		assert!(hazard("http://example.org/مثالa"));
+		assert!(hazard("http://example.org/a?q=مفتاح-"));
+		assert!(hazard("http://example.org/a\u{200F}b"));
+		assert!(hazard("http://example.org/a#\u{202E}b"));
+
+		let iri = IriBuf::new("http://مثالexample.org").unwrap();
+		assert!(iri.has_bidi_hazard());
+	}
+
+	#[test]
+	fn bidi_display() {
+		let display = |iri| Iri::new(iri).unwrap().bidi_display().to_string();
+
+		assert_eq!(display("http://a/b"), "\u{2066}http://a/b\u{2069}");
+		assert_eq!(
+			display("http://ab.עברית.il/מבחן?q#f"),
+			"\u{2066}http://ab.\u{2068}עברית\u{2069}.il/\u{2068}מבחן\u{2069}?q#f\u{2069}"
+		);
+		assert_eq!(
+			display("http://a/b\u{202E}c"),
+			"\u{2066}http://a/b%E2%80%AEc\u{2069}"
+		);
+
+		let iri = IriBuf::new("http://a/%D7%90").unwrap();
+		assert_eq!(
+			iri.bidi_display().to_string(),
+			"\u{2066}http://a/%D7%90\u{2069}"
+		);
+	}
+}
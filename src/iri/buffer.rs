@@ -1,4 +1,5 @@
-use std::{
+use core::{
+	borrow::Borrow,
 	cmp::{Ord, Ordering, PartialOrd},
 	convert::TryFrom,
 	fmt,
@@ -7,13 +8,46 @@ use std::{
 	str::FromStr,
 };
 
+use alloc::{string::String, vec::Vec};
+
 use crate::{
-	iri::Iri, AsIri, AsIriRef, Authority, AuthorityMut, Error, Fragment, IriRef, IriRefBuf, Path,
-	PathMut, Query, Scheme,
+	iri::Iri, AsIri, AsIriRef, Authority, AuthorityMut, Error, Fragment, IntoSegments, IriRef,
+	IriRefBuf, Path, PathMut, Query, Resolver, Scheme,
 };
 
 /// Owned IRI.
-#[derive(Clone)]
+///
+/// ## Borrowing
+///
+/// The borrowed [`Iri<'a>`](Iri) type is not a view onto the buffer memory but a small
+/// `Copy` value pairing a `&'a [u8]` slice with its parsing data.
+/// Nothing of type `Iri` is stored inside an `IriBuf`, so there is no `&Iri` to hand out
+/// and this type can implement neither `Deref<Target = Iri>`, `AsRef<Iri>` nor `Borrow<Iri>`.
+/// Instead, [`as_iri`](IriBuf::as_iri) (or the [`AsIri`] trait for generic code)
+/// builds an `Iri<'a>` borrowing the buffer for `'a`:
+/// the buffer cannot be modified or dropped while the returned value is alive.
+///
+/// `IriBuf` dereferences to, and implements `AsRef`/`Borrow` for, [`IriRefBuf`] instead.
+/// Since both types hash and compare the same way, a `HashSet<IriBuf>` can be queried
+/// with an `&IriRefBuf`.
+///
+/// ```rust
+/// # use std::collections::HashSet;
+/// # use iref::{AsIri, Iri, IriBuf, IriRefBuf};
+/// # fn main() -> Result<(), iref::Error> {
+/// fn scheme_of(iri: impl AsIri) -> String {
+///     iri.as_iri().scheme().as_str().to_string()
+/// }
+///
+/// let iri = IriBuf::new("https://example.org/a")?;
+/// assert_eq!(scheme_of(&iri), "https");
+///
+/// let mut set = HashSet::new();
+/// set.insert(iri);
+/// assert!(set.contains(&IriRefBuf::new("https://example.org/%61")?));
+/// # Ok(())
+/// # }
+/// ```
 pub struct IriBuf(pub(crate) IriRefBuf);
 
 impl IriBuf {
@@ -27,6 +61,41 @@ impl IriBuf {
 		}
 	}
 
+	/// Create a new absolute IRI, rejecting fragments.
+	///
+	/// See [`Iri::new_absolute`].
+	#[inline]
+	pub fn new_absolute<S: AsRef<[u8]> + ?Sized>(buffer: &S) -> Result<IriBuf, Error> {
+		let iri = IriBuf::new(buffer)?;
+		if iri.is_absolute_uri() {
+			Ok(iri)
+		} else {
+			Err(Error::UnexpectedFragment)
+		}
+	}
+
+	/// Build an IRI from its components.
+	///
+	/// Fails with [`Error::MissingScheme`] if no scheme is given.
+	/// See [`IriRefBuf::from_parts`].
+	#[inline]
+	pub fn from_parts(
+		scheme: Option<&str>,
+		authority: Option<&str>,
+		path: &str,
+		query: Option<&str>,
+		fragment: Option<&str>,
+	) -> Result<IriBuf, Error> {
+		let scheme = scheme.ok_or(Error::MissingScheme)?;
+		Ok(IriBuf(IriRefBuf::from_parts(
+			Some(scheme),
+			authority,
+			path,
+			query,
+			fragment,
+		)?))
+	}
+
 	#[inline]
 	pub fn from_scheme(scheme: Scheme) -> IriBuf {
 		let mut iri_ref = IriRefBuf::default();
@@ -34,6 +103,54 @@ impl IriBuf {
 		IriBuf(iri_ref)
 	}
 
+	/// Create an IRI with the given scheme, and an empty path, with at least the given
+	/// capacity in bytes, the scheme included.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryInto;
+	/// # use iref::IriBuf;
+	/// let iri = IriBuf::with_capacity("https".try_into().unwrap(), 64);
+	/// assert_eq!(iri, "https:");
+	/// assert!(iri.capacity() >= 64);
+	/// ```
+	#[inline]
+	pub fn with_capacity(scheme: Scheme, capacity: usize) -> IriBuf {
+		let mut iri_ref = IriRefBuf::with_capacity(capacity);
+		iri_ref.set_scheme(Some(scheme));
+		IriBuf(iri_ref)
+	}
+
+	/// See [`IriRefBuf::capacity`].
+	#[inline]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	/// See [`IriRefBuf::reserve`].
+	#[inline]
+	pub fn reserve(&mut self, additional: usize) {
+		self.0.reserve(additional)
+	}
+
+	/// See [`IriRefBuf::shrink_to_fit`].
+	#[inline]
+	pub fn shrink_to_fit(&mut self) {
+		self.0.shrink_to_fit()
+	}
+
+	/// See [`IriRefBuf::into_string`].
+	#[inline]
+	pub fn into_string(self) -> String {
+		self.0.into_string()
+	}
+
+	/// See [`IriRefBuf::into_bytes`].
+	#[inline]
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.0.into_bytes()
+	}
+
 	#[inline]
 	pub fn as_iri(&self) -> Iri {
 		Iri(self.0.as_iri_ref())
@@ -44,11 +161,147 @@ impl IriBuf {
 		self.0.as_iri_ref()
 	}
 
+	/// Returns a reference to the byte representation of the IRI.
+	///
+	/// See [`IriRefBuf::as_bytes`].
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] {
+		self.0.as_bytes()
+	}
+
 	#[inline]
 	pub fn scheme(&self) -> Scheme {
 		self.0.scheme().unwrap()
 	}
 
+	/// Get the scheme of the IRI as a string slice.
+	///
+	/// See [`IriRef::scheme_str`].
+	#[inline]
+	pub fn scheme_str(&self) -> &str {
+		self.0.scheme_str().unwrap()
+	}
+
+	/// Return a copy of this IRI with the given scheme.
+	///
+	/// See [`Iri::with_scheme`].
+	#[inline]
+	pub fn with_scheme(&self, scheme: &str) -> Result<IriBuf, Error> {
+		self.as_iri().with_scheme(scheme)
+	}
+
+	/// Return a copy of this IRI with the given authority, or without authority.
+	///
+	/// See [`Iri::with_authority`].
+	#[inline]
+	pub fn with_authority(&self, authority: Option<&str>) -> Result<IriBuf, Error> {
+		self.as_iri().with_authority(authority)
+	}
+
+	/// Return a copy of this IRI with the given path.
+	///
+	/// See [`Iri::with_path`].
+	#[inline]
+	pub fn with_path(&self, path: &str) -> Result<IriBuf, Error> {
+		self.as_iri().with_path(path)
+	}
+
+	/// Return a copy of this IRI with the given query, or without query.
+	///
+	/// See [`Iri::with_query`].
+	#[inline]
+	pub fn with_query(&self, query: Option<&str>) -> Result<IriBuf, Error> {
+		self.as_iri().with_query(query)
+	}
+
+	/// Return a copy of this IRI with the given fragment, or without fragment.
+	///
+	/// See [`Iri::with_fragment`].
+	#[inline]
+	pub fn with_fragment(&self, fragment: Option<&str>) -> Result<IriBuf, Error> {
+		self.as_iri().with_fragment(fragment)
+	}
+
+	/// Resolve the given IRI reference against this base IRI.
+	///
+	/// See [`Iri::resolve_ref`].
+	#[inline]
+	pub fn resolve_ref<'b, R: Into<IriRef<'b>>>(&self, reference: R) -> IriBuf {
+		self.as_iri().resolve_ref(reference)
+	}
+
+	/// Create a resolver of IRI references against this IRI, used as base.
+	///
+	/// See [`Iri::resolver`].
+	#[inline]
+	pub fn resolver(&self) -> Resolver<'_> {
+		self.as_iri().resolver()
+	}
+
+	/// Strip the given base IRI from the start of this IRI.
+	///
+	/// See [`Iri::strip_prefix`].
+	#[inline]
+	pub fn strip_prefix<'b, Base: Into<Iri<'b>>>(&self, base: Base) -> Option<IriRef<'_>> {
+		self.as_iri().strip_prefix(base)
+	}
+
+	/// Checks if this IRI has the given origin, made of a scheme, a host and a port.
+	///
+	/// See [`Iri::origin_matches`].
+	#[inline]
+	pub fn origin_matches(&self, scheme: &str, host: &str, port: Option<u16>) -> bool {
+		self.as_iri().origin_matches(scheme, host, port)
+	}
+
+	/// Return the deepest base IRI shared by this IRI and `other`.
+	///
+	/// See [`Iri::common_base`].
+	#[inline]
+	pub fn common_base<'b, Other: Into<Iri<'b>>>(&self, other: Other) -> Option<IriBuf> {
+		self.as_iri().common_base(other)
+	}
+
+	/// Return a copy of this IRI without its fragment.
+	///
+	/// See [`Iri::without_fragment`].
+	#[inline]
+	pub fn without_fragment(&self) -> IriBuf {
+		self.as_iri().without_fragment()
+	}
+
+	/// Return the syntax-normalized form of this IRI.
+	///
+	/// See [`IriRef::normalized`].
+	#[inline]
+	pub fn normalized(&self) -> IriBuf {
+		self.as_iri().normalized()
+	}
+
+	/// Return the canonical form of this IRI.
+	///
+	/// See [`IriRef::canonical`].
+	#[inline]
+	pub fn canonical(&self) -> IriBuf {
+		self.as_iri().canonical()
+	}
+
+	/// Map this IRI to a URI, percent-encoding every non-ASCII character.
+	///
+	/// See [`IriRef::to_uri`].
+	#[inline]
+	pub fn to_uri(&self) -> IriBuf {
+		self.as_iri().to_uri()
+	}
+
+	/// Map this URI to an IRI, decoding percent-encoded non-ASCII characters where allowed.
+	///
+	/// See [`IriRef::to_iri`].
+	#[inline]
+	pub fn to_iri(&self) -> Result<IriBuf, Error> {
+		self.as_iri().to_iri()
+	}
+
 	/// Set the scheme of the IRI.
 	#[inline]
 	pub fn set_scheme(&mut self, scheme: Scheme) {
@@ -69,36 +322,221 @@ impl IriBuf {
 		self.0.set_authority(authority)
 	}
 
+	/// Parse and set the host of the authority.
+	///
+	/// See [`IriRefBuf::set_host_str`].
+	#[inline]
+	pub fn set_host_str(&mut self, host: &str) -> Result<(), Error> {
+		self.0.set_host_str(host)
+	}
+
 	#[inline]
 	pub fn path_mut(&mut self) -> PathMut {
 		self.0.path_mut()
 	}
 
+	/// Consume the IRI and iterate over the segments of its path.
+	///
+	/// See [`IriRefBuf::into_segments`].
+	#[inline]
+	pub fn into_segments(self) -> IntoSegments {
+		self.0.into_segments()
+	}
+
 	/// Set the IRI path.
+	///
+	/// See [`IriRefBuf::set_path`].
 	#[inline]
 	pub fn set_path(&mut self, path: Path) {
 		self.0.set_path(path)
 	}
 
+	/// Overwrite the IRI path with a path of the same length.
+	///
+	/// See [`IriRefBuf::replace_path_in_place`].
+	#[inline]
+	pub fn replace_path_in_place(&mut self, path: Path) -> bool {
+		self.0.replace_path_in_place(path)
+	}
+
+	/// Set the path and query of the IRI from a single `path?query` string.
+	///
+	/// See [`IriRefBuf::set_path_and_query`].
+	#[inline]
+	pub fn set_path_and_query(&mut self, path_and_query: &str) -> Result<(), Error> {
+		self.0.set_path_and_query(path_and_query)
+	}
+
+	/// Return a copy of this IRI with `path` appended to its path.
+	///
+	/// See [`IriRefBuf::join_mut`].
+	#[inline]
+	pub fn join(&self, path: &str) -> Result<IriBuf, Error> {
+		Ok(IriBuf(self.0.join(path)?))
+	}
+
+	/// Append `path` to the path of this IRI.
+	///
+	/// See [`IriRefBuf::join_mut`].
+	#[inline]
+	pub fn join_mut(&mut self, path: &str) -> Result<(), Error> {
+		self.0.join_mut(path)
+	}
+
 	#[inline]
 	pub fn set_query(&mut self, query: Option<Query>) {
 		self.0.set_query(query)
 	}
 
+	/// Convert the host to its ASCII form, using the IDNA `ToASCII` operation.
+	///
+	/// See [`IriRefBuf::host_to_ascii`].
+	#[cfg(feature = "idna")]
+	#[inline]
+	pub fn host_to_ascii(&mut self) -> Result<(), Error> {
+		self.0.host_to_ascii()
+	}
+
+	/// Convert the host to its Unicode form, using the IDNA `ToUnicode` operation.
+	///
+	/// See [`IriRefBuf::host_to_unicode`].
+	#[cfg(feature = "idna")]
+	#[inline]
+	pub fn host_to_unicode(&mut self) -> Result<(), Error> {
+		self.0.host_to_unicode()
+	}
+
+	/// Remove the port if its number is `default`.
+	///
+	/// See [`IriRefBuf::remove_default_port`].
+	#[inline]
+	pub fn remove_default_port(&mut self, default: u16) -> bool {
+		self.0.remove_default_port(default)
+	}
+
+	/// Normalize the percent-encoded characters of every component.
+	///
+	/// See [`IriRefBuf::normalize_percent_encoding`].
+	#[inline]
+	pub fn normalize_percent_encoding(&mut self) {
+		self.0.normalize_percent_encoding()
+	}
+
+	/// Replace or remove the extension of the final path segment.
+	///
+	/// See [`IriRefBuf::set_extension`].
+	#[inline]
+	pub fn set_extension(&mut self, extension: Option<&str>) -> bool {
+		self.0.set_extension(extension)
+	}
+
+	/// Set the value of a query parameter.
+	///
+	/// See [`IriRefBuf::set_query_param`].
+	#[inline]
+	pub fn set_query_param(&mut self, key: &str, value: Option<&str>) {
+		self.0.set_query_param(key, value)
+	}
+
+	/// Add a query parameter at the end of the query.
+	///
+	/// See [`IriRefBuf::append_query_param`].
+	#[inline]
+	pub fn append_query_param(&mut self, key: &str, value: Option<&str>) {
+		self.0.append_query_param(key, value)
+	}
+
+	/// Remove every occurrence of a query parameter.
+	///
+	/// See [`IriRefBuf::remove_query_param`].
+	#[inline]
+	pub fn remove_query_param(&mut self, key: &str) {
+		self.0.remove_query_param(key)
+	}
+
+	/// Only keep the query parameters for which `f` returns `true`.
+	///
+	/// See [`IriRefBuf::retain_query_params`].
+	#[inline]
+	pub fn retain_query_params<F: FnMut(&str, Option<&str>) -> bool>(&mut self, f: F) {
+		self.0.retain_query_params(f)
+	}
+
+	/// Sort the query parameters by key, then by value.
+	///
+	/// See [`IriRefBuf::sort_query_params`].
+	#[inline]
+	pub fn sort_query_params(&mut self) {
+		self.0.sort_query_params()
+	}
+
 	#[inline]
 	pub fn set_fragment(&mut self, fragment: Option<Fragment>) {
 		self.0.set_fragment(fragment)
 	}
 }
 
+/// Parse an owned IRI.
+///
+/// This is equivalent to [`IriBuf::new`].
 impl FromStr for IriBuf {
 	type Err = Error;
 
+	#[inline]
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		Self::new(s)
 	}
 }
 
+/// Parse an owned IRI.
+///
+/// This is equivalent to [`IriBuf::new`].
+impl<'a> TryFrom<&'a str> for IriBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(s: &'a str) -> Result<IriBuf, Error> {
+		IriBuf::new(s)
+	}
+}
+
+/// Parse an owned IRI.
+///
+/// This is equivalent to [`IriBuf::new`].
+impl<'a> TryFrom<&'a [u8]> for IriBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(bytes: &'a [u8]) -> Result<IriBuf, Error> {
+		IriBuf::new(bytes)
+	}
+}
+
+/// Parse an owned IRI, reusing the string buffer.
+impl TryFrom<String> for IriBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(s: String) -> Result<IriBuf, Error> {
+		let iri_ref = IriRefBuf::try_from(s)?;
+		if iri_ref.scheme().is_some() {
+			Ok(IriBuf(iri_ref))
+		} else {
+			Err(Error::MissingScheme)
+		}
+	}
+}
+
+impl Clone for IriBuf {
+	/// Clone the IRI, copying only its `len()` bytes.
+	///
+	/// The clone does not inherit the spare capacity of the buffer.
+	#[inline]
+	fn clone(&self) -> IriBuf {
+		IriBuf(self.0.clone())
+	}
+}
+
 impl AsIri for IriBuf {
 	#[inline]
 	fn as_iri(&self) -> Iri {
@@ -122,6 +560,34 @@ impl Deref for IriBuf {
 	}
 }
 
+impl AsRef<[u8]> for IriBuf {
+	#[inline]
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl AsRef<str> for IriBuf {
+	#[inline]
+	fn as_ref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl AsRef<IriRefBuf> for IriBuf {
+	#[inline]
+	fn as_ref(&self) -> &IriRefBuf {
+		&self.0
+	}
+}
+
+impl Borrow<IriRefBuf> for IriBuf {
+	#[inline]
+	fn borrow(&self) -> &IriRefBuf {
+		&self.0
+	}
+}
+
 impl fmt::Display for IriBuf {
 	#[inline]
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -132,7 +598,7 @@ impl fmt::Display for IriBuf {
 impl fmt::Debug for IriBuf {
 	#[inline]
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		self.as_iri().fmt(f)
+		self.as_iri().fmt_components("IriBuf", f)
 	}
 }
 
@@ -166,14 +632,49 @@ impl PartialEq<IriRefBuf> for IriBuf {
 	}
 }
 
-impl<'a> PartialEq<&'a str> for IriBuf {
+/// Normalizing comparison with a string.
+///
+/// See [`IriRef`]'s implementation, and compare [`as_str`](IriBuf::as_str)
+/// for a byte-exact comparison.
+impl PartialEq<str> for IriBuf {
 	#[inline]
-	fn eq(&self, other: &&'a str) -> bool {
-		if let Ok(other) = Iri::new(other) {
-			self == &other
-		} else {
-			false
-		}
+	fn eq(&self, other: &str) -> bool {
+		self.as_iri_ref() == *other
+	}
+}
+
+impl PartialEq<&str> for IriBuf {
+	#[inline]
+	fn eq(&self, other: &&str) -> bool {
+		*self == **other
+	}
+}
+
+impl PartialEq<String> for IriBuf {
+	#[inline]
+	fn eq(&self, other: &String) -> bool {
+		*self == *other.as_str()
+	}
+}
+
+impl PartialEq<IriBuf> for str {
+	#[inline]
+	fn eq(&self, other: &IriBuf) -> bool {
+		*other == *self
+	}
+}
+
+impl PartialEq<IriBuf> for &str {
+	#[inline]
+	fn eq(&self, other: &IriBuf) -> bool {
+		*other == **self
+	}
+}
+
+impl PartialEq<IriBuf> for String {
+	#[inline]
+	fn eq(&self, other: &IriBuf) -> bool {
+		*other == *self.as_str()
 	}
 }
 
@@ -0,0 +1,420 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::Write;
+use core::ops::Range;
+
+use crate::{Authority, Error, Fragment, Host, Iri, IriBuf, Path, Query, Scheme, UserInfo};
+
+#[inline]
+fn as_str(data: &[u8]) -> &str {
+	unsafe { core::str::from_utf8_unchecked(data) }
+}
+
+/// Port given to an [`IriBuilder`].
+#[derive(Clone, Copy)]
+enum PortValue<'a> {
+	Number(u16),
+	Str(&'a str),
+}
+
+/// IRI builder.
+///
+/// Collects the components of an IRI, each setter replacing the previous value of its
+/// component. Components are only validated by [`build`](IriBuilder::build),
+/// which allocates the resulting [`IriBuf`] once.
+///
+/// The [`userinfo`](IriBuilder::userinfo), [`host`](IriBuilder::host) and
+/// [`port`](IriBuilder::port) setters override the respective parts of the
+/// [`authority`](IriBuilder::authority), if any.
+/// Setting any of them adds an authority to the IRI.
+///
+/// ## Example
+///
+/// ```rust
+/// # use iref::{Iri, IriBuilder};
+/// # fn main() -> Result<(), iref::Error> {
+/// let iri = IriBuilder::new()
+///     .scheme("https")
+///     .host("example.org")
+///     .port(8080)
+///     .path("/search")
+///     .query("q=iri")
+///     .build()?;
+/// assert_eq!(iri, "https://example.org:8080/search?q=iri");
+///
+/// let mailto = IriBuilder::new()
+///     .scheme("mailto")
+///     .path("someone@example.org")
+///     .build()?;
+/// assert_eq!(mailto, "mailto:someone@example.org");
+///
+/// // Modify an existing IRI.
+/// let base = Iri::new("http://user@example.org/a?q#f")?;
+/// let iri = IriBuilder::from(base)
+///     .scheme("https")
+///     .userinfo(None)
+///     .fragment(None)
+///     .build()?;
+/// assert_eq!(iri, "https://example.org/a?q");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Clone)]
+pub struct IriBuilder<'a> {
+	scheme: Option<&'a str>,
+	authority: Option<&'a str>,
+	userinfo: Option<Option<&'a str>>,
+	host: Option<&'a str>,
+	port: Option<Option<PortValue<'a>>>,
+	path: &'a str,
+	query: Option<&'a str>,
+	fragment: Option<&'a str>,
+}
+
+impl<'a> IriBuilder<'a> {
+	/// Create a new builder with no component.
+	#[inline]
+	pub fn new() -> IriBuilder<'a> {
+		IriBuilder::default()
+	}
+
+	/// Set the scheme.
+	#[inline]
+	pub fn scheme(mut self, scheme: &'a str) -> Self {
+		self.scheme = Some(scheme);
+		self
+	}
+
+	/// Set the whole authority, or remove it.
+	///
+	/// Removing the authority also discards the user info, host and port previously set.
+	#[inline]
+	pub fn authority<A: Into<Option<&'a str>>>(mut self, authority: A) -> Self {
+		self.authority = authority.into();
+		if self.authority.is_none() {
+			self.userinfo = None;
+			self.host = None;
+			self.port = None;
+		}
+		self
+	}
+
+	/// Set the user info of the authority, or remove it.
+	#[inline]
+	pub fn userinfo<U: Into<Option<&'a str>>>(mut self, userinfo: U) -> Self {
+		self.userinfo = Some(userinfo.into());
+		self
+	}
+
+	/// Set the host of the authority.
+	#[inline]
+	pub fn host(mut self, host: &'a str) -> Self {
+		self.host = Some(host);
+		self
+	}
+
+	/// Set the port of the authority, or remove it.
+	#[inline]
+	pub fn port<P: Into<Option<u16>>>(mut self, port: P) -> Self {
+		self.port = Some(port.into().map(PortValue::Number));
+		self
+	}
+
+	/// Set the path.
+	///
+	/// If the IRI has an authority, the path must be empty or start with `/`.
+	#[inline]
+	pub fn path(mut self, path: &'a str) -> Self {
+		self.path = path;
+		self
+	}
+
+	/// Set the query, or remove it.
+	#[inline]
+	pub fn query<Q: Into<Option<&'a str>>>(mut self, query: Q) -> Self {
+		self.query = query.into();
+		self
+	}
+
+	/// Set the fragment, or remove it.
+	#[inline]
+	pub fn fragment<F: Into<Option<&'a str>>>(mut self, fragment: F) -> Self {
+		self.fragment = fragment.into();
+		self
+	}
+
+	/// Validate the components and build the IRI.
+	///
+	/// Fails with [`Error::MissingScheme`] if no scheme was given,
+	/// with the error of the first invalid component otherwise.
+	pub fn build(&self) -> Result<IriBuf, Error> {
+		let scheme = as_str(Scheme::try_from(self.scheme.ok_or(Error::MissingScheme)?)?.data);
+
+		let base = match self.authority {
+			Some(authority) => Some(Authority::try_from(authority)?),
+			None => None,
+		};
+
+		let has_authority =
+			base.is_some() || self.userinfo.is_some() || self.host.is_some() || self.port.is_some();
+
+		let userinfo = match self.userinfo {
+			Some(Some(userinfo)) => Some(as_str(UserInfo::try_from(userinfo)?.data)),
+			Some(None) => None,
+			None => base
+				.as_ref()
+				.and_then(|a| a.userinfo())
+				.map(|u| as_str(u.data)),
+		};
+
+		let host = match self.host {
+			Some(host) => as_str(Host::try_from(host)?.data),
+			None => base.as_ref().map(|a| as_str(a.host().data)).unwrap_or(""),
+		};
+
+		let port = match self.port {
+			Some(port) => port,
+			None => base
+				.as_ref()
+				.and_then(|a| a.port())
+				.map(|p| PortValue::Str(as_str(p.data))),
+		};
+
+		let path = as_str(Path::try_from(self.path)?.data);
+		if has_authority {
+			if !path.is_empty() && !path.starts_with('/') {
+				return Err(Error::InvalidPath);
+			}
+		} else if path.starts_with("//") {
+			return Err(Error::InvalidPath);
+		}
+
+		let query = match self.query {
+			Some(query) => Some(as_str(Query::try_from(query)?.data)),
+			None => None,
+		};
+
+		let fragment = match self.fragment {
+			Some(fragment) => Some(as_str(Fragment::try_from(fragment)?.data)),
+			None => None,
+		};
+
+		let mut len = scheme.len() + 1 + path.len();
+		if has_authority {
+			len += 2 + host.len();
+			len += userinfo.map(|u| u.len() + 1).unwrap_or(0);
+			len += match port {
+				Some(PortValue::Number(_)) => 6,
+				Some(PortValue::Str(p)) => p.len() + 1,
+				None => 0,
+			};
+		}
+		len += query.map(|q| q.len() + 1).unwrap_or(0);
+		len += fragment.map(|f| f.len() + 1).unwrap_or(0);
+
+		let mut buffer = String::with_capacity(len);
+		buffer.push_str(scheme);
+		buffer.push(':');
+
+		if has_authority {
+			buffer.push_str("//");
+			if let Some(userinfo) = userinfo {
+				buffer.push_str(userinfo);
+				buffer.push('@');
+			}
+			buffer.push_str(host);
+			match port {
+				Some(PortValue::Number(n)) => write!(buffer, ":{}", n).unwrap(),
+				Some(PortValue::Str(p)) => {
+					buffer.push(':');
+					buffer.push_str(p)
+				}
+				None => (),
+			}
+		}
+
+		buffer.push_str(path);
+
+		if let Some(query) = query {
+			buffer.push('?');
+			buffer.push_str(query);
+		}
+
+		if let Some(fragment) = fragment {
+			buffer.push('#');
+			buffer.push_str(fragment);
+		}
+
+		IriBuf::try_from(buffer)
+	}
+}
+
+/// Seed a builder with the components of an existing IRI.
+impl<'a> From<Iri<'a>> for IriBuilder<'a> {
+	fn from(iri: Iri<'a>) -> IriBuilder<'a> {
+		let iri_ref = iri.as_iri_ref();
+		let data = iri_ref.data;
+		let p = iri_ref.p;
+		let get = |range: Range<usize>| as_str(&data[range]);
+
+		let scheme_len = p.scheme_len.unwrap();
+		let authority = p.authority.map(|authority| {
+			let offset = p.authority_offset();
+			get(offset..(offset + authority.len()))
+		});
+
+		let path_offset = p.path_offset();
+		let query = p.query_len.map(|len| {
+			let offset = p.query_offset();
+			get(offset..(offset + len))
+		});
+		let fragment = p.fragment_len.map(|len| {
+			let offset = p.fragment_offset();
+			get(offset..(offset + len))
+		});
+
+		IriBuilder {
+			scheme: Some(get(0..scheme_len)),
+			authority,
+			path: get(path_offset..(path_offset + p.path_len)),
+			query,
+			fragment,
+			..IriBuilder::default()
+		}
+	}
+}
+
+impl<'a> From<&'a IriBuf> for IriBuilder<'a> {
+	#[inline]
+	fn from(iri: &'a IriBuf) -> IriBuilder<'a> {
+		iri.as_iri().into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::IriBuilder;
+	use crate::{Error, Iri};
+
+	#[test]
+	fn build() {
+		let iri = IriBuilder::new()
+			.scheme("foo")
+			.userinfo("user")
+			.host("example.com")
+			.port(8042)
+			.path("/over/there")
+			.query("name=ferret")
+			.fragment("nose")
+			.build()
+			.unwrap();
+		assert_eq!(
+			iri.as_str(),
+			"foo://user@example.com:8042/over/there?name=ferret#nose"
+		);
+
+		let iri = IriBuilder::new()
+			.scheme("urn")
+			.path("isbn:0451450523")
+			.build()
+			.unwrap();
+		assert_eq!(iri.as_str(), "urn:isbn:0451450523");
+
+		let iri = IriBuilder::new()
+			.scheme("file")
+			.host("")
+			.path("/etc/hosts")
+			.build()
+			.unwrap();
+		assert_eq!(iri.as_str(), "file:///etc/hosts");
+
+		let iri = IriBuilder::new()
+			.scheme("http")
+			.authority("user@a:80")
+			.host("b")
+			.build()
+			.unwrap();
+		assert_eq!(iri.as_str(), "http://user@b:80");
+
+		let iri = IriBuilder::new()
+			.scheme("http")
+			.authority("user@a:80")
+			.port(None)
+			.authority("c")
+			.build()
+			.unwrap();
+		assert_eq!(iri.as_str(), "http://c");
+	}
+
+	#[test]
+	fn build_capacity() {
+		let iri = IriBuilder::new()
+			.scheme("http")
+			.authority("a:1")
+			.path("/b")
+			.query("c")
+			.fragment("d")
+			.build()
+			.unwrap();
+		let bytes = iri.0.into_bytes();
+		assert_eq!(bytes.len(), bytes.capacity());
+	}
+
+	#[test]
+	fn build_invalid() {
+		let invalid = [
+			(IriBuilder::new().path("/a"), Error::MissingScheme),
+			(IriBuilder::new().scheme("1http"), Error::InvalidScheme),
+			(
+				IriBuilder::new().scheme("http").host("a/b"),
+				Error::InvalidHost,
+			),
+			(
+				IriBuilder::new().scheme("http").host("a").path("b"),
+				Error::InvalidPath,
+			),
+			(
+				IriBuilder::new().scheme("http").path("//a"),
+				Error::InvalidPath,
+			),
+			(
+				IriBuilder::new().scheme("http").path("/a?b"),
+				Error::InvalidPath,
+			),
+			(
+				IriBuilder::new().scheme("http").query("a#b"),
+				Error::InvalidQuery,
+			),
+		];
+
+		for (builder, expected) in &invalid {
+			let e = builder.build().unwrap_err();
+			assert_eq!(e.to_string(), expected.to_string());
+		}
+	}
+
+	#[test]
+	fn from_iri() {
+		let inputs = [
+			"https://www.rust-lang.org/foo/bar?query#frag",
+			"foo://user@example.com:8042/over/there?name=ferret#nose",
+			"http://a:/b?#",
+			"file:///etc/hosts",
+			"mailto:someone@example.org",
+		];
+
+		for input in &inputs {
+			let iri = Iri::new(input).unwrap();
+			assert_eq!(IriBuilder::from(iri).build().unwrap().as_str(), *input);
+		}
+
+		let iri = Iri::new("http://user@example.org:80/a?q#f").unwrap();
+		let iri = IriBuilder::from(iri)
+			.port(None)
+			.path("/b")
+			.query(None)
+			.build()
+			.unwrap();
+		assert_eq!(iri.as_str(), "http://user@example.org/b#f");
+	}
+}
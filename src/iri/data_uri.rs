@@ -0,0 +1,194 @@
+use alloc::vec::Vec;
+use pct_str::PctStr;
+
+use crate::{Iri, IriBuf};
+
+/// View of a `data:` URI, as defined by [RFC 2397](https://tools.ietf.org/html/rfc2397).
+///
+/// Returned by [`Iri::as_data_uri`].
+/// A `data:` URI is of the form `data:[<media type>][;base64],<payload>`,
+/// where the payload runs until the fragment, if any.
+///
+/// # Example
+/// ```
+/// # use iref::Iri;
+/// let iri = Iri::new("data:text/plain;base64,SGVsbG8=").unwrap();
+/// let data = iri.as_data_uri().unwrap();
+///
+/// assert_eq!(data.media_type(), Some("text/plain"));
+/// assert!(data.is_base64());
+/// assert_eq!(data.decode_payload().unwrap(), b"Hello");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DataUri<'a> {
+	media_type: &'a str,
+	is_base64: bool,
+	payload: &'a str,
+}
+
+impl<'a> DataUri<'a> {
+	/// Returns the media type, with its parameters, if any.
+	///
+	/// When absent, the media type is `text/plain;charset=US-ASCII` by default.
+	#[inline]
+	pub fn media_type(&self) -> Option<&'a str> {
+		if self.media_type.is_empty() {
+			None
+		} else {
+			Some(self.media_type)
+		}
+	}
+
+	/// Checks if the payload is base64-encoded.
+	#[inline]
+	pub fn is_base64(&self) -> bool {
+		self.is_base64
+	}
+
+	/// Returns the payload as it appears in the IRI, still encoded.
+	#[inline]
+	pub fn payload(&self) -> &'a str {
+		self.payload
+	}
+
+	/// Decode the payload.
+	///
+	/// The payload is percent-decoded, then base64-decoded if it is marked as such.
+	/// Returns `None` if the base64 encoding is invalid.
+	pub fn decode_payload(&self) -> Option<Vec<u8>> {
+		let bytes: Vec<u8> = unsafe { PctStr::new_unchecked(self.payload) }
+			.bytes()
+			.collect();
+
+		if self.is_base64 {
+			decode_base64(&bytes)
+		} else {
+			Some(bytes)
+		}
+	}
+}
+
+impl<'a> Iri<'a> {
+	/// Returns a view of this IRI as a `data:` URI, if it has the `data` scheme.
+	///
+	/// Returns `None` if the scheme is not `data` or if there is no `,` before the fragment
+	/// separating the media type from the payload.
+	#[inline]
+	pub fn as_data_uri(&self) -> Option<DataUri<'a>> {
+		if self.scheme() != "data" {
+			return None;
+		}
+
+		let data = self.path_and_query();
+		let comma = data.find(',')?;
+		let (header, payload) = (&data[..comma], &data[(comma + 1)..]);
+
+		let (media_type, is_base64) = match header.len().checked_sub(7) {
+			Some(i) if header[i..].eq_ignore_ascii_case(";base64") => (&header[..i], true),
+			_ => (header, false),
+		};
+
+		Some(DataUri {
+			media_type,
+			is_base64,
+			payload,
+		})
+	}
+}
+
+impl IriBuf {
+	/// Returns a view of this IRI as a `data:` URI, if it has the `data` scheme.
+	///
+	/// See [`Iri::as_data_uri`].
+	#[inline]
+	pub fn as_data_uri(&self) -> Option<DataUri<'_>> {
+		self.as_iri().as_data_uri()
+	}
+}
+
+/// Decode base64 (standard alphabet), with optional padding.
+// `usize::is_multiple_of` requires Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+	let data = match input {
+		[data @ .., b'=', b'='] | [data @ .., b'='] => {
+			if input.len() % 4 != 0 {
+				return None;
+			}
+
+			data
+		}
+		data => data,
+	};
+
+	if data.len() % 4 == 1 {
+		return None;
+	}
+
+	let mut output = Vec::with_capacity(data.len() * 3 / 4);
+	let mut buffer: u32 = 0;
+	let mut bits = 0;
+
+	for &b in data {
+		let value = match b {
+			b'A'..=b'Z' => b - b'A',
+			b'a'..=b'z' => b - b'a' + 26,
+			b'0'..=b'9' => b - b'0' + 52,
+			b'+' => 62,
+			b'/' => 63,
+			_ => return None,
+		};
+
+		buffer = (buffer << 6) | value as u32;
+		bits += 6;
+
+		if bits >= 8 {
+			bits -= 8;
+			output.push((buffer >> bits) as u8);
+			buffer &= (1 << bits) - 1;
+		}
+	}
+
+	Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Iri;
+
+	#[test]
+	fn base64() {
+		let iri = Iri::new("data:text/plain;base64,SGVsbG8=").unwrap();
+		let data = iri.as_data_uri().unwrap();
+		assert_eq!(data.media_type(), Some("text/plain"));
+		assert!(data.is_base64());
+		assert_eq!(data.payload(), "SGVsbG8=");
+		assert_eq!(data.decode_payload().unwrap(), b"Hello");
+
+		let data = |iri| Iri::new(iri).unwrap().as_data_uri().unwrap();
+		assert_eq!(data("data:;BASE64,SGk").decode_payload().unwrap(), b"Hi");
+		assert_eq!(data("data:;base64,SGk%3D").decode_payload().unwrap(), b"Hi");
+		assert_eq!(data("data:;base64,").decode_payload().unwrap(), b"");
+		assert!(data("data:;base64,SGk==").decode_payload().is_none());
+		assert!(data("data:;base64,S").decode_payload().is_none());
+		assert!(data("data:;base64,S!").decode_payload().is_none());
+	}
+
+	#[test]
+	fn percent_encoded() {
+		let iri = Iri::new("DATA:text/plain;charset=utf-8,a%20b%C3%A9?c#d").unwrap();
+		let data = iri.as_data_uri().unwrap();
+		assert_eq!(data.media_type(), Some("text/plain;charset=utf-8"));
+		assert!(!data.is_base64());
+		assert_eq!(data.payload(), "a%20b%C3%A9?c");
+		assert_eq!(data.decode_payload().unwrap(), "a bé?c".as_bytes());
+
+		let data = Iri::new("data:,").unwrap().as_data_uri().unwrap();
+		assert_eq!(data.media_type(), None);
+		assert_eq!(data.decode_payload().unwrap(), b"");
+
+		assert!(Iri::new("data:text/plain").unwrap().as_data_uri().is_none());
+		assert!(Iri::new("data:a#,b").unwrap().as_data_uri().is_none());
+		assert!(Iri::new("http://a/,b").unwrap().as_data_uri().is_none());
+	}
+}
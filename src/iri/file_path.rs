@@ -0,0 +1,333 @@
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf, Prefix};
+
+use crate::{encode_path_segment, Error, Iri, IriBuf};
+
+impl IriBuf {
+	/// Build a `file:` IRI from an absolute file system path.
+	///
+	/// Each component of the path becomes a path segment, percent-encoded with
+	/// [`encode_path_segment`], so `/`, `?`, `#` and `%` in file names are preserved.
+	/// On Windows, a drive letter becomes the first segment (`C:\foo` gives `file:///C:/foo`)
+	/// and the server of a UNC path becomes the host (`\\server\share\foo` gives
+	/// `file://server/share/foo`).
+	/// On Unix, bytes of file names that are not valid UTF-8 are percent-encoded.
+	///
+	/// Returns [`Error::InvalidPath`] if the path is not absolute or has an unsupported
+	/// Windows prefix (such as `\\.\COM1`), and [`Error::InvalidEncoding`] if a
+	/// component is not valid Unicode on platforms other than Unix.
+	///
+	/// # Example
+	/// ```
+	/// # #[cfg(unix)] {
+	/// # use iref::IriBuf;
+	/// # use std::path::Path;
+	/// let iri = IriBuf::from_file_path(Path::new("/tmp/a b#1.txt")).unwrap();
+	/// assert_eq!(iri, "file:///tmp/a%20b%231.txt");
+	/// # }
+	/// ```
+	pub fn from_file_path(path: &Path) -> Result<IriBuf, Error> {
+		if !path.is_absolute() {
+			return Err(Error::InvalidPath);
+		}
+
+		let mut iri = String::from("file://");
+		let mut is_root = true;
+		for component in path.components() {
+			match component {
+				Component::Prefix(prefix) => match prefix.kind() {
+					Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+						iri.push('/');
+						iri.push(letter as char);
+						iri.push(':');
+					}
+					Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+						push_encoded(server, &mut iri)?;
+						iri.push('/');
+						push_encoded(share, &mut iri)?;
+					}
+					_ => return Err(Error::InvalidPath),
+				},
+				Component::RootDir | Component::CurDir => (),
+				Component::ParentDir => {
+					iri.push_str("/..");
+					is_root = false;
+				}
+				Component::Normal(name) => {
+					iri.push('/');
+					push_encoded(name, &mut iri)?;
+					is_root = false;
+				}
+			}
+		}
+
+		if is_root {
+			iri.push('/');
+		}
+
+		IriBuf::new(&iri)
+	}
+}
+
+impl<'a> Iri<'a> {
+	/// Convert this `file:` IRI into a file system path.
+	///
+	/// The path segments are percent-decoded and joined with the separator of the platform.
+	/// The host must be empty or `localhost`, except on Windows where any other host gives
+	/// a UNC path.
+	/// On Windows, the first segment must also be a drive letter, such as `C:`.
+	/// The fragment is ignored.
+	///
+	/// Dot segments are not removed: to avoid path traversals, `.` and `..` segments,
+	/// including percent-encoded ones such as `%2E%2E`, are rejected.
+	/// Normalize the IRI first to remove them.
+	///
+	/// Returns `None` if the scheme is not `file`, if the IRI has a query, a user info, a
+	/// port or a relative path, if it contains a dot segment, or if a decoded segment cannot
+	/// be part of a path component (for instance because it contains a separator, or is not
+	/// valid UTF-8 on platforms other than Unix).
+	///
+	/// # Example
+	/// ```
+	/// # #[cfg(unix)] {
+	/// # use iref::Iri;
+	/// # use std::path::Path;
+	/// let iri = Iri::new("file:///tmp/a%20b%231.txt").unwrap();
+	/// assert_eq!(iri.to_file_path().unwrap(), Path::new("/tmp/a b#1.txt"));
+	/// # }
+	/// ```
+	pub fn to_file_path(&self) -> Option<PathBuf> {
+		if !self.scheme().as_str().eq_ignore_ascii_case("file") || self.query().is_some() {
+			return None;
+		}
+
+		let host = match self.authority() {
+			Some(authority) if authority.userinfo().is_some() || authority.port().is_some() => {
+				return None
+			}
+			Some(authority) if !authority.host().is_empty() && authority.host() != "localhost" => {
+				Some(authority.host_decoded())
+			}
+			_ => None,
+		};
+
+		let path = self.path();
+		if !path.is_absolute() {
+			return None;
+		}
+
+		let mut segments = Vec::new();
+		for segment in path.segments() {
+			let bytes: Vec<u8> = segment.as_pct_str().bytes().collect();
+			if bytes.contains(&b'/') || bytes.contains(&0) || bytes == b"." || bytes == b".." {
+				return None;
+			}
+
+			segments.push(bytes);
+		}
+
+		build_file_path(host, segments)
+	}
+}
+
+impl IriBuf {
+	/// Convert this `file:` IRI into a file system path.
+	///
+	/// See [`Iri::to_file_path`].
+	#[inline]
+	pub fn to_file_path(&self) -> Option<PathBuf> {
+		self.as_iri().to_file_path()
+	}
+}
+
+/// Percent-encode the given path component and push it to `output`.
+#[cfg(unix)]
+fn push_encoded(component: &OsStr, output: &mut String) -> Result<(), Error> {
+	use std::os::unix::ffi::OsStrExt;
+
+	let mut bytes = component.as_bytes();
+	loop {
+		match std::str::from_utf8(bytes) {
+			Ok(valid) => {
+				output.push_str(&encode_path_segment(valid));
+				return Ok(());
+			}
+			Err(e) => {
+				let (valid, rest) = bytes.split_at(e.valid_up_to());
+				output.push_str(&encode_path_segment(unsafe {
+					std::str::from_utf8_unchecked(valid)
+				}));
+
+				let invalid_len = e.error_len().unwrap_or(rest.len());
+				for b in &rest[..invalid_len] {
+					output.push_str(&format!("%{:02X}", b));
+				}
+
+				bytes = &rest[invalid_len..];
+			}
+		}
+	}
+}
+
+/// Percent-encode the given path component and push it to `output`.
+#[cfg(not(unix))]
+fn push_encoded(component: &OsStr, output: &mut String) -> Result<(), Error> {
+	let component = component.to_str().ok_or(Error::InvalidEncoding)?;
+	output.push_str(&encode_path_segment(component));
+	Ok(())
+}
+
+/// Build the path made of the given decoded segments, on the given host.
+#[cfg(unix)]
+fn build_file_path(host: Option<String>, segments: Vec<Vec<u8>>) -> Option<PathBuf> {
+	use std::os::unix::ffi::OsStrExt;
+
+	if host.is_some() {
+		return None;
+	}
+
+	let mut path = PathBuf::from("/");
+	for segment in &segments {
+		path.push(OsStr::from_bytes(segment));
+	}
+
+	Some(path)
+}
+
+/// Build the path made of the given decoded segments, on the given host.
+#[cfg(windows)]
+fn build_file_path(host: Option<String>, segments: Vec<Vec<u8>>) -> Option<PathBuf> {
+	let mut segments = segments.into_iter().map(String::from_utf8);
+	let mut path = match host {
+		Some(host) => format!(r"\\{}", host),
+		None => {
+			let drive = segments.next()?.ok()?;
+			match drive.as_bytes() {
+				[letter, b':'] | [letter, b'|'] if letter.is_ascii_alphabetic() => {
+					format!("{}:", *letter as char)
+				}
+				_ => return None,
+			}
+		}
+	};
+
+	let mut is_root = true;
+	for segment in segments {
+		let segment = segment.ok()?;
+		if segment.contains('\\') {
+			return None;
+		}
+
+		path.push('\\');
+		path.push_str(&segment);
+		is_root = false;
+	}
+
+	if is_root {
+		path.push('\\');
+	}
+
+	Some(PathBuf::from(path))
+}
+
+/// Build the path made of the given decoded segments, on the given host.
+#[cfg(not(any(unix, windows)))]
+fn build_file_path(host: Option<String>, segments: Vec<Vec<u8>>) -> Option<PathBuf> {
+	if host.is_some() {
+		return None;
+	}
+
+	let mut path = PathBuf::from("/");
+	for segment in segments {
+		path.push(String::from_utf8(segment).ok()?);
+	}
+
+	Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Error, Iri, IriBuf};
+	use std::path::Path;
+
+	#[cfg(unix)]
+	#[test]
+	fn unix_paths() {
+		let from = |path| IriBuf::from_file_path(Path::new(path));
+
+		assert_eq!(from("/").unwrap(), "file:///");
+		assert_eq!(from("/a/b/").unwrap(), "file:///a/b");
+		assert_eq!(from("/a/./b/../c").unwrap(), "file:///a/b/../c");
+		assert_eq!(from("/a?b#c%d é").unwrap(), "file:///a%3Fb%23c%25d%20é");
+		assert!(matches!(from("a/b"), Err(Error::InvalidPath)));
+
+		use std::ffi::OsStr;
+		use std::os::unix::ffi::OsStrExt;
+		let invalid = Path::new(OsStr::from_bytes(b"/a\xffb"));
+		let iri = IriBuf::from_file_path(invalid).unwrap();
+		assert_eq!(iri.as_str(), "file:///a%FFb");
+		assert_eq!(iri.to_file_path().unwrap(), invalid);
+
+		for path in &["/", "/a/b", "/a?b#c%d é/e", "/a\\b:c"] {
+			let iri = from(path).unwrap();
+			assert_eq!(iri.to_file_path().unwrap(), Path::new(path));
+		}
+		assert_eq!(from("/a/../e").unwrap().to_file_path(), None);
+
+		let to = |iri| Iri::new(iri).unwrap().to_file_path();
+		assert_eq!(to("file:/a/b%2Fc.txt"), None);
+		assert_eq!(to("FILE://localhost/a/b#f").unwrap(), Path::new("/a/b"));
+		assert_eq!(to("file://host/a"), None);
+	}
+
+	#[cfg(windows)]
+	#[test]
+	fn windows_paths() {
+		let from = |path| IriBuf::from_file_path(Path::new(path));
+		let to = |iri| Iri::new(iri).unwrap().to_file_path();
+
+		assert_eq!(from(r"C:\x").unwrap().as_str(), "file:///C:/x");
+		assert_eq!(from(r"C:\").unwrap().as_str(), "file:///C:/");
+		assert_eq!(
+			from(r"C:\a b\c#d").unwrap().as_str(),
+			"file:///C:/a%20b/c%23d"
+		);
+		assert_eq!(
+			from(r"\\server\share\x").unwrap().as_str(),
+			"file://server/share/x"
+		);
+		assert!(matches!(from(r"x\y"), Err(Error::InvalidPath)));
+		assert!(matches!(from(r"\\.\COM1"), Err(Error::InvalidPath)));
+
+		assert_eq!(to("file:///C:/x").unwrap(), Path::new(r"C:\x"));
+		assert_eq!(to("file:///C:/").unwrap(), Path::new(r"C:\"));
+		assert_eq!(to("file:///c:/a%20b").unwrap(), Path::new(r"c:\a b"));
+		assert_eq!(
+			to("file://server/share/x").unwrap(),
+			Path::new(r"\\server\share\x")
+		);
+		assert_eq!(to("file:///x"), None);
+		assert_eq!(to("file:///C:/a%5Cb"), None);
+
+		for path in &[r"C:\x", r"C:\a b\c#d", r"\\server\share\x"] {
+			let iri = from(path).unwrap();
+			assert_eq!(iri.to_file_path().unwrap(), Path::new(path));
+		}
+	}
+
+	#[test]
+	fn invalid_file_iris() {
+		let to = |iri| Iri::new(iri).unwrap().to_file_path();
+
+		assert_eq!(to("http://localhost/a"), None);
+		assert_eq!(to("file:///a?q"), None);
+		assert_eq!(to("file://u@localhost/a"), None);
+		assert_eq!(to("file://localhost:1/a"), None);
+		assert_eq!(to("file:a/b"), None);
+		assert_eq!(to("file:///a%00"), None);
+		assert_eq!(to("file:///a/./b"), None);
+		assert_eq!(to("file:///a/../b"), None);
+		assert_eq!(to("file:///srv/%2E%2E/etc"), None);
+		assert_eq!(to("file:///srv/%2e/etc"), None);
+	}
+}
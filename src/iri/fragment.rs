@@ -1,10 +1,11 @@
 use super::Error;
 use crate::parsing;
+use alloc::string::String;
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::{cmp, fmt};
 use pct_str::PctStr;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
 
 #[derive(Clone, Copy)]
 pub struct Fragment<'a> {
@@ -22,7 +23,7 @@ impl<'a> Fragment<'a> {
 	/// Get the underlying fragment slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(&self.data) }
+		unsafe { core::str::from_utf8_unchecked(&self.data) }
 	}
 
 	/// Get the underlying fragment slice as a percent-encoded string slice.
@@ -36,6 +37,22 @@ impl<'a> Fragment<'a> {
 	pub fn is_empty(&self) -> bool {
 		self.data.is_empty()
 	}
+
+	/// Percent-decode the fragment.
+	///
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+	#[inline]
+	pub fn decoded(&self) -> String {
+		crate::pct_decode_lossy(self.as_pct_str())
+	}
+
+	/// Checks if the percent-decoded fragment contains `needle`, without allocating.
+	///
+	/// See [`Query::contains_decoded`](crate::Query::contains_decoded).
+	#[inline]
+	pub fn contains_decoded(&self, needle: &str) -> bool {
+		crate::pct_contains(self.data, needle)
+	}
 }
 
 impl<'a> AsRef<[u8]> for Fragment<'a> {
@@ -1,11 +1,17 @@
 use super::Error;
 use crate::parsing;
+use alloc::string::{String, ToString};
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::{cmp, fmt};
 use pct_str::PctStr;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
 
+/// Authority host.
+///
+/// Hosts are case-insensitive: comparison, ordering and hashing ignore ASCII case,
+/// after decoding percent-encoded characters.
 #[derive(Clone, Copy)]
 pub struct Host<'a> {
 	/// The path slice.
@@ -22,7 +28,7 @@ impl<'a> Host<'a> {
 	/// Get the underlying host slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(&self.data) }
+		unsafe { core::str::from_utf8_unchecked(&self.data) }
 	}
 
 	/// Get the underlying host slice as a percent-encoded string slice.
@@ -36,6 +42,238 @@ impl<'a> Host<'a> {
 	pub fn is_empty(&self) -> bool {
 		self.data.is_empty()
 	}
+
+	/// Percent-decode the host.
+	///
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+	/// The case of the host is preserved.
+	#[inline]
+	pub fn decoded(&self) -> String {
+		crate::pct_decode_lossy(self.as_pct_str())
+	}
+
+	/// Returns the canonical form of the host.
+	///
+	/// IPv6 literals are rewritten in the canonical form of
+	/// [RFC 5952](https://tools.ietf.org/html/rfc5952#section-4): hexadecimal digits in
+	/// lowercase, leading zeros removed and the longest run of zero fields compressed as `::`,
+	/// with IPv4-mapped addresses in dotted notation.
+	/// The zone identifier, if any, is lowercased and its percent-encoded unreserved
+	/// characters are decoded, as in [`IriRef::canonical`](crate::IriRef::canonical).
+	/// IPv4 addresses and registered names are returned unchanged.
+	///
+	/// Hosts are compared, ordered and hashed by their canonical form.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use iref::Host;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let host = Host::try_from("[2001:DB8:0:0:0:0:0:1]")?;
+	/// assert_eq!(host.canonical(), "[2001:db8::1]");
+	/// assert_eq!(host, Host::try_from("[2001:db8::1]")?);
+	///
+	/// let host = Host::try_from("[0:0:0:0:0:FFFF:C000:0201%25ETH0]")?;
+	/// assert_eq!(host.canonical(), "[::ffff:192.0.2.1%25eth0]");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn canonical(&self) -> String {
+		match self.ipv6_literal() {
+			Some(_) => crate::reference::CanonicalHost(*self).to_string(),
+			None => self.as_str().to_string(),
+		}
+	}
+
+	/// Splits an IPv6 literal into its address, and the rest of the literal following the
+	/// address: the zone identifier, if any, and the closing bracket.
+	#[inline]
+	pub(crate) fn ipv6_literal(&self) -> Option<(Ipv6Addr, &'a str)> {
+		let str: &'a str = unsafe { core::str::from_utf8_unchecked(self.data) };
+
+		if str.starts_with('[') && str.ends_with(']') {
+			let end = str.find("%25").unwrap_or(str.len() - 1);
+			str[1..end].parse().ok().map(|addr| (addr, &str[end..]))
+		} else {
+			None
+		}
+	}
+
+	/// Decoded characters of the canonical host, with ASCII letters in lowercase.
+	/// This is what comparison, ordering and hashing are based upon.
+	#[inline]
+	fn lowercase_chars(&self) -> impl Iterator<Item = char> + 'a {
+		let (canonical, rest) = match self.ipv6_literal() {
+			Some((addr, rest)) => (Some(CanonicalIpv6::new(addr)), rest),
+			None => (None, unsafe { core::str::from_utf8_unchecked(self.data) }),
+		};
+
+		let pct_str: &'a PctStr = unsafe { PctStr::new_unchecked(rest) };
+		canonical
+			.into_iter()
+			.flat_map(CanonicalIpv6::into_chars)
+			.chain(pct_str.chars().map(|c| c.to_ascii_lowercase()))
+	}
+
+	/// Get the kind of host, parsing IP addresses.
+	///
+	/// The brackets around IPv6 literals are stripped before parsing,
+	/// and the zone identifier (following `%25`), if any, is returned separately.
+	/// Anything that is not an IP address is a registered name.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use std::net::Ipv6Addr;
+	/// # use iref::{Host, HostKind};
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let host = Host::try_from("[fe80::1%25eth0]")?;
+	/// match host.kind() {
+	///   HostKind::Ipv6(addr, zone) => {
+	///     assert_eq!(addr, "fe80::1".parse::<Ipv6Addr>().unwrap());
+	///     assert_eq!(zone.unwrap(), "eth0")
+	///   }
+	///   _ => panic!("not an IPv6 address")
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[inline]
+	pub fn kind(&self) -> HostKind<'a> {
+		let str: &'a str = unsafe { core::str::from_utf8_unchecked(self.data) };
+
+		if str.starts_with('[') && str.ends_with(']') {
+			let literal = &str[1..(str.len() - 1)];
+			let (addr, zone) = match literal.find("%25") {
+				Some(i) => (
+					&literal[..i],
+					Some(unsafe { PctStr::new_unchecked(&literal[(i + 3)..]) }),
+				),
+				None => (literal, None),
+			};
+
+			if let Ok(addr) = addr.parse() {
+				return HostKind::Ipv6(addr, zone);
+			}
+		} else if let Ok(addr) = str.parse() {
+			return HostKind::Ipv4(addr);
+		}
+
+		HostKind::RegName(unsafe { PctStr::new_unchecked(str) })
+	}
+}
+
+#[cfg(feature = "idna")]
+impl<'a> Host<'a> {
+	/// Convert the host to its ASCII form, using the IDNA `ToASCII` operation.
+	///
+	/// Registered names are percent-decoded then processed according to
+	/// [UTS #46](https://www.unicode.org/reports/tr46/), compatible with IDNA2008.
+	/// IP literals are returned unchanged.
+	/// Fails with [`Error::InvalidHost`] if the name is rejected by IDNA.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use iref::Host;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let host = Host::try_from("例え.jp")?;
+	/// assert_eq!(host.to_ascii()?, "xn--r8jz45g.jp");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn to_ascii(&self) -> Result<String, Error> {
+		match self.kind() {
+			HostKind::RegName(name) if !name.is_empty() => {
+				let name = decode_reg_name(name)?;
+				let ascii = idna::domain_to_ascii(&name).map_err(|_| Error::InvalidHost)?;
+				Host::try_from(ascii.as_str())?;
+				Ok(ascii)
+			}
+			_ => Ok(self.as_str().to_string()),
+		}
+	}
+
+	/// Convert the host to its Unicode form, using the IDNA `ToUnicode` operation.
+	///
+	/// This is the reverse of [`Host::to_ascii`].
+	/// Fails with [`Error::InvalidHost`] if the name is rejected by IDNA,
+	/// or if the result is not a valid host.
+	pub fn to_unicode(&self) -> Result<String, Error> {
+		match self.kind() {
+			HostKind::RegName(name) if !name.is_empty() => {
+				let name = decode_reg_name(name)?;
+				let (unicode, result) = idna::domain_to_unicode(&name);
+				result.map_err(|_| Error::InvalidHost)?;
+				Host::try_from(unicode.as_str())?;
+				Ok(unicode)
+			}
+			_ => Ok(self.as_str().to_string()),
+		}
+	}
+}
+
+/// Opening bracket of an IPv6 literal followed by the canonical form of its address,
+/// formatted without allocating.
+#[derive(Clone, Copy)]
+pub(crate) struct CanonicalIpv6 {
+	data: [u8; 40],
+	len: usize,
+}
+
+impl CanonicalIpv6 {
+	pub(crate) fn new(addr: Ipv6Addr) -> CanonicalIpv6 {
+		use fmt::Write;
+		let mut canonical = CanonicalIpv6 {
+			data: [0; 40],
+			len: 0,
+		};
+
+		// The `Display` implementation of `Ipv6Addr` follows RFC 5952.
+		write!(canonical, "[{}", addr).unwrap();
+		canonical
+	}
+
+	#[inline]
+	pub(crate) fn as_str(&self) -> &str {
+		unsafe { core::str::from_utf8_unchecked(&self.data[..self.len]) }
+	}
+
+	#[inline]
+	fn into_chars(self) -> impl Iterator<Item = char> {
+		(0..self.len).map(move |i| self.data[i] as char)
+	}
+}
+
+impl fmt::Write for CanonicalIpv6 {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let end = self.len + s.len();
+		self.data
+			.get_mut(self.len..end)
+			.ok_or(fmt::Error)?
+			.copy_from_slice(s.as_bytes());
+		self.len = end;
+		Ok(())
+	}
+}
+
+/// Percent-decode a registered name, which must be valid UTF-8.
+#[cfg(feature = "idna")]
+fn decode_reg_name(name: &PctStr) -> Result<String, Error> {
+	String::from_utf8(name.bytes().collect()).map_err(|_| Error::InvalidHost)
+}
+
+/// Kind of [`Host`], as defined in [RFC 3986 section 3.2.2](https://tools.ietf.org/html/rfc3986#section-3.2.2).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HostKind<'a> {
+	/// IPv4 address, such as `192.0.2.1`.
+	Ipv4(Ipv4Addr),
+
+	/// IPv6 address literal, such as `[2001:db8::1]`, with its optional zone identifier.
+	Ipv6(Ipv6Addr, Option<&'a PctStr>),
+
+	/// Registered name, such as `example.com`.
+	RegName(&'a PctStr),
 }
 
 impl<'a> AsRef<[u8]> for Host<'a> {
@@ -76,7 +314,7 @@ impl<'a> fmt::Debug for Host<'a> {
 impl<'a> cmp::PartialEq for Host<'a> {
 	#[inline]
 	fn eq(&self, other: &Host) -> bool {
-		self.as_pct_str() == other.as_pct_str()
+		self.lowercase_chars().eq(other.lowercase_chars())
 	}
 }
 
@@ -85,7 +323,7 @@ impl<'a> Eq for Host<'a> {}
 impl<'a> cmp::PartialEq<&'a str> for Host<'a> {
 	#[inline]
 	fn eq(&self, other: &&'a str) -> bool {
-		self.as_str() == *other
+		self.as_str().eq_ignore_ascii_case(other)
 	}
 }
 
@@ -99,13 +337,126 @@ impl<'a> PartialOrd for Host<'a> {
 impl<'a> Ord for Host<'a> {
 	#[inline]
 	fn cmp(&self, other: &Host<'a>) -> Ordering {
-		self.as_pct_str().cmp(other.as_pct_str())
+		self.lowercase_chars().cmp(other.lowercase_chars())
 	}
 }
 
 impl<'a> Hash for Host<'a> {
 	#[inline]
 	fn hash<H: Hasher>(&self, hasher: &mut H) {
-		self.as_pct_str().hash(hasher)
+		for c in self.lowercase_chars() {
+			c.hash(hasher)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn kind() {
+		let host = Host::try_from("192.0.2.1").unwrap();
+		assert_eq!(host.kind(), HostKind::Ipv4(Ipv4Addr::new(192, 0, 2, 1)));
+
+		let host = Host::try_from("[2001:db8::1]").unwrap();
+		let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+		assert_eq!(host.kind(), HostKind::Ipv6(addr, None));
+
+		let host = Host::try_from("[fe80::1%25en%301]").unwrap();
+		let addr: Ipv6Addr = "fe80::1".parse().unwrap();
+		let zone = PctStr::new("en%301").unwrap();
+		assert_eq!(host.kind(), HostKind::Ipv6(addr, Some(zone)));
+
+		let host = Host::try_from("example.com").unwrap();
+		let name = PctStr::new("example.com").unwrap();
+		assert_eq!(host.kind(), HostKind::RegName(name));
+
+		for name in &["192.0.2.256", "192.0.2.1a", "1.2.3.4.5", "01.2.3.4"] {
+			let host = Host::try_from(*name).unwrap();
+			assert!(matches!(host.kind(), HostKind::RegName(_)));
+		}
+
+		let host = Host::try_from("").unwrap();
+		assert!(matches!(host.kind(), HostKind::RegName(_)));
+
+		assert!(Host::try_from("[fe80::1%25]").is_err());
+		assert!(Host::try_from("[fe80::1%eth0]").is_err());
+	}
+
+	#[test]
+	fn canonical_ipv6() {
+		use std::collections::hash_map::DefaultHasher;
+
+		fn hash_of(host: &Host) -> u64 {
+			let mut hasher = DefaultHasher::new();
+			host.hash(&mut hasher);
+			hasher.finish()
+		}
+
+		let equivalent = [
+			("[2001:db8::1]", "[2001:db8::1]"),
+			("[2001:DB8::1]", "[2001:db8::1]"),
+			("[2001:0db8:0000:0000:0000:0000:0000:0001]", "[2001:db8::1]"),
+			("[2001:DB8:0:0:0:0:0:1]", "[2001:db8::1]"),
+			("[2001:db8:0::0:1]", "[2001:db8::1]"),
+			("[2001:0:0:1:0:0:0:1]", "[2001:0:0:1::1]"),
+			("[2001:db8:0:1:1:1:1:1]", "[2001:db8:0:1:1:1:1:1]"),
+			("[0:0:0:0:0:0:0:0]", "[::]"),
+			("[::FFFF:C000:201]", "[::ffff:192.0.2.1]"),
+			("[::ffff:192.0.2.1]", "[::ffff:192.0.2.1]"),
+			("[FE80::0:1%25eth0]", "[fe80::1%25eth0]"),
+			("[fe80::1%25ETH0]", "[fe80::1%25eth0]"),
+			("[fe80::1%25eth%2D0]", "[fe80::1%25eth-0]"),
+		];
+
+		for (host, canonical) in &equivalent {
+			let host = Host::try_from(*host).unwrap();
+			let canonical_host = Host::try_from(*canonical).unwrap();
+			assert_eq!(host.canonical(), *canonical);
+			assert_eq!(host, canonical_host);
+			assert_eq!(host.cmp(&canonical_host), Ordering::Equal);
+			assert_eq!(hash_of(&host), hash_of(&canonical_host));
+		}
+
+		let a = Host::try_from("[2001:db8::1]").unwrap();
+		let b = Host::try_from("[2001:db8::2]").unwrap();
+		assert_ne!(a, b);
+		assert_eq!(a.cmp(&b), Ordering::Less);
+		assert_ne!(
+			Host::try_from("[fe80::1%25eth0]").unwrap(),
+			Host::try_from("[fe80::1%25eth1]").unwrap()
+		);
+
+		for host in &["Example.com", "192.000.2.1", "192.0.2.1", "", "[::1"] {
+			if let Ok(host) = Host::try_from(*host) {
+				assert_eq!(host.canonical(), host.as_str());
+			}
+		}
+	}
+
+	#[cfg(feature = "idna")]
+	#[test]
+	fn idna() {
+		let challenges = [
+			("例え.jp", "xn--r8jz45g.jp", "例え.jp"),
+			("%E4%BE%8B%E3%81%88.jp", "xn--r8jz45g.jp", "例え.jp"),
+			("xn--r8jz45g.jp", "xn--r8jz45g.jp", "例え.jp"),
+			("EXAMPLE.com", "example.com", "example.com"),
+			("192.0.2.1", "192.0.2.1", "192.0.2.1"),
+			("[2001:db8::1]", "[2001:db8::1]", "[2001:db8::1]"),
+			("", "", ""),
+		];
+
+		for (host, ascii, unicode) in &challenges {
+			let host = Host::try_from(*host).unwrap();
+			assert_eq!(host.to_ascii().unwrap(), *ascii);
+			assert_eq!(host.to_unicode().unwrap(), *unicode);
+		}
+
+		for host in &["xn--a.com", "%FF.com", "a%20b.com"] {
+			let host = Host::try_from(*host).unwrap();
+			assert!(matches!(host.to_ascii(), Err(Error::InvalidHost)));
+		}
 	}
 }
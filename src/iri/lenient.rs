@@ -0,0 +1,253 @@
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+
+use crate::parsing::{self, Component, ParsedIriRef};
+use crate::{Error, IriBuf, IriRefBuf};
+
+/// Repair made by [`IriBuf::parse_lenient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Repair {
+	/// Byte offset of the repaired character in the input.
+	pub offset: usize,
+
+	/// Component of the resulting IRI containing the repaired character.
+	pub component: Component,
+
+	/// Repaired character, replaced by its percent-encoding.
+	pub char: char,
+}
+
+impl IriBuf {
+	/// Parse an IRI, percent-encoding the characters that are not allowed where they appear.
+	///
+	/// Returns the IRI with the list of the repairs made, in order.
+	/// The following repairs are attempted:
+	///  - characters that are not allowed in the user info, host, path, query or fragment,
+	///    such as spaces, control characters, `"`, `<`, `>`, `\`, `^`, `` ` ``, `{`, `|`,
+	///    `}`, or `[` and `]` outside of an IP literal, are percent-encoded;
+	///  - non-ASCII characters that RFC 3987 forbids in their component,
+	///    such as private use characters outside of the query, are percent-encoded;
+	///  - a `%` not followed by two hexadecimal digits is encoded as `%25`.
+	///
+	/// Invalid schemes, ports and IP literals are not repaired, nor is a `#` in the fragment,
+	/// and the input is not trimmed.
+	/// In these cases, and if the input has no scheme, the error that [`IriBuf::new`] would
+	/// return is returned.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{parsing::Component, IriBuf};
+	/// let (iri, repairs) = IriBuf::parse_lenient("http://example.org/a b?100%").unwrap();
+	/// assert_eq!(iri.as_str(), "http://example.org/a%20b?100%25");
+	/// assert_eq!(repairs[0].offset, 20);
+	/// assert_eq!(repairs[0].component, Component::Path);
+	/// assert_eq!(repairs[1].char, '%');
+	///
+	/// assert!(IriBuf::parse_lenient("http://example.org/#a#b").is_err());
+	/// ```
+	pub fn parse_lenient(input: &str) -> Result<(IriBuf, Vec<Repair>), Error> {
+		let mut r = Repairer {
+			input,
+			offset: 0,
+			buffer: String::with_capacity(input.len()),
+			repairs: Vec::new(),
+		};
+
+		let scheme_len = parsing::parse_scheme(input.as_bytes(), 0)?;
+		if input[scheme_len..].starts_with(':') {
+			r.keep(scheme_len + 1);
+		}
+
+		if input[r.offset..].starts_with("//") {
+			r.keep(r.offset + 2);
+			let authority_end = r.find(&['/', '?', '#'], input.len());
+
+			if let Some(userinfo_end) = r.find_before('@', authority_end) {
+				r.repair(userinfo_end, Component::UserInfo, parsing::is_userinfo_char);
+				r.keep(userinfo_end + 1);
+			}
+
+			// IP literals and ports are not repaired.
+			if input[r.offset..].starts_with('[') {
+				let host_end = r
+					.find_before(']', authority_end)
+					.map_or(authority_end, |i| i + 1);
+				r.keep(host_end);
+			} else {
+				let host_end = r.find_before(':', authority_end).unwrap_or(authority_end);
+				r.repair(host_end, Component::Host, parsing::is_reg_name_char);
+			}
+
+			r.keep(authority_end);
+		}
+
+		let path_end = r.find(&['?', '#'], input.len());
+		r.repair(path_end, Component::Path, parsing::is_path_char);
+
+		if input[r.offset..].starts_with('?') {
+			r.keep(r.offset + 1);
+			let query_end = r.find(&['#'], input.len());
+			r.repair(query_end, Component::Query, parsing::is_query_char);
+		}
+
+		if input[r.offset..].starts_with('#') {
+			r.keep(r.offset + 1);
+			r.repair(input.len(), Component::Fragment, parsing::is_fragment_char);
+		}
+
+		let Repairer {
+			buffer, repairs, ..
+		} = r;
+		let p = ParsedIriRef::new(&buffer)?;
+		let iri = IriBuf::try_from(IriRefBuf {
+			p,
+			data: buffer.into_bytes(),
+		})
+		.map_err(|_| Error::MissingScheme)?;
+
+		Ok((iri, repairs))
+	}
+}
+
+/// Copies an input to a buffer in a single pass, repairing its components.
+struct Repairer<'a> {
+	input: &'a str,
+
+	/// Offset of the next character to copy in the input.
+	offset: usize,
+
+	buffer: String,
+
+	repairs: Vec<Repair>,
+}
+
+impl<'a> Repairer<'a> {
+	/// Offset of the first of the given delimiters after the current offset, or `default`.
+	fn find(&self, delimiters: &[char], default: usize) -> usize {
+		self.input[self.offset..]
+			.find(delimiters)
+			.map_or(default, |i| self.offset + i)
+	}
+
+	/// Offset of the first `delimiter` between the current offset and `end`, if any.
+	fn find_before(&self, delimiter: char, end: usize) -> Option<usize> {
+		self.input[self.offset..end]
+			.find(delimiter)
+			.map(|i| self.offset + i)
+	}
+
+	/// Copy the input up to `end` as is.
+	fn keep(&mut self, end: usize) {
+		self.buffer.push_str(&self.input[self.offset..end]);
+		self.offset = end;
+	}
+
+	/// Copy the input up to `end`, percent-encoding the characters not allowed by
+	/// `is_allowed` and the `%` not followed by two hexadecimal digits.
+	///
+	/// A `#` is never repaired.
+	fn repair(&mut self, end: usize, component: Component, is_allowed: fn(char) -> bool) {
+		for c in self.input[self.offset..end].chars() {
+			let valid = match c {
+				'%' => {
+					let digits = self.input.as_bytes()[(self.offset + 1)..end].iter().take(2);
+					digits.filter(|b| b.is_ascii_hexdigit()).count() == 2
+				}
+				'#' => true,
+				c => is_allowed(c),
+			};
+
+			if valid {
+				self.buffer.push(c);
+			} else {
+				crate::pct_encode_char(c, &mut self.buffer);
+				self.repairs.push(Repair {
+					offset: self.offset,
+					component,
+					char: c,
+				});
+			}
+
+			self.offset += c.len_utf8();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Repair;
+	use crate::{parsing::Component, Error, IriBuf};
+
+	#[test]
+	fn parse_lenient() {
+		let (iri, repairs) = IriBuf::parse_lenient("http://u s@a b/c|d\u{E000}?%zz#f^").unwrap();
+		assert_eq!(iri.as_str(), "http://u%20s@a%20b/c%7Cd%EE%80%80?%25zz#f%5E");
+		assert_eq!(
+			repairs,
+			[
+				Repair {
+					offset: 8,
+					component: Component::UserInfo,
+					char: ' '
+				},
+				Repair {
+					offset: 12,
+					component: Component::Host,
+					char: ' '
+				},
+				Repair {
+					offset: 16,
+					component: Component::Path,
+					char: '|'
+				},
+				Repair {
+					offset: 18,
+					component: Component::Path,
+					char: '\u{E000}'
+				},
+				Repair {
+					offset: 22,
+					component: Component::Query,
+					char: '%'
+				},
+				Repair {
+					offset: 27,
+					component: Component::Fragment,
+					char: '^'
+				},
+			]
+		);
+
+		let (iri, repairs) = IriBuf::parse_lenient("https://example.org/é?q#f").unwrap();
+		assert_eq!(iri.as_str(), "https://example.org/é?q#f");
+		assert!(repairs.is_empty());
+
+		let (iri, _) = IriBuf::parse_lenient("http://a/100%").unwrap();
+		assert_eq!(iri.as_str(), "http://a/100%25");
+		let (iri, _) = IriBuf::parse_lenient("http://a/%4\t").unwrap();
+		assert_eq!(iri.as_str(), "http://a/%254%09");
+		let (iri, _) = IriBuf::parse_lenient("http://a@b@c]:80/[").unwrap();
+		assert_eq!(iri.as_str(), "http://a@b%40c%5D:80/%5B");
+
+		let long = "a b".repeat(10_000);
+		let (iri, repairs) = IriBuf::parse_lenient(&format!("http://h/{}", long)).unwrap();
+		assert_eq!(iri.path().len(), 1 + long.len() + 2 * repairs.len());
+		assert_eq!(repairs.len(), 10_000);
+	}
+
+	#[test]
+	fn parse_lenient_invalid() {
+		let invalid = ["http://a/#b#c", "http://[::1/", "http://a:8x/", "1http://a"];
+
+		for input in &invalid {
+			let e = IriBuf::parse_lenient(input).unwrap_err();
+			let expected = IriBuf::new(input).unwrap_err();
+			assert_eq!(e.to_string(), expected.to_string(), "{}", input);
+		}
+
+		for input in &["ht tp://a", "/a b"] {
+			let e = IriBuf::parse_lenient(input).unwrap_err();
+			assert!(matches!(e, Error::MissingScheme));
+		}
+	}
+}
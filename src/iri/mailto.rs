@@ -0,0 +1,150 @@
+use alloc::borrow::Cow;
+use core::str::Split;
+
+use crate::{Iri, IriBuf, Query, QueryPairs};
+
+/// View of a `mailto:` URI, as defined by [RFC 6068](https://tools.ietf.org/html/rfc6068).
+///
+/// Returned by [`Iri::as_mailto`].
+/// A `mailto:` URI is of the form `mailto:<addresses>?<headers>`,
+/// where the addresses are separated by `,` and the headers are `name=value` pairs
+/// separated by `&`, such as `subject`, `body` or `cc`.
+///
+/// # Example
+/// ```
+/// # use iref::Iri;
+/// let iri = Iri::new("mailto:alice@example.org,bob@example.org?subject=Hi&body=There").unwrap();
+/// let mailto = iri.as_mailto().unwrap();
+///
+/// let to: Vec<_> = mailto.to().collect();
+/// assert_eq!(to, ["alice@example.org", "bob@example.org"]);
+/// assert_eq!(mailto.header("subject").unwrap(), "Hi");
+/// assert_eq!(mailto.header("body").unwrap(), "There");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Mailto<'a> {
+	to: &'a str,
+	headers: &'a [u8],
+}
+
+impl<'a> Mailto<'a> {
+	/// Iterate over the addresses of the path.
+	///
+	/// Addresses are returned as they appear in the IRI, still percent-encoded.
+	/// Empty addresses are skipped, so `mailto:?to=alice@example.org` has no address in its
+	/// path: its recipient is given as a header.
+	#[inline]
+	pub fn to(&self) -> MailtoAddresses<'a> {
+		MailtoAddresses {
+			split: self.to.split(','),
+		}
+	}
+
+	/// Iterate over the `name=value` headers of the query.
+	///
+	/// Names and values are percent-decoded, as with [`Query::pairs`].
+	#[inline]
+	pub fn headers(&self) -> QueryPairs<'a> {
+		Query { data: self.headers }.pairs()
+	}
+
+	/// Returns the value of the first header with the given name, ignoring ASCII case.
+	///
+	/// A header without `=` has an empty value.
+	#[inline]
+	pub fn header(&self, name: &str) -> Option<Cow<'a, str>> {
+		self.headers()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.unwrap_or_default())
+	}
+}
+
+/// Iterator over the addresses of a [`Mailto`].
+///
+/// See [`Mailto::to`].
+#[derive(Clone)]
+pub struct MailtoAddresses<'a> {
+	split: Split<'a, char>,
+}
+
+impl<'a> Iterator for MailtoAddresses<'a> {
+	type Item = &'a str;
+
+	#[inline]
+	fn next(&mut self) -> Option<&'a str> {
+		self.split.find(|address| !address.is_empty())
+	}
+}
+
+impl<'a> Iri<'a> {
+	/// Returns a view of this IRI as a `mailto:` URI, if it has the `mailto` scheme.
+	#[inline]
+	pub fn as_mailto(&self) -> Option<Mailto<'a>> {
+		if self.scheme() != "mailto" {
+			return None;
+		}
+
+		let data: &'a [u8] = self.0.data;
+		let path_offset = self.p.path_offset();
+		let to = &data[path_offset..(path_offset + self.p.path_len)];
+		let headers = match self.p.query_len {
+			Some(len) => &data[self.p.query_offset()..(self.p.query_offset() + len)],
+			None => &[],
+		};
+
+		Some(Mailto {
+			to: unsafe { core::str::from_utf8_unchecked(to) },
+			headers,
+		})
+	}
+}
+
+impl IriBuf {
+	/// Returns a view of this IRI as a `mailto:` URI, if it has the `mailto` scheme.
+	///
+	/// See [`Iri::as_mailto`].
+	#[inline]
+	pub fn as_mailto(&self) -> Option<Mailto<'_>> {
+		self.as_iri().as_mailto()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Iri;
+
+	#[test]
+	fn recipients() {
+		let iri = Iri::new("mailto:a@example.org,b@example.org,,c%2Bd@example.org").unwrap();
+		let mailto = iri.as_mailto().unwrap();
+		let to: Vec<_> = mailto.to().collect();
+		assert_eq!(to, ["a@example.org", "b@example.org", "c%2Bd@example.org"]);
+		assert_eq!(mailto.headers().count(), 0);
+
+		let iri = Iri::new("MAILTO:?to=a@example.org").unwrap();
+		let mailto = iri.as_mailto().unwrap();
+		assert_eq!(mailto.to().count(), 0);
+		assert_eq!(mailto.header("to").unwrap(), "a@example.org");
+
+		assert!(Iri::new("http://a@example.org")
+			.unwrap()
+			.as_mailto()
+			.is_none());
+	}
+
+	#[test]
+	fn headers() {
+		let iri = Iri::new("mailto:a@example.org?subject=Hi&body=There&Cc=b@example.org").unwrap();
+		let mailto = iri.as_mailto().unwrap();
+		let headers: Vec<_> = mailto.headers().collect();
+		assert_eq!(headers[0], ("subject".into(), Some("Hi".into())));
+		assert_eq!(headers[1], ("body".into(), Some("There".into())));
+		assert_eq!(mailto.header("cc").unwrap(), "b@example.org");
+		assert!(mailto.header("bcc").is_none());
+
+		let iri = Iri::new("mailto:a@example.org?subject=Hello%20there&x#f").unwrap();
+		let mailto = iri.as_mailto().unwrap();
+		assert_eq!(mailto.header("subject").unwrap(), "Hello there");
+		assert_eq!(mailto.header("x").unwrap(), "");
+	}
+}
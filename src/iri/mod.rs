@@ -1,38 +1,70 @@
+mod ancestors;
 mod authority;
+mod bidi;
 mod buffer;
+mod builder;
+mod data_uri;
+#[cfg(feature = "std")]
+mod file_path;
 mod fragment;
 mod host;
+mod lenient;
+mod mailto;
+mod origin;
 mod path;
 mod port;
 mod query;
+mod resolver;
 mod scheme;
 mod segment;
+#[cfg(feature = "bytes")]
+mod shared;
+mod urn;
 mod userinfo;
 
+use crate::parsing::ParsedIriRef;
 use crate::{IriRef, IriRefBuf};
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::error::Error as StdError;
-use std::fmt;
-use std::hash::{Hash, Hasher};
-use std::ops::Deref;
+use alloc::string::String;
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::error::Error as StdError;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
 
+pub use self::ancestors::*;
 pub use self::authority::*;
+pub use self::bidi::*;
 pub use self::buffer::*;
+pub use self::builder::*;
+pub use self::data_uri::*;
 pub use self::fragment::*;
 pub use self::host::*;
+pub use self::lenient::*;
+pub use self::mailto::*;
+pub use self::origin::*;
 pub use self::path::*;
 pub use self::port::*;
 pub use self::query::*;
+pub use self::resolver::*;
 pub use self::scheme::*;
 pub use self::segment::*;
+#[cfg(feature = "bytes")]
+pub use self::shared::*;
+pub use self::urn::*;
 pub use self::userinfo::*;
 
 /// Parsing errors.
 ///
 /// These are the different errors raised when some part of an IRI or IRI reference has an
 /// invalid syntax or encoding.
+/// Use [`ParsedIriRef::parse_detailed`] to also get the byte offset of the error,
+/// in a [`ParseError`](crate::parsing::ParseError) converting into this type.
+///
+/// New variants may be added in the future, so matching on this type requires a
+/// catch-all arm.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error {
 	/// The input data is not a valid UTF-8 encoded string.
 	InvalidEncoding,
@@ -47,6 +79,10 @@ pub enum Error {
 	/// or when an IRI is parsed with no scheme.
 	MissingScheme,
 
+	/// Occurs when a component of the [`Authority`] is set on an IRI reference that has no
+	/// authority, such as `mailto:x`.
+	MissingAuthority,
+
 	/// Occurs when the parsed [`Scheme`] is not syntactically valid.
 	/// Note that even in an IRI, only ASCII letters, digit and symbols `+`, `-` and `.` are
 	/// allowed.
@@ -81,6 +117,13 @@ pub enum Error {
 
 	/// Occurs when a [`Fragment`] part is not syntactically valid.
 	InvalidFragment,
+
+	/// Occurs when an absolute IRI is expected but a [`Fragment`] is present.
+	UnexpectedFragment,
+
+	/// Occurs when a path [`Segment`] is accessed with an index greater than the number of
+	/// segments of the [`Path`].
+	InvalidSegmentIndex,
 }
 
 impl fmt::Display for Error {
@@ -89,6 +132,7 @@ impl fmt::Display for Error {
 			Error::InvalidEncoding => "Invalid encoding",
 			Error::InvalidPercentEncoding => "Invalid percent encoding",
 			Error::MissingScheme => "Missing scheme",
+			Error::MissingAuthority => "Missing authority",
 			Error::InvalidScheme => "Invalid scheme",
 			Error::InvalidAuthority => "Invalid authority",
 			Error::InvalidUserInfo => "Invalid user info",
@@ -98,12 +142,42 @@ impl fmt::Display for Error {
 			Error::InvalidPath => "Invalid path",
 			Error::InvalidQuery => "Invalid query",
 			Error::InvalidFragment => "Invalid fragment",
+			Error::UnexpectedFragment => "Unexpected fragment",
+			Error::InvalidSegmentIndex => "Invalid segment index",
 		})
 	}
 }
 
 impl StdError for Error {}
 
+/// Build an [`Iri`] from a string literal, validated at compile time.
+///
+/// Fails to compile if the literal is not a valid IRI.
+/// Use [`const_validate`](crate::const_validate) to validate a string constant.
+///
+/// # Example
+/// ```
+/// # use iref::{iri, Iri};
+/// const HOME: Iri<'static> = iri!("https://example.org/");
+/// assert_eq!(HOME.authority().unwrap().host(), "example.org");
+///
+/// let iri = iri!("https://example.org/a?q#f");
+/// assert_eq!(iri.path(), "/a");
+/// ```
+///
+/// Invalid literals are rejected:
+/// ```compile_fail
+/// # use iref::iri;
+/// let iri = iri!("https://example.org/a b");
+/// ```
+#[macro_export]
+macro_rules! iri {
+	($iri:literal) => {{
+		const IRI: $crate::Iri<'static> = $crate::Iri::from_literal($iri);
+		IRI
+	}};
+}
+
 /// IRI slice.
 ///
 /// Wrapper around a borrowed bytes slice representing an IRI.
@@ -111,6 +185,9 @@ impl StdError for Error {}
 /// All methods of [`IriRef`] are available from this type, however the [`scheme`](Iri::scheme) method
 /// is redefined to always return some scheme.
 ///
+/// Like `&str`, this type is `Copy`: it can be passed by value as many times as needed,
+/// for instance as the base IRI of several resolutions.
+///
 /// ## Example
 ///
 /// ```rust
@@ -146,6 +223,49 @@ impl<'a> Iri<'a> {
 		}
 	}
 
+	/// Create a new absolute IRI slice from a bytes slice.
+	///
+	/// Same as [`new`](Iri::new), but also fails with [`Error::UnexpectedFragment`] if the
+	/// IRI has a fragment, even an empty one.
+	/// See [`IriRef::is_absolute_uri`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::{Error, Iri};
+	/// assert!(Iri::new_absolute("http://h/p?q").is_ok());
+	/// assert!(matches!(Iri::new_absolute("http://h/p#f"), Err(Error::UnexpectedFragment)));
+	/// ```
+	#[inline]
+	pub fn new_absolute<S: AsRef<[u8]> + ?Sized>(buffer: &'a S) -> Result<Iri<'a>, Error> {
+		let iri = Iri::new(buffer)?;
+		if iri.is_absolute_uri() {
+			Ok(iri)
+		} else {
+			Err(Error::UnexpectedFragment)
+		}
+	}
+
+	/// Create a new IRI slice from raw bytes, such as read from the network.
+	///
+	/// The IRI grammar and the UTF-8 encoding are checked in a single pass over the bytes,
+	/// so there is no need to call [`core::str::from_utf8`] beforehand.
+	/// This fails with [`Error::InvalidEncoding`] if the bytes are not valid UTF-8.
+	/// On success, the IRI borrows the bytes: this allocates nothing.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Error, Iri};
+	/// let iri = Iri::from_utf8(b"http://example.org/caf\xC3\xA9").unwrap();
+	/// assert_eq!(iri.path(), "/café");
+	///
+	/// assert!(matches!(Iri::from_utf8(b"http://example.org/\xC3"), Err(Error::InvalidEncoding)));
+	/// ```
+	#[inline]
+	pub fn from_utf8(bytes: &'a [u8]) -> Result<Iri<'a>, Error> {
+		Self::new(bytes)
+	}
+
 	/// Create a new IRI from a string.
 	///
 	/// This replaces a [`std::str::FromStr`] implementation as the trait is
@@ -166,6 +286,22 @@ impl<'a> Iri<'a> {
 		Iri(iri_ref)
 	}
 
+	/// Build an IRI from a string, in a constant context.
+	///
+	/// Used by the [`iri!`](crate::iri) macro.
+	/// Panics if the string is not a valid IRI, which is a compilation error in a
+	/// constant context.
+	#[doc(hidden)]
+	pub const fn from_literal(s: &'a str) -> Iri<'a> {
+		match crate::parsing::validate(s.as_bytes()) {
+			Some(p) if p.scheme_len.is_some() => Iri(IriRef {
+				p,
+				data: s.as_bytes(),
+			}),
+			_ => panic!("invalid IRI literal"),
+		}
+	}
+
 	/// Get an [`IriRef`] out of this IRI.
 	///
 	/// An IRI is always a valid IRI-reference.
@@ -174,6 +310,12 @@ impl<'a> Iri<'a> {
 		self.0
 	}
 
+	/// Returns a reference to the byte representation of the IRI.
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] {
+		self.0.as_bytes()
+	}
+
 	/// Get the scheme of the IRI.
 	///
 	/// Contrarily to [`IriRef`], the scheme of an IRI is always defined.
@@ -181,6 +323,335 @@ impl<'a> Iri<'a> {
 	pub fn scheme(&self) -> Scheme {
 		self.0.scheme().unwrap()
 	}
+
+	/// Get the scheme of the IRI as a string slice.
+	///
+	/// See [`IriRef::scheme_str`].
+	#[inline]
+	pub fn scheme_str(&self) -> &'a str {
+		self.0.scheme_str().unwrap()
+	}
+
+	/// Return a copy of this IRI with the given scheme.
+	#[inline]
+	pub fn with_scheme(&self, scheme: &str) -> Result<IriBuf, Error> {
+		let scheme = Scheme::try_from(scheme)?;
+		let mut iri = IriBuf::from(*self);
+		iri.set_scheme(scheme);
+		Ok(iri)
+	}
+
+	/// Return a copy of this IRI with the given authority, or without authority.
+	///
+	/// An IRI with an authority must have an empty or absolute path:
+	/// adding an authority to an IRI with a relative path fails with [`Error::InvalidPath`].
+	pub fn with_authority(&self, authority: Option<&str>) -> Result<IriBuf, Error> {
+		let authority = authority.map(Authority::try_from).transpose()?;
+		let path = self.path();
+		if authority.is_some() && !path.is_empty() && !path.is_absolute() {
+			return Err(Error::InvalidPath);
+		}
+
+		let mut iri = IriBuf::from(*self);
+		iri.set_authority(authority);
+		Ok(iri)
+	}
+
+	/// Return a copy of this IRI with the given path.
+	///
+	/// If the IRI has an authority, the path must be empty or absolute.
+	/// Otherwise, it must not start with `//`.
+	/// This fails with [`Error::InvalidPath`] if not.
+	pub fn with_path(&self, path: &str) -> Result<IriBuf, Error> {
+		let path = Path::try_from(path)?;
+		let valid = if self.authority().is_some() {
+			path.is_empty() || path.is_absolute()
+		} else {
+			!path.as_bytes().starts_with(b"//")
+		};
+
+		if !valid {
+			return Err(Error::InvalidPath);
+		}
+
+		let mut iri = IriBuf::from(*self);
+		iri.set_path(path);
+		Ok(iri)
+	}
+
+	/// Return a copy of this IRI with the given query, or without query.
+	#[inline]
+	pub fn with_query(&self, query: Option<&str>) -> Result<IriBuf, Error> {
+		let query = query.map(Query::try_from).transpose()?;
+		let mut iri = IriBuf::from(*self);
+		iri.set_query(query);
+		Ok(iri)
+	}
+
+	/// Return a copy of this IRI with the given fragment, or without fragment.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let base = Iri::new("https://example.org/book#preface")?;
+	/// let chapters: Vec<_> = ["chapter1", "chapter2"]
+	///     .iter()
+	///     .map(|f| base.with_fragment(Some(f)))
+	///     .collect::<Result<_, _>>()?;
+	/// assert_eq!(chapters[1], "https://example.org/book#chapter2");
+	/// assert_eq!(base, "https://example.org/book#preface");
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[inline]
+	pub fn with_fragment(&self, fragment: Option<&str>) -> Result<IriBuf, Error> {
+		let fragment = fragment.map(Fragment::try_from).transpose()?;
+		let mut iri = IriBuf::from(*self);
+		iri.set_fragment(fragment);
+		Ok(iri)
+	}
+
+	/// Resolve the given IRI reference against this base IRI.
+	///
+	/// This is [`IriRef::resolved`] with its operands the other way around,
+	/// following the same algorithm (see [`IriRefBuf::resolve`]).
+	/// The fragment of the base is ignored.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Iri, IriRef};
+	/// let base = Iri::new("http://a/b/c/d;p?q").unwrap();
+	/// let iri = base.resolve_ref(IriRef::new("../g").unwrap());
+	/// assert_eq!(iri, "http://a/b/g");
+	/// ```
+	#[inline]
+	pub fn resolve_ref<'b, R: Into<IriRef<'b>>>(&self, reference: R) -> IriBuf {
+		reference.into().resolved(*self)
+	}
+
+	/// Create a resolver of IRI references against this IRI, used as base.
+	///
+	/// Resolving many references against the same base with the returned [`Resolver`]
+	/// is faster than calling [`IriRef::resolved`] for each of them.
+	#[inline]
+	pub fn resolver(&self) -> Resolver<'a> {
+		Resolver::new(*self)
+	}
+
+	/// Strip the given base IRI from the start of this IRI.
+	///
+	/// Returns the remaining relative reference if this IRI has the same scheme and authority
+	/// as `base`, and `base`'s path is an ancestor of (or equal to) this IRI's path,
+	/// ending at a segment boundary.
+	/// Paths are compared byte for byte and the query and fragment of `base` are ignored.
+	/// Returns `None` otherwise, or if the remainder cannot be used as a relative reference
+	/// as it is, because its path starts with a `/` or its first segment contains a `:`.
+	///
+	/// Unlike [`IriRef::relative_to`], no `../` segment is ever added.
+	/// If `base`'s path ends with a `/`, resolving the remainder against `base` gives back
+	/// this IRI.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let iri = Iri::new("https://example.org/static/css/main.css?v=2").unwrap();
+	///
+	/// let base = Iri::new("https://example.org/static/").unwrap();
+	/// assert_eq!(iri.strip_prefix(base).unwrap(), "css/main.css?v=2");
+	///
+	/// let base = Iri::new("https://example.org/static").unwrap();
+	/// assert_eq!(iri.strip_prefix(base).unwrap(), "css/main.css?v=2");
+	///
+	/// let base = Iri::new("https://example.org/stat").unwrap();
+	/// assert!(iri.strip_prefix(base).is_none());
+	/// ```
+	pub fn strip_prefix<'b, Base: Into<Iri<'b>>>(&self, base: Base) -> Option<IriRef<'a>> {
+		let base = base.into();
+		if self.scheme() != base.scheme() || self.authority() != base.authority() {
+			return None;
+		}
+
+		let path = self.path();
+		let base_path = base.path();
+		let (path, base_path) = (path.as_bytes(), base_path.as_bytes());
+		if !path.starts_with(base_path) {
+			return None;
+		}
+
+		let mut start = base_path.len();
+		if !base_path.ends_with(b"/") {
+			match path.get(start) {
+				Some(b'/') => start += 1,
+				Some(_) if !base_path.is_empty() => return None,
+				_ => (),
+			}
+		}
+
+		let rest = &path[start..];
+		let first_segment = rest.split(|b| *b == b'/').next().unwrap_or(&[]);
+		if rest.starts_with(b"/") || first_segment.contains(&b':') {
+			return None;
+		}
+
+		let p = ParsedIriRef {
+			scheme_len: None,
+			authority: None,
+			path_len: rest.len(),
+			..self.p
+		};
+
+		Some(IriRef {
+			p,
+			data: &self.data[(self.p.path_offset() + start)..],
+		})
+	}
+
+	/// Checks if this IRI has the given origin, made of a scheme, a host and a port.
+	///
+	/// Unlike a string prefix check, this compares whole components, so that
+	/// `https://trusted.example.evil.com/` does not match the `trusted.example` host.
+	/// The scheme is compared ignoring case, and the host as [`Host`]s are, ignoring case
+	/// and percent-encoding. With the `idna` feature, hosts having the same ASCII form
+	/// (such as `例え.jp` and `xn--r8jz45g.jp`) also match.
+	/// A `None` port, like a missing or empty port in the IRI, stands for the default port of
+	/// the `http`, `https` and `ftp` schemes, and for no port otherwise.
+	/// The user info, path, query and fragment are ignored.
+	///
+	/// Returns `false` if the IRI has no authority or if `host` is not a valid host.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let iri = Iri::new("HTTPS://Trusted.Example/a?b").unwrap();
+	/// assert!(iri.origin_matches("https", "trusted.example", None));
+	/// assert!(iri.origin_matches("https", "trusted.example", Some(443)));
+	/// assert!(!iri.origin_matches("https", "trusted.example", Some(8443)));
+	///
+	/// let iri = Iri::new("https://trusted.example.evil.com/a").unwrap();
+	/// assert!(!iri.origin_matches("https", "trusted.example", None));
+	/// ```
+	pub fn origin_matches(&self, scheme: &str, host: &str, port: Option<u16>) -> bool {
+		if !self.scheme_str().eq_ignore_ascii_case(scheme) {
+			return false;
+		}
+
+		let authority = match self.authority() {
+			Some(authority) => authority,
+			None => return false,
+		};
+
+		match Host::try_from(host) {
+			Ok(host) if host_matches(authority.host(), host) => (),
+			_ => return false,
+		}
+
+		let default_port = self.scheme().default_port();
+		let actual_port = match authority.port() {
+			Some(actual) if !actual.is_empty() => match actual.as_u16() {
+				Some(actual) => Some(actual),
+				None => return false,
+			},
+			_ => default_port,
+		};
+
+		actual_port == port.or(default_port)
+	}
+
+	/// Return the deepest base IRI shared by this IRI and `other`.
+	///
+	/// Returns `None` if they do not have the same scheme and authority.
+	/// Otherwise, the path of the result is the longest common prefix of both paths ending
+	/// with a `/`, compared byte for byte, and it has no query nor fragment.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let a = Iri::new("http://h/a/b/c").unwrap();
+	/// let b = Iri::new("http://h/a/b/d?q").unwrap();
+	/// assert_eq!(a.common_base(b).unwrap(), "http://h/a/b/");
+	///
+	/// let b = Iri::new("http://h/a/bc").unwrap();
+	/// assert_eq!(a.common_base(b).unwrap(), "http://h/a/");
+	///
+	/// let b = Iri::new("http://example.org/a/b/c").unwrap();
+	/// assert!(a.common_base(b).is_none());
+	/// ```
+	pub fn common_base<'b, Other: Into<Iri<'b>>>(&self, other: Other) -> Option<IriBuf> {
+		let other = other.into();
+		if self.scheme() != other.scheme() || self.authority() != other.authority() {
+			return None;
+		}
+
+		let path = self.path();
+		let other_path = other.path();
+		let common = path
+			.as_bytes()
+			.iter()
+			.zip(other_path.as_bytes())
+			.take_while(|(a, b)| a == b)
+			.count();
+		let path_len = path.as_bytes()[..common]
+			.iter()
+			.rposition(|b| *b == b'/')
+			.map_or(0, |i| i + 1);
+
+		let p = ParsedIriRef {
+			path_len,
+			query_len: None,
+			fragment_len: None,
+			..self.p
+		};
+
+		Some(IriBuf(IriRefBuf {
+			p,
+			data: self.data[..(self.p.path_offset() + path_len)].to_vec(),
+		}))
+	}
+
+	/// Return a copy of this IRI without its fragment.
+	///
+	/// See also [`eq_ignoring_fragment`](IriRef::eq_ignoring_fragment)
+	/// to compare IRIs regardless of their fragment without allocating.
+	#[inline]
+	pub fn without_fragment(&self) -> IriBuf {
+		let mut iri = IriBuf::from(*self);
+		iri.set_fragment(None);
+		iri
+	}
+
+	/// Return the syntax-normalized form of this IRI.
+	///
+	/// See [`IriRef::normalized`].
+	#[inline]
+	pub fn normalized(&self) -> IriBuf {
+		IriBuf(self.0.normalized())
+	}
+
+	/// Return the canonical form of this IRI.
+	///
+	/// Two IRIs are equal if and only if their canonical forms are the same byte strings.
+	/// See [`IriRef::canonical`].
+	#[inline]
+	pub fn canonical(&self) -> IriBuf {
+		IriBuf(self.0.canonical())
+	}
+
+	/// Map this IRI to a URI, percent-encoding every non-ASCII character.
+	///
+	/// See [`IriRef::to_uri`].
+	#[inline]
+	pub fn to_uri(&self) -> IriBuf {
+		IriBuf(self.0.to_uri())
+	}
+
+	/// Map this URI to an IRI, decoding percent-encoded non-ASCII characters where allowed.
+	///
+	/// See [`IriRef::to_iri`].
+	#[inline]
+	pub fn to_iri(&self) -> Result<IriBuf, Error> {
+		Ok(IriBuf(self.0.to_iri()?))
+	}
 }
 
 impl<'a> Deref for Iri<'a> {
@@ -199,10 +670,27 @@ impl<'a> fmt::Display for Iri<'a> {
 	}
 }
 
+impl<'a> Iri<'a> {
+	/// Write the components of this IRI as a struct with the given name,
+	/// as in `Iri { scheme: "http", authority: Some("a"), path: "/b", .. }`.
+	pub(crate) fn fmt_components(&self, name: &str, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct(name)
+			.field("scheme", &self.scheme_str())
+			.field("authority", &self.authority())
+			.field("path", &self.path_str())
+			.field("query", &self.query_str())
+			.field("fragment", &self.fragment_str())
+			.finish()
+	}
+}
+
+/// Components of the IRI, absent ones being `None`.
+///
+/// Use the [`Display`](fmt::Display) implementation for the plain string form.
 impl<'a> fmt::Debug for Iri<'a> {
 	#[inline]
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		self.as_iri_ref().fmt(f)
+		self.fmt_components("Iri", f)
 	}
 }
 
@@ -215,9 +703,12 @@ impl<'a> PartialEq for Iri<'a> {
 
 impl<'a> Eq for Iri<'a> {}
 
-impl<'a> PartialEq<IriRef<'a>> for Iri<'a> {
+/// Normalizing comparison with an IRI reference.
+///
+/// An IRI reference without scheme is never equal to an IRI.
+impl<'a, 'b> PartialEq<IriRef<'b>> for Iri<'a> {
 	#[inline]
-	fn eq(&self, other: &IriRef<'a>) -> bool {
+	fn eq(&self, other: &IriRef<'b>) -> bool {
 		self.as_iri_ref() == *other
 	}
 }
@@ -236,10 +727,49 @@ impl<'a> PartialEq<IriBuf> for Iri<'a> {
 	}
 }
 
-impl<'a> PartialEq<&'a str> for Iri<'a> {
+/// Normalizing comparison with a string.
+///
+/// See [`IriRef`]'s implementation, and compare [`as_str`](Iri::as_str)
+/// for a byte-exact comparison.
+impl<'a> PartialEq<str> for Iri<'a> {
+	#[inline]
+	fn eq(&self, other: &str) -> bool {
+		self.as_iri_ref() == *other
+	}
+}
+
+impl<'a> PartialEq<&str> for Iri<'a> {
 	#[inline]
-	fn eq(&self, other: &&'a str) -> bool {
-		self.as_iri_ref().eq(other)
+	fn eq(&self, other: &&str) -> bool {
+		*self == **other
+	}
+}
+
+impl<'a> PartialEq<String> for Iri<'a> {
+	#[inline]
+	fn eq(&self, other: &String) -> bool {
+		*self == *other.as_str()
+	}
+}
+
+impl<'a> PartialEq<Iri<'a>> for str {
+	#[inline]
+	fn eq(&self, other: &Iri<'a>) -> bool {
+		*other == *self
+	}
+}
+
+impl<'a> PartialEq<Iri<'a>> for &str {
+	#[inline]
+	fn eq(&self, other: &Iri<'a>) -> bool {
+		*other == **self
+	}
+}
+
+impl<'a> PartialEq<Iri<'a>> for String {
+	#[inline]
+	fn eq(&self, other: &Iri<'a>) -> bool {
+		*other == *self.as_str()
 	}
 }
 
@@ -318,6 +848,20 @@ impl<'a> Hash for Iri<'a> {
 	}
 }
 
+impl<'a> AsRef<[u8]> for Iri<'a> {
+	#[inline]
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl<'a> AsRef<str> for Iri<'a> {
+	#[inline]
+	fn as_ref(&self) -> &str {
+		self.as_str()
+	}
+}
+
 impl<'a> AsIri for Iri<'a> {
 	#[inline]
 	fn as_iri(&self) -> Iri {
@@ -335,6 +879,8 @@ impl<'a> AsIriRef for Iri<'a> {
 /// Cheap reference-to-IRI-reference convertion.
 ///
 /// This is to be used instead of `AsRef<IriRef>` until custom DSTs are introduced.
+/// [`IriRef<'a>`](IriRef) is a value borrowing its data for `'a`, not a view type that
+/// a reference could point to, hence the by-value return.
 pub trait AsIriRef {
 	fn as_iri_ref(&self) -> IriRef;
 }
@@ -342,6 +888,7 @@ pub trait AsIriRef {
 /// Cheap reference-to-IRI convertion.
 ///
 /// This is to be used instead of `AsRef<Iri>` until custom DSTs are introduced.
+/// See the [`IriBuf`] documentation for details.
 pub trait AsIri {
 	fn as_iri(&self) -> Iri;
 }
@@ -359,3 +906,249 @@ impl<'a, T: AsIriRef> AsIriRef for &'a T {
 		(*self).as_iri_ref()
 	}
 }
+
+/// Checks if the given hosts are equal, or have the same IDNA ASCII form.
+fn host_matches(a: Host, b: Host) -> bool {
+	if a == b {
+		return true;
+	}
+
+	#[cfg(feature = "idna")]
+	{
+		if let (Ok(a), Ok(b)) = (a.to_ascii(), b.to_ascii()) {
+			return a == b;
+		}
+	}
+
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn str_accessors() {
+		let iri = Iri::new("HTTP://u@H%41:8/a/./b%20c?q%3D#f%23").unwrap();
+		assert_eq!(iri.scheme_str(), "HTTP");
+		assert_eq!(iri.authority().unwrap().host_str(), "H%41");
+		assert_eq!(iri.path_str(), "/a/./b%20c");
+		assert_eq!(iri.query_str(), Some("q%3D"));
+		assert_eq!(iri.fragment_str(), Some("f%23"));
+
+		let buffer = IriBuf::new("foo:?").unwrap();
+		assert_eq!(buffer.scheme_str(), "foo");
+		assert_eq!(buffer.path_str(), "");
+		assert_eq!(buffer.query_str(), Some(""));
+		assert_eq!(buffer.fragment_str(), None);
+
+		let iri_ref = IriRefBuf::new("a/b#").unwrap();
+		assert_eq!(iri_ref.scheme_str(), None);
+		assert_eq!(iri_ref.path_str(), "a/b");
+		assert_eq!(iri_ref.query_str(), None);
+		assert_eq!(iri_ref.fragment_str(), Some(""));
+	}
+
+	#[test]
+	fn from_utf8() {
+		let bytes = "http://例え.jp/é?q#f".as_bytes();
+		let iri = Iri::from_utf8(bytes).unwrap();
+		assert_eq!(iri.as_bytes().as_ptr(), bytes.as_ptr());
+		assert_eq!(iri.as_str(), "http://例え.jp/é?q#f");
+
+		let invalid: [&[u8]; 5] = [
+			b"http://a/\xff",
+			b"http://a/\xC0\xAF",
+			b"http://a/\xED\xA0\x80",
+			b"http://\xC3/",
+			b"http://a/?\xE6\x97#f",
+		];
+		for bytes in &invalid {
+			assert!(matches!(Iri::from_utf8(bytes), Err(Error::InvalidEncoding)));
+		}
+
+		assert!(matches!(
+			Iri::from_utf8(b"//a/b"),
+			Err(Error::MissingScheme)
+		));
+		assert!(Iri::from_utf8(b"http://a/ b").is_err());
+		assert!(IriRef::from_utf8(b"//a/\xC3\xA9").is_ok());
+	}
+
+	#[test]
+	fn absolute_uri() {
+		let iri = Iri::new_absolute("http://h/p?q").unwrap();
+		assert!(iri.is_absolute_uri());
+		assert_eq!(iri.query().unwrap(), "q");
+		let buffer = IriBuf::new_absolute("http://h/p?q").unwrap();
+		assert!(buffer.is_absolute_uri());
+
+		for invalid in &["http://h/p#f", "http://h/p#"] {
+			assert!(!IriRef::new(invalid).unwrap().is_absolute_uri());
+			assert!(matches!(
+				Iri::new_absolute(invalid),
+				Err(Error::UnexpectedFragment)
+			));
+			assert!(matches!(
+				IriBuf::new_absolute(invalid),
+				Err(Error::UnexpectedFragment)
+			));
+		}
+
+		assert!(!IriRefBuf::new("/p?q").unwrap().is_absolute_uri());
+		assert!(matches!(
+			Iri::new_absolute("//h/p"),
+			Err(Error::MissingScheme)
+		));
+	}
+
+	#[test]
+	fn with_components() {
+		let iri = Iri::new("http://a/b?q#f").unwrap();
+		assert_eq!(iri.with_scheme("https").unwrap(), "https://a/b?q#f");
+		assert_eq!(
+			iri.with_authority(Some("u@c:1")).unwrap(),
+			"http://u@c:1/b?q#f"
+		);
+		assert_eq!(iri.with_authority(None).unwrap(), "http:/b?q#f");
+		assert_eq!(iri.with_path("/c/d").unwrap(), "http://a/c/d?q#f");
+		assert_eq!(iri.with_path("").unwrap(), "http://a?q#f");
+		assert_eq!(iri.with_query(Some("r")).unwrap(), "http://a/b?r#f");
+		assert_eq!(iri.with_query(None).unwrap(), "http://a/b#f");
+		assert_eq!(iri.with_fragment(Some("g")).unwrap(), "http://a/b?q#g");
+		assert_eq!(iri.with_fragment(None).unwrap(), "http://a/b?q");
+		assert_eq!(iri.as_str(), "http://a/b?q#f");
+
+		assert!(iri.with_scheme("1").is_err());
+		assert!(iri.with_authority(Some("a/b")).is_err());
+		assert!(iri.with_path("c").is_err());
+		assert!(iri.with_path("/c?d").is_err());
+		assert!(iri.with_query(Some("a#b")).is_err());
+		assert!(iri.with_fragment(Some("a#b")).is_err());
+
+		let iri = IriBuf::new("mailto:someone@example.org").unwrap();
+		assert_eq!(
+			iri.with_path("other@example.org").unwrap(),
+			"mailto:other@example.org"
+		);
+		assert!(iri.with_path("//a").is_err());
+		assert!(matches!(
+			iri.with_authority(Some("a")),
+			Err(Error::InvalidPath)
+		));
+	}
+
+	#[test]
+	fn strip_prefix() {
+		let iri = Iri::new("http://a/b/c/d?q#f").unwrap();
+		let strip = |base| iri.strip_prefix(Iri::new(base).unwrap());
+
+		assert_eq!(strip("http://a/b/").unwrap(), "c/d?q#f");
+		assert_eq!(strip("http://a/b").unwrap(), "c/d?q#f");
+		assert_eq!(strip("HTTP://A/b?r#g").unwrap(), "c/d?q#f");
+		assert_eq!(strip("http://a").unwrap(), "b/c/d?q#f");
+		assert_eq!(strip("http://a/b/c/d").unwrap(), "?q#f");
+		assert!(strip("http://a/b/c/d/").is_none());
+		assert!(strip("http://a/b/c/d/e").is_none());
+		assert!(strip("http://a/b/c/").unwrap().as_str() == "d?q#f");
+		assert!(strip("http://a/b/c").unwrap().scheme().is_none());
+		assert!(strip("http://a/bc").is_none());
+		assert!(strip("https://a/b/").is_none());
+		assert!(strip("http://b/b/").is_none());
+		assert!(strip("http:/b/").is_none());
+
+		let base = Iri::new("http://a/b/").unwrap();
+		let rest = iri.strip_prefix(base).unwrap();
+		assert!(rest.resolved(base).eq_exact(iri));
+
+		let iri = Iri::new("http://a/b//c").unwrap();
+		assert!(iri.strip_prefix(Iri::new("http://a/b").unwrap()).is_none());
+		let iri = Iri::new("urn:a/b:c").unwrap();
+		assert!(iri.strip_prefix(Iri::new("urn:a/").unwrap()).is_none());
+		assert_eq!(
+			iri.strip_prefix(Iri::new("urn:").unwrap()).unwrap(),
+			"a/b:c"
+		);
+	}
+
+	#[test]
+	fn origin_matches() {
+		let iri = Iri::new("https://trusted.example/a/b?q#f").unwrap();
+		assert!(iri.origin_matches("https", "trusted.example", None));
+		assert!(iri.origin_matches("HTTPS", "Trusted.EXAMPLE", Some(443)));
+		assert!(!iri.origin_matches("http", "trusted.example", None));
+		assert!(!iri.origin_matches("https", "trusted.example", Some(8443)));
+		assert!(!iri.origin_matches("https", "example", None));
+		assert!(!iri.origin_matches("https", "trusted.example/", None));
+		assert!(!iri.origin_matches("https", "trusted.example:443", None));
+
+		let attacks = [
+			"https://trusted.example.evil.com/a",
+			"https://trusted.example@evil.com/a",
+			"https://evil.com/trusted.example",
+			"https://trusted.examplee/a",
+		];
+		for attack in &attacks {
+			let iri = Iri::new(attack).unwrap();
+			assert!(!iri.origin_matches("https", "trusted.example", None));
+		}
+
+		let iri = IriBuf::new("https://u@trusted.example:443").unwrap();
+		assert!(iri.origin_matches("https", "trusted.example", None));
+		let iri = Iri::new("https://tr%75sted.example:/").unwrap();
+		assert!(iri.origin_matches("https", "trusted.example", Some(443)));
+		let iri = Iri::new("https://trusted.example:8443/").unwrap();
+		assert!(iri.origin_matches("https", "trusted.example", Some(8443)));
+		assert!(!iri.origin_matches("https", "trusted.example", None));
+		let iri = Iri::new("https://trusted.example:99999/").unwrap();
+		assert!(!iri.origin_matches("https", "trusted.example", None));
+
+		let iri = Iri::new("foo://h/a").unwrap();
+		assert!(iri.origin_matches("foo", "h", None));
+		assert!(!iri.origin_matches("foo", "h", Some(80)));
+		assert!(!Iri::new("urn:h").unwrap().origin_matches("urn", "h", None));
+
+		let iri = Iri::new("http://[::1]:80/").unwrap();
+		assert!(iri.origin_matches("http", "[0:0::1]", None));
+
+		#[cfg(feature = "idna")]
+		{
+			let iri = Iri::new("https://例え.jp/").unwrap();
+			assert!(iri.origin_matches("https", "xn--r8jz45g.jp", None));
+			assert!(!iri.origin_matches("https", "xn--r8jz45g.jp.evil.com", None));
+		}
+	}
+
+	#[test]
+	fn common_base() {
+		let common_base = |a, b| {
+			let (a, b) = (Iri::new(a).unwrap(), Iri::new(b).unwrap());
+			let base = a.common_base(b);
+			assert_eq!(base, b.common_base(a));
+			base
+		};
+		let base = |a, b| common_base(a, b).unwrap();
+
+		assert_eq!(base("http://h/a/b/c", "http://h/a/b/d"), "http://h/a/b/");
+		assert_eq!(
+			base("http://u@h:8/a/x?q", "HTTP://u@H:8/a/y#f"),
+			"http://u@h:8/a/"
+		);
+		assert_eq!(base("http://h/a/b", "http://h/a/b"), "http://h/a/");
+		assert_eq!(base("http://h/a/b/", "http://h/a/b/"), "http://h/a/b/");
+		assert_eq!(base("http://h/a", "http://h"), "http://h");
+		assert_eq!(base("foo:a/b", "foo:a/c"), "foo:a/");
+		assert_eq!(base("foo:a/b", "foo:b/a"), "foo:");
+		assert!(common_base("http://h/a", "https://h/a").is_none());
+		assert!(common_base("http://h/a", "http://g/a").is_none());
+		assert!(common_base("foo:/a", "foo://h/a").is_none());
+
+		let a = Iri::new("http://h/a/b/c").unwrap();
+		let b = Iri::new("http://h/a/d").unwrap();
+		let base = a.common_base(b).unwrap();
+		for iri in &[a, b] {
+			let rest = iri.strip_prefix(&base).unwrap();
+			assert!(rest.resolved(&base).eq_exact(*iri));
+		}
+	}
+}
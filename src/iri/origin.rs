@@ -0,0 +1,206 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::reference::CanonicalHost;
+use crate::{Iri, IriBuf};
+
+/// Origin of an IRI, made of its scheme, host and port.
+///
+/// Returned by [`Iri::origin`].
+/// The scheme and host are kept in their [canonical](crate::IriRef::canonical) form, so that two
+/// origins are equal if and only if their components are, ignoring case and percent-encoding.
+/// The default port of the `http`, `https` and `ftp` schemes is removed, as it is the same as
+/// no port, like in [`Iri::origin_matches`].
+///
+/// IRIs without authority, such as `mailto:` or `urn:` IRIs, have an opaque origin,
+/// represented by `None`: an opaque origin is never the same as another origin,
+/// see [`Iri::same_origin`].
+///
+/// The [`Display`](fmt::Display) implementation gives the serialization
+/// `scheme://host[:port]` of the origin.
+///
+/// # Example
+/// ```
+/// # use iref::Iri;
+/// let iri = Iri::new("HTTP://u@Example.ORG:8080/a?b#c").unwrap();
+/// let origin = iri.origin().unwrap();
+/// assert_eq!(origin.scheme(), "http");
+/// assert_eq!(origin.host(), "example.org");
+/// assert_eq!(origin.port(), Some(8080));
+/// assert_eq!(origin.to_string(), "http://example.org:8080");
+///
+/// assert!(Iri::new("mailto:a@example.org").unwrap().origin().is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Origin {
+	scheme: String,
+	host: String,
+	port: Option<u16>,
+}
+
+impl Origin {
+	/// Scheme of the origin, in lowercase.
+	#[inline]
+	pub fn scheme(&self) -> &str {
+		&self.scheme
+	}
+
+	/// Host of the origin, in its canonical form.
+	#[inline]
+	pub fn host(&self) -> &str {
+		&self.host
+	}
+
+	/// Port of the origin, if any.
+	#[inline]
+	pub fn port(&self) -> Option<u16> {
+		self.port
+	}
+}
+
+impl fmt::Display for Origin {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}://{}", self.scheme, self.host)?;
+		if let Some(port) = self.port {
+			write!(f, ":{}", port)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl<'a> Iri<'a> {
+	/// Returns the origin of this IRI.
+	///
+	/// Returns `None`, the opaque origin, if the IRI has no authority, an empty host
+	/// (as in `file:///a`) or a port greater than 65535.
+	/// An empty port (as in `http://h:/a`) is the same as no port.
+	/// See [`Origin`].
+	pub fn origin(&self) -> Option<Origin> {
+		let authority = self.authority()?;
+		let host = authority.host();
+		if host.is_empty() {
+			return None;
+		}
+
+		let default_port = self.scheme().default_port();
+		let port = match authority.port() {
+			Some(port) if !port.is_empty() => Some(port.as_u16()?),
+			_ => None,
+		}
+		.filter(|port| Some(*port) != default_port);
+
+		Some(Origin {
+			scheme: self.scheme_str().to_ascii_lowercase(),
+			host: CanonicalHost(host).to_string(),
+			port,
+		})
+	}
+
+	/// Checks if this IRI and `other` have the same origin.
+	///
+	/// Returns `false` if any of them has an opaque origin, even when they are equal.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let iri = Iri::new("https://example.org/a").unwrap();
+	/// assert!(iri.same_origin(Iri::new("HTTPS://example.org/b?c").unwrap()));
+	/// assert!(!iri.same_origin(Iri::new("http://example.org/a").unwrap()));
+	///
+	/// let urn = Iri::new("urn:isbn:0451450523").unwrap();
+	/// assert!(!urn.same_origin(urn));
+	/// ```
+	pub fn same_origin<'b, Other: Into<Iri<'b>>>(&self, other: Other) -> bool {
+		match (self.origin(), other.into().origin()) {
+			(Some(a), Some(b)) => a == b,
+			_ => false,
+		}
+	}
+}
+
+impl IriBuf {
+	/// Returns the origin of this IRI.
+	///
+	/// See [`Iri::origin`].
+	#[inline]
+	pub fn origin(&self) -> Option<Origin> {
+		self.as_iri().origin()
+	}
+
+	/// Checks if this IRI and `other` have the same origin.
+	///
+	/// See [`Iri::same_origin`].
+	#[inline]
+	pub fn same_origin<'b, Other: Into<Iri<'b>>>(&self, other: Other) -> bool {
+		self.as_iri().same_origin(other)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Iri, IriBuf};
+	use std::collections::HashMap;
+
+	#[test]
+	fn origin() {
+		let origin = |iri| Iri::new(iri).unwrap().origin();
+
+		let a = origin("https://Example.org/a").unwrap();
+		assert_eq!(a.to_string(), "https://example.org");
+		assert_eq!(a.port(), None);
+		assert_eq!(origin("HTTPS://u@EXAMPLE.%6Frg:/b?q#f").unwrap(), a);
+		assert_ne!(origin("http://example.org/a").unwrap(), a);
+		assert_ne!(origin("https://example.org.evil.com/a").unwrap(), a);
+
+		let port = origin("https://example.org:8443/").unwrap();
+		assert_eq!(port.port(), Some(8443));
+		assert_eq!(port.to_string(), "https://example.org:8443");
+		assert_ne!(port, a);
+
+		assert_eq!(origin("https://example.org:443/").unwrap(), a);
+		assert_eq!(origin("HTTP://example.org:0080/").unwrap().port(), None);
+		assert_eq!(origin("foo://example.org:443/").unwrap().port(), Some(443));
+
+		let ipv6 = origin("http://[0:0::1]:8080").unwrap();
+		assert_eq!(ipv6.host(), "[::1]");
+		assert_eq!(ipv6.to_string(), "http://[::1]:8080");
+		let zone = origin("http://[fe80::1%25%C3%A9]/").unwrap();
+		assert_eq!(zone.host(), "[fe80::1%25%C3%A9]");
+		assert_eq!(
+			origin("http://EX%41mple.%C3%A9/").unwrap().host(),
+			"example.é"
+		);
+
+		assert!(origin("mailto:a@example.org").is_none());
+		assert!(origin("urn:isbn:0451450523").is_none());
+		assert!(origin("file:///a").is_none());
+		assert!(origin("http://h:99999/").is_none());
+
+		let mut limits = HashMap::new();
+		*limits.entry(a).or_insert(0) += 1;
+		let b = IriBuf::new("https://example.org/c")
+			.unwrap()
+			.origin()
+			.unwrap();
+		*limits.entry(b).or_insert(0) += 1;
+		assert_eq!(limits.len(), 1);
+	}
+
+	#[test]
+	fn same_origin() {
+		let iri = IriBuf::new("https://example.org/a").unwrap();
+		assert!(iri.same_origin(Iri::new("https://EXAMPLE.org:/b").unwrap()));
+		assert!(!iri.same_origin(Iri::new("https://example.org:8443/a").unwrap()));
+		assert!(!iri.same_origin(Iri::new("https://other.org/a").unwrap()));
+
+		let urn = Iri::new("urn:a").unwrap();
+		assert!(!urn.same_origin(urn));
+		assert!(!iri.same_origin(urn));
+
+		// Consistent with `origin_matches`, with or without the `schemes` feature.
+		let explicit = Iri::new("https://example.org:443/").unwrap();
+		assert!(explicit.origin_matches("https", "example.org", None));
+		assert!(iri.same_origin(explicit));
+	}
+}
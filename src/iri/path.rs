@@ -1,12 +1,18 @@
 use super::{Error, Segment};
 use crate::{parsing, AsIriRef, IriRef, IriRefBuf};
+use alloc::{
+	borrow::Cow,
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::iter::{IntoIterator, Rev};
+use core::ops::Range;
+use core::{cmp, fmt};
 use pct_str::PctStr;
 use smallvec::SmallVec;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::iter::IntoIterator;
-use std::{cmp, fmt};
 
 #[derive(Clone, Copy)]
 pub struct Path<'a> {
@@ -46,13 +52,13 @@ impl<'a> Path<'a> {
 	/// Get the underlying path slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(self.data) }
+		unsafe { core::str::from_utf8_unchecked(self.data) }
 	}
 
 	/// Convert this path into the underlying path slice.
 	#[inline]
 	pub fn into_str(self) -> &'a str {
-		unsafe { std::str::from_utf8_unchecked(self.data) }
+		unsafe { core::str::from_utf8_unchecked(self.data) }
 	}
 
 	/// Get the underlying path slice as a percent-encoded string slice.
@@ -85,6 +91,26 @@ impl<'a> Path<'a> {
 		}
 	}
 
+	/// Percent-decode the path.
+	///
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+	/// Note that a decoded `%2F` cannot be distinguished from a segment separator:
+	/// use [`segments`](Path::segments) and [`Segment::decoded`] to decode each segment
+	/// separately.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// use iref::Path;
+	///
+	/// let path = Path::try_from("/caf%C3%A9/a%20b").unwrap();
+	/// assert_eq!(path.decoded(), "/café/a b");
+	/// ```
+	#[inline]
+	pub fn decoded(&self) -> String {
+		crate::pct_decode_lossy(self.as_pct_str())
+	}
+
 	/// Checks if the path is empty.
 	///
 	/// Returns `true` if the path is `` or `/`.
@@ -170,6 +196,26 @@ impl<'a> Path<'a> {
 		)
 	}
 
+	/// Byte range of the segment at the given index in the path, if any.
+	fn segment_range(&self, index: usize) -> Option<Range<usize>> {
+		let closed_len = self.closed_len();
+		let mut offset = 0;
+		for _ in 0..index {
+			if offset >= closed_len {
+				return None;
+			}
+
+			offset = self.segment_at(offset).1;
+		}
+
+		if offset >= closed_len {
+			None
+		} else {
+			let (segment, end) = self.segment_at(offset);
+			segment.map(|segment| (end - segment.len())..end)
+		}
+	}
+
 	#[inline]
 	pub fn first(&self) -> Option<Segment<'a>> {
 		let (segment, _) = self.segment_at(0);
@@ -211,12 +257,44 @@ impl<'a> Path<'a> {
 	///
 	/// Empty segments are preserved: the path `a//b` will raise the three segments `a`, `` and
 	/// `b`.
-	/// The absolute path `/` has no segments, but the path `/a/` has two segments, `a` and ``.
+	/// A single trailing `/` does not produce a final empty segment, it makes the last segment
+	/// [open](Segment::is_open) instead: the absolute path `/` has no segments,
+	/// the path `/a/` has one open segment `a`, and the path `/a//` has two segments, `a` and ``.
+	///
+	/// The returned iterator is double-ended.
 	#[inline]
 	pub fn segments(&self) -> Segments<'a> {
 		Segments::new(*self)
 	}
 
+	/// Produces an iterator over the segments of the IRI path, starting from the last one.
+	///
+	/// This is the reverse of [`segments`](Path::segments), with the same trailing `/`
+	/// semantics.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// use iref::Path;
+	///
+	/// let path = Path::try_from("/a/b/c/").unwrap();
+	/// let segments: Vec<_> = path.rsegments().map(|s| s.into_str()).collect();
+	/// assert_eq!(segments, ["c", "b", "a"]);
+	/// ```
+	#[inline]
+	pub fn rsegments(&self) -> Rev<Segments<'a>> {
+		self.segments().rev()
+	}
+
+	/// Returns the segment at the given index, as yielded by [`segments`](Path::segments).
+	///
+	/// Returns `None` if the path has `index` segments or less.
+	/// This iterates over the first segments of the path.
+	#[inline]
+	pub fn segment(&self, index: usize) -> Option<Segment<'a>> {
+		self.segments().nth(index)
+	}
+
 	/// Iterate over the normalized segments of the path.
 	///
 	/// Remove the special dot segments `..` and `.` from the iteration using the usual path
@@ -228,21 +306,125 @@ impl<'a> Path<'a> {
 		NormalizedSegments::new(*self)
 	}
 
+	/// Iterate over the percent-decoded segments of the path.
+	///
+	/// The segments are the ones of [`segments`](Path::segments).
+	/// A segment without percent-encoded character is borrowed, avoiding any allocation.
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// use iref::Path;
+	///
+	/// let path = Path::try_from("/a%20b/c/").unwrap();
+	/// let segments: Vec<_> = path.decoded_segments().collect();
+	/// assert_eq!(segments, ["a b", "c"]);
+	/// ```
+	#[inline]
+	pub fn decoded_segments(&self) -> DecodedSegments<'a> {
+		DecodedSegments {
+			segments: self.segments(),
+		}
+	}
+
 	/// Consume the path reference and return an iterator over its normalized segments.
 	#[inline]
 	pub fn into_normalized_segments(self) -> NormalizedSegments<'a> {
 		NormalizedSegments::new(self)
 	}
 
-	/// Returns the name of the final segment of the path, if there is one.
+	/// Returns the name of the final non-empty segment of the path, if there is one.
 	///
 	/// If the path is a normal file, this is the file name. If it's the path of a directory, this
-	/// is the directory name.
+	/// is the directory name: the file name of both `/a/b` and `/a/b/` is `b`.
+	/// Empty segments are skipped, so the file name of `/a/b//` is also `b`.
 	///
 	/// This does not consider the normalized version of the path, dot segments are preserved.
 	#[inline]
-	pub fn file_name(&self) -> Option<&'a str> {
-		self.into_iter().next_back().map(|s| s.into_str())
+	pub fn file_name(&self) -> Option<&'a PctStr> {
+		self.rsegments()
+			.find(|s| !s.is_empty())
+			.map(|s| unsafe { PctStr::new_unchecked(s.into_str()) })
+	}
+
+	/// Returns the extension of the final segment of the path, if there is one.
+	///
+	/// The extension is the part of the final segment after its last `.`, in the manner of
+	/// [`std::path::Path::extension`]. There is no extension if the final segment has no `.`,
+	/// if its only `.` is the first character (as in `.hidden`), if it is `..`, or if
+	/// the path ends with a `/`.
+	/// Percent-encoded dots (`%2E`) are not extension separators.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// use iref::Path;
+	///
+	/// assert_eq!(Path::try_from("/a/archive.tar.gz").unwrap().extension(), Some("gz"));
+	/// assert_eq!(Path::try_from("/a/.hidden").unwrap().extension(), None);
+	/// assert_eq!(Path::try_from("/a.d/").unwrap().extension(), None);
+	/// ```
+	#[inline]
+	pub fn extension(&self) -> Option<&'a str> {
+		if self.is_open() {
+			return None;
+		}
+
+		let name = self.segments().next_back()?.into_str();
+		split_extension(name).map(|(_, extension)| extension)
+	}
+
+	/// Returns the number of segments of the path, as yielded by [`segments`](Path::segments).
+	///
+	/// The path is scanned once, counting `/` separators (a percent-encoded `%2F` is not one).
+	/// The leading `/` of an absolute path and a single trailing `/` do not delimit empty
+	/// segments: `/` has no segment, `/a` and `/a/` have one, and `/a//` has two.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use iref::Path;
+	/// assert_eq!(Path::try_from("/a/b/c").unwrap().segment_count(), 3);
+	/// assert_eq!(Path::try_from("a/b%2Fc/").unwrap().segment_count(), 2);
+	/// ```
+	#[inline]
+	pub fn segment_count(&self) -> usize {
+		let mut data = self.data;
+		if let [b'/', rest @ ..] = data {
+			data = rest
+		}
+
+		if data.is_empty() {
+			return 0;
+		}
+
+		if let [rest @ .., b'/'] = data {
+			data = rest
+		}
+
+		data.iter().filter(|b| **b == b'/').count() + 1
+	}
+
+	/// Checks if the path contains a `.` or `..` segment.
+	///
+	/// If not, the path is already normalized, and [`normalized_segments`](Path::normalized_segments)
+	/// gives the same segments as [`segments`](Path::segments).
+	/// The reverse is not true: the leading `..` segments of a relative path are preserved
+	/// by the normalization.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use iref::Path;
+	/// assert!(Path::try_from("a/./b").unwrap().has_dot_segments());
+	/// assert!(Path::try_from("/a/b/..").unwrap().has_dot_segments());
+	/// assert!(!Path::try_from("/a/.b/%2E").unwrap().has_dot_segments());
+	/// ```
+	#[inline]
+	pub fn has_dot_segments(&self) -> bool {
+		self.segments()
+			.any(|segment| matches!(segment.as_bytes(), b"." | b".."))
 	}
 
 	/// Returns the path without its final component, if there is one.
@@ -314,6 +496,20 @@ impl<'a> Path<'a> {
 	}
 }
 
+/// Split a segment into its stem and extension, if any.
+///
+/// See [`Path::extension`].
+pub(crate) fn split_extension(name: &str) -> Option<(&str, &str)> {
+	if name == ".." {
+		return None;
+	}
+
+	match name.rfind('.') {
+		Some(0) | None => None,
+		Some(i) => Some((&name[..i], &name[(i + 1)..])),
+	}
+}
+
 impl<'a> AsRef<[u8]> for Path<'a> {
 	#[inline]
 	fn as_ref(&self) -> &[u8] {
@@ -342,6 +538,24 @@ impl<'a> TryFrom<&'a str> for Path<'a> {
 	}
 }
 
+/// Iterate over the segments of the path.
+///
+/// This is how to iterate over the segments of an IRI: the IRI types themselves do not
+/// implement `IntoIterator`, but their [`path`](crate::IriBuf::path) does.
+/// Each [`Segment`] borrows the IRI, and gives its percent-encoded value with
+/// [`Segment::as_pct_str`].
+/// Use [`IriBuf::into_segments`](crate::IriBuf::into_segments) to consume an owned IRI instead.
+///
+/// # Example
+/// ```
+/// # use iref::IriBuf;
+/// let iri = IriBuf::new("http://h/a/b%20c").unwrap();
+/// let mut decoded = Vec::new();
+/// for segment in iri.path() {
+/// 	decoded.push(segment.as_pct_str().decode());
+/// }
+/// assert_eq!(decoded, ["a", "b c"]);
+/// ```
 impl<'a> IntoIterator for Path<'a> {
 	type Item = Segment<'a>;
 	type IntoIter = Segments<'a>;
@@ -413,6 +627,95 @@ impl<'a> DoubleEndedIterator for Segments<'a> {
 	}
 }
 
+/// Owning iterator over the segments of a path.
+///
+/// Yields the percent-encoded value of each segment, in both directions.
+/// See [`IriRefBuf::into_segments`](crate::IriRefBuf::into_segments).
+#[derive(Clone)]
+pub struct IntoSegments {
+	data: Vec<u8>,
+	offset: usize,
+	offset_back: usize,
+}
+
+impl IntoSegments {
+	/// Iterate over the segments of the given path bytes.
+	pub(crate) fn new(data: Vec<u8>) -> IntoSegments {
+		let offset_back = Path { data: &data }.closed_len();
+		IntoSegments {
+			data,
+			offset: 0,
+			offset_back,
+		}
+	}
+
+	/// Apply `f` on the borrowed [`Segments`] iterator over the remaining segments.
+	fn with_segments<F>(&mut self, f: F) -> Option<String>
+	where
+		F: for<'s> FnOnce(&mut Segments<'s>) -> Option<Segment<'s>>,
+	{
+		let mut segments = Segments {
+			path: Path { data: &self.data },
+			offset: self.offset,
+			offset_back: self.offset_back,
+		};
+
+		let segment = f(&mut segments).map(|segment| segment.as_str().to_string());
+		self.offset = segments.offset;
+		self.offset_back = segments.offset_back;
+		segment
+	}
+}
+
+impl Iterator for IntoSegments {
+	type Item = String;
+
+	#[inline]
+	fn next(&mut self) -> Option<String> {
+		self.with_segments(|segments| segments.next())
+	}
+}
+
+impl DoubleEndedIterator for IntoSegments {
+	#[inline]
+	fn next_back(&mut self) -> Option<String> {
+		self.with_segments(|segments| segments.next_back())
+	}
+}
+
+/// Iterator over the percent-decoded segments of a path.
+///
+/// See [`Path::decoded_segments`].
+#[derive(Clone)]
+pub struct DecodedSegments<'a> {
+	segments: Segments<'a>,
+}
+
+/// Percent-decode the given segment, borrowing it if it contains no percent-encoded character.
+fn decode_segment(segment: Segment) -> Cow<str> {
+	if segment.data.contains(&b'%') {
+		Cow::Owned(segment.decoded())
+	} else {
+		Cow::Borrowed(segment.into_str())
+	}
+}
+
+impl<'a> Iterator for DecodedSegments<'a> {
+	type Item = Cow<'a, str>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Cow<'a, str>> {
+		self.segments.next().map(decode_segment)
+	}
+}
+
+impl<'a> DoubleEndedIterator for DecodedSegments<'a> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Cow<'a, str>> {
+		self.segments.next_back().map(decode_segment)
+	}
+}
+
 /// Stack size (in `Segment`) allocated for [`NormalizedSegments`] to normalize a `Path`.
 /// If it needs more space, it will allocate memory on the heap.
 const NORMALIZE_STACK_SIZE: usize = 16;
@@ -437,11 +740,18 @@ impl<'a> NormalizedSegments<'a> {
 						last_segment.open();
 					}
 				}
-				b".." => {
-					if stack.pop().is_none() && relative {
-						stack.push(segment)
+				b".." => match stack.last() {
+					// `..` segments that could not be removed are kept in relative paths.
+					Some(last) if last.data == b".." => stack.push(segment),
+					Some(_) => {
+						stack.pop();
 					}
-				}
+					None => {
+						if relative {
+							stack.push(segment)
+						}
+					}
+				},
 				_ => stack.push(segment),
 			}
 		}
@@ -552,9 +862,14 @@ impl<'a> Ord for Path<'a> {
 }
 
 impl<'a> Hash for Path<'a> {
+	/// Hash the normalized segments of the path,
+	/// consistently with the [`PartialEq`] implementation.
 	#[inline]
 	fn hash<H: Hasher>(&self, hasher: &mut H) {
-		self.as_pct_str().hash(hasher)
+		self.is_absolute().hash(hasher);
+		for segment in self.normalized_segments() {
+			segment.hash(hasher)
+		}
 	}
 }
 
@@ -635,7 +950,8 @@ impl<'a> PathMut<'a> {
 	///
 	/// Empty segments are preserved: the path `a//b` will raise the three segments `a`, `` and
 	/// `b`.
-	/// The absolute path `/` has no segments, but the path `/a/` has two segments, `a` and ``.
+	/// A single trailing `/` makes the last segment open instead of adding an empty segment,
+	/// as for [`Path::segments`].
 	#[inline]
 	pub fn segments(&self) -> Segments {
 		self.buffer.path().into_iter()
@@ -697,6 +1013,13 @@ impl<'a> PathMut<'a> {
 				self.push(Segment::current())
 			}
 
+			// if the IRI has an authority, the path must be absolute.
+			if self.buffer.p.path_len == 0 && self.buffer.authority().is_some() {
+				let offset = self.buffer.p.path_offset();
+				self.buffer.replace(offset..offset, b"/");
+				self.buffer.p.path_len += 1;
+			}
+
 			// make sure it ends with a slash.
 			self.open();
 
@@ -711,6 +1034,168 @@ impl<'a> PathMut<'a> {
 		}
 	}
 
+	/// Add the given segments at the end of the path.
+	///
+	/// This is equivalent to calling [`push`](PathMut::push) for each segment,
+	/// except that the IRI buffer is only modified once, after all the segments have been
+	/// validated. If a segment is invalid, the path is left unchanged.
+	#[inline]
+	pub fn extend<'s, I: IntoIterator<Item = &'s str>>(
+		&mut self,
+		segments: I,
+	) -> Result<(), Error> {
+		self.append_segments(segments.into_iter().map(Segment::try_from))
+	}
+
+	/// Add the segments of the given relative path at the end of the path.
+	///
+	/// The path `raw` is validated, then its segments are added as with
+	/// [`extend`](PathMut::extend): pushing `b/c/` to the path `a` gives `a/b/c/`.
+	/// A leading `/` in `raw` is ignored.
+	#[inline]
+	pub fn push_str(&mut self, raw: &str) -> Result<(), Error> {
+		let path = Path::try_from(raw)?;
+		self.append_segments(path.segments().map(Ok))
+	}
+
+	/// Replace the segment at the given index.
+	///
+	/// The `value` must be a single segment, without `/`.
+	/// Fails with [`Error::InvalidSegmentIndex`] if there is no segment at this index,
+	/// leaving the path unchanged.
+	pub fn set_segment(&mut self, index: usize, value: &str) -> Result<(), Error> {
+		let segment = Self::parse_segment(value)?;
+		let range = self
+			.as_path()
+			.segment_range(index)
+			.ok_or(Error::InvalidSegmentIndex)?;
+
+		let offset = self.buffer.p.path_offset();
+		let was_relative = self.is_relative();
+		self.buffer.replace(
+			(offset + range.start)..(offset + range.end),
+			segment.as_ref(),
+		);
+		self.buffer.p.path_len = self.buffer.p.path_len - range.len() + segment.len();
+		self.fix_relative(was_relative);
+		self.disambiguate();
+		Ok(())
+	}
+
+	/// Insert a segment at the given index, shifting the following segments.
+	///
+	/// The `value` must be a single segment, without `/`.
+	/// Inserting at the index equal to the number of segments adds the segment at the end of
+	/// the path, as with [`push`](PathMut::push), but without changing whether the path is
+	/// open.
+	/// Fails with [`Error::InvalidSegmentIndex`] if the index is greater than the number of
+	/// segments, leaving the path unchanged.
+	pub fn insert_segment(&mut self, index: usize, value: &str) -> Result<(), Error> {
+		let segment = Self::parse_segment(value)?;
+		match self.as_path().segment_range(index) {
+			Some(range) => {
+				let offset = self.buffer.p.path_offset() + range.start;
+				let was_relative = self.is_relative();
+				let mut content: SmallVec<[u8; 64]> = SmallVec::new();
+				content.extend_from_slice(segment.as_ref());
+				content.push(b'/');
+				self.buffer.replace(offset..offset, &content);
+				self.buffer.p.path_len += content.len();
+				self.fix_relative(was_relative);
+				self.disambiguate();
+				Ok(())
+			}
+			None => {
+				if index != self.segments().count() {
+					return Err(Error::InvalidSegmentIndex);
+				}
+
+				let open = self.is_open();
+				self.push(segment);
+				if open {
+					self.open()
+				}
+
+				Ok(())
+			}
+		}
+	}
+
+	/// Remove the segment at the given index, shifting the following segments.
+	///
+	/// Whether the path is open and absolute is preserved.
+	/// Fails with [`Error::InvalidSegmentIndex`] if there is no segment at this index.
+	pub fn remove_segment(&mut self, index: usize) -> Result<(), Error> {
+		let path = self.as_path();
+		let mut range = path
+			.segment_range(index)
+			.ok_or(Error::InvalidSegmentIndex)?;
+
+		if path.as_bytes().get(range.end) == Some(&b'/') {
+			// remove the following slash.
+			range.end += 1
+		} else if range.start > 1 {
+			// last segment of a closed path, remove the preceding slash.
+			range.start -= 1
+		}
+
+		let offset = self.buffer.p.path_offset();
+		let was_relative = self.is_relative();
+		self.buffer
+			.replace((offset + range.start)..(offset + range.end), &[]);
+		self.buffer.p.path_len -= range.len();
+		self.fix_relative(was_relative);
+		self.disambiguate();
+		Ok(())
+	}
+
+	/// Parse a single, closed, segment.
+	fn parse_segment<'s>(value: &'s str) -> Result<Segment<'s>, Error> {
+		let segment = Segment::try_from(value)?;
+		if segment.is_open() {
+			Err(Error::InvalidSegment)
+		} else {
+			Ok(segment)
+		}
+	}
+
+	/// Make sure that a relative path did not become absolute because of an empty first
+	/// segment, by adding `./` at the begining.
+	fn fix_relative(&mut self, was_relative: bool) {
+		if was_relative && self.is_absolute() {
+			let offset = self.buffer.p.path_offset();
+			self.buffer.replace(offset..offset, b"./");
+			self.buffer.p.path_len += 2;
+		}
+	}
+
+	fn append_segments<'s, I: Iterator<Item = Result<Segment<'s>, Error>>>(
+		&mut self,
+		segments: I,
+	) -> Result<(), Error> {
+		// Segments are pushed into a copy of the IRI reference truncated after its path,
+		// where pushing never moves any query or fragment,
+		// and the new path is then copied back at once.
+		let path_offset = self.buffer.p.path_offset();
+		let end = path_offset + self.buffer.p.path_len;
+		let mut prefix = IriRefBuf {
+			p: self.buffer.p,
+			data: self.buffer.data[0..end].to_vec(),
+		};
+		prefix.p.query_len = None;
+		prefix.p.fragment_len = None;
+
+		let mut path = prefix.path_mut();
+		for segment in segments {
+			path.push(segment?)
+		}
+
+		self.buffer
+			.replace(path_offset..end, &prefix.data[path_offset..]);
+		self.buffer.p.path_len = prefix.p.path_len;
+		Ok(())
+	}
+
 	#[inline]
 	pub fn pop(&mut self) {
 		if !self.is_empty() {
@@ -738,6 +1223,11 @@ impl<'a> PathMut<'a> {
 		}
 	}
 
+	/// Remove all the segments of the path.
+	///
+	/// Whether the path is absolute is preserved: the path becomes `/` if it was absolute,
+	/// and the empty path otherwise.
+	/// The buffer is modified in place, without parsing anything.
 	#[inline]
 	pub fn clear(&mut self) {
 		let mut offset = self.buffer.p.path_offset();
@@ -752,13 +1242,54 @@ impl<'a> PathMut<'a> {
 		self.buffer.p.path_len = offset - self.buffer.p.path_offset();
 	}
 
+	/// Add or remove the leading `/` of the path.
+	///
+	/// The path of an IRI with an authority cannot be relative unless it is empty:
+	/// the `/` is then only removed from the path `/`.
+	/// If removing the `/` would make the path ambiguous, `./` is added at the beginning
+	/// (see [`push`](PathMut::push)).
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// let mut iri_ref = IriRefBuf::new("a/b?q").unwrap();
+	/// iri_ref.path_mut().set_absolute(true);
+	/// assert_eq!(iri_ref, "/a/b?q");
+	///
+	/// let mut iri_ref = IriRefBuf::new("/a:b").unwrap();
+	/// iri_ref.path_mut().set_absolute(false);
+	/// assert_eq!(iri_ref, "./a:b");
+	/// ```
+	pub fn set_absolute(&mut self, absolute: bool) {
+		let offset = self.buffer.p.path_offset();
+
+		if absolute {
+			if self.is_relative() {
+				self.buffer.replace(offset..offset, b"/");
+				self.buffer.p.path_len += 1;
+			}
+		} else if self.is_absolute()
+			&& (self.buffer.p.authority.is_none() || self.as_bytes() == b"/")
+		{
+			self.buffer.replace(offset..(offset + 1), &[]);
+			self.buffer.p.path_len -= 1;
+			self.disambiguate();
+		}
+	}
+
 	#[inline]
 	pub fn symbolic_append<'s, P: IntoIterator<Item = Segment<'s>>>(&mut self, path: P) {
 		for segment in path {
 			match segment.data {
 				b"." => self.open(),
 				b".." => {
-					self.pop();
+					if self.as_path().rsegments().next().map(|s| s.data) == Some(b"..") {
+						// the previous `..` could not be removed.
+						self.push(segment)
+					} else {
+						self.pop();
+					}
+
 					if segment.is_open() {
 						self.open()
 					}
@@ -768,6 +1299,25 @@ impl<'a> PathMut<'a> {
 		}
 	}
 
+	/// Remove the dot segments of the path, in place.
+	///
+	/// The path is rewritten to its normalized form, as used for comparison, following
+	/// [Errata 4547](https://www.rfc-editor.org/errata/eid4547): `..` segments that cannot be
+	/// removed are kept in relative paths, so `a/b/../../../` becomes `../`.
+	/// The rest of the IRI reference is moved accordingly, without reallocating.
+	/// This is useful to clean up a path after a series of [`push`](PathMut::push) calls.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{IriRefBuf, Segment};
+	/// # use std::convert::TryFrom;
+	/// let mut iri_ref = IriRefBuf::new("http://h/a/b?q").unwrap();
+	/// let mut path = iri_ref.path_mut();
+	/// path.push(Segment::try_from("..").unwrap());
+	/// path.push(Segment::try_from("c").unwrap());
+	/// path.normalize();
+	/// assert_eq!(iri_ref.as_str(), "http://h/a/c?q");
+	/// ```
 	#[inline]
 	pub fn normalize(&mut self) {
 		let mut buffer: SmallVec<[u8; REMOVE_DOTS_BUFFER_LEN]> = SmallVec::new();
@@ -941,7 +1491,8 @@ impl<'a> PartialEq<&'a str> for PathBuf {
 
 #[cfg(test)]
 mod tests {
-	use crate::{Iri, IriBuf, IriRefBuf, Path, PathBuf};
+	use crate::{Error, Iri, IriBuf, IriRefBuf, Path, PathBuf, Segment};
+	use pct_str::PctStr;
 	use std::convert::{TryFrom, TryInto};
 
 	#[test]
@@ -1022,6 +1573,13 @@ mod tests {
 		assert_eq!(iri.as_str(), "scheme:foo/bar");
 	}
 
+	#[test]
+	fn push_authority_edge_case() {
+		let mut iri = IriBuf::new("http://example.org?q").unwrap();
+		iri.path_mut().push("a".try_into().unwrap());
+		assert_eq!(iri.as_str(), "http://example.org/a?q");
+	}
+
 	#[test]
 	fn push_empty_segment() {
 		let mut iri = IriBuf::new("scheme:foo/bar").unwrap();
@@ -1062,6 +1620,240 @@ mod tests {
 		assert_eq!(iri_ref.as_str(), "./a:b");
 	}
 
+	#[test]
+	fn normalize_parent_segments() {
+		let inputs = [
+			("../../g", "../../g"),
+			("a/../../../g", "../../g"),
+			("../a/../../b/", "../../b/"),
+			("/a/../../g", "/g"),
+		];
+
+		for (input, expected) in &inputs {
+			let mut iri_ref = IriRefBuf::new(input).unwrap();
+			iri_ref.path_mut().normalize();
+			assert_eq!(iri_ref.as_str(), *expected);
+		}
+	}
+
+	#[test]
+	fn normalize() {
+		let inputs = [
+			("/a/b/../c/./d", "/a/c/d"),
+			("a/b/../../../", "../"),
+			("http://h/a/./b/..?q#f", "http://h/a/?q#f"),
+			("foo:a/..//b", "foo:.//b"),
+			("/.", "/"),
+			("", ""),
+		];
+
+		for (input, expected) in &inputs {
+			let mut iri_ref = IriRefBuf::new(input).unwrap();
+			iri_ref.path_mut().normalize();
+			assert_eq!(iri_ref.as_str(), *expected);
+			assert!(iri_ref.eq_exact(&IriRefBuf::new(expected).unwrap()));
+		}
+
+		let mut iri_ref = IriRefBuf::new("/a").unwrap();
+		let mut path = iri_ref.path_mut();
+		for segment in &["..", "..", "b", ".", "c", "..", "d"] {
+			path.push(Segment::try_from(*segment).unwrap());
+		}
+		path.normalize();
+		assert_eq!(path.as_path(), "/b/d");
+		assert_eq!(iri_ref.as_str(), "/b/d");
+	}
+
+	#[test]
+	fn set_segment() {
+		let mut iri = IriBuf::new("http://example.org/a/b/c?q").unwrap();
+		iri.path_mut().set_segment(1, "{id}").unwrap_err();
+		iri.path_mut().set_segment(1, "42").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a/42/c?q");
+
+		iri.path_mut().set_segment(2, "").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a/42/?q");
+
+		assert!(iri.path_mut().set_segment(1, "d/e").is_err());
+		assert!(matches!(
+			iri.path_mut().set_segment(3, "d"),
+			Err(Error::InvalidSegmentIndex)
+		));
+		assert_eq!(iri.as_str(), "http://example.org/a/42/?q");
+
+		let mut iri = IriBuf::new("scheme:a/b/").unwrap();
+		iri.path_mut().set_segment(1, "c").unwrap();
+		assert_eq!(iri.as_str(), "scheme:a/c/");
+		iri.path_mut().set_segment(0, "").unwrap();
+		assert_eq!(iri.as_str(), "scheme:.//c/");
+
+		let mut iri = IriBuf::new("scheme:/a/b").unwrap();
+		iri.path_mut().set_segment(0, "").unwrap();
+		assert_eq!(iri.as_str(), "scheme:/.//b");
+
+		let mut iri_ref = IriRefBuf::new("a/b").unwrap();
+		iri_ref.path_mut().set_segment(0, "c:d").unwrap();
+		assert_eq!(iri_ref.as_str(), "./c:d/b");
+	}
+
+	#[test]
+	fn insert_segment() {
+		let mut iri = IriBuf::new("http://example.org/a/b/c").unwrap();
+		iri.path_mut().insert_segment(1, "x").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a/x/b/c");
+		iri.path_mut().insert_segment(0, "").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org//a/x/b/c");
+		iri.path_mut().insert_segment(5, "y").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org//a/x/b/c/y");
+		assert!(matches!(
+			iri.path_mut().insert_segment(7, "z"),
+			Err(Error::InvalidSegmentIndex)
+		));
+
+		let mut iri = IriBuf::new("scheme:a/b/").unwrap();
+		iri.path_mut().insert_segment(2, "c").unwrap();
+		assert_eq!(iri.as_str(), "scheme:a/b/c/");
+		iri.path_mut().insert_segment(0, "").unwrap();
+		assert_eq!(iri.as_str(), "scheme:.//a/b/c/");
+
+		let mut iri = IriBuf::new("http://example.org").unwrap();
+		iri.path_mut().insert_segment(0, "a").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a");
+	}
+
+	#[test]
+	fn remove_segment() {
+		let mut iri = IriBuf::new("http://example.org/a/b/c?q").unwrap();
+		iri.path_mut().remove_segment(1).unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a/c?q");
+		iri.path_mut().remove_segment(1).unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a?q");
+		assert!(matches!(
+			iri.path_mut().remove_segment(1),
+			Err(Error::InvalidSegmentIndex)
+		));
+		iri.path_mut().remove_segment(0).unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/?q");
+
+		let mut iri = IriBuf::new("scheme:a/b/").unwrap();
+		iri.path_mut().remove_segment(1).unwrap();
+		assert_eq!(iri.as_str(), "scheme:a/");
+
+		let mut iri = IriBuf::new("scheme:a//b").unwrap();
+		iri.path_mut().remove_segment(0).unwrap();
+		assert_eq!(iri.as_str(), "scheme:.//b");
+
+		let mut iri = IriBuf::new("scheme:/a//b").unwrap();
+		iri.path_mut().remove_segment(0).unwrap();
+		assert_eq!(iri.as_str(), "scheme:/.//b");
+	}
+
+	#[test]
+	fn extend() {
+		let mut iri = IriBuf::new("scheme:foo?q#f").unwrap();
+		iri.path_mut().extend(vec!["bar", "", "baz/"]).unwrap();
+		assert_eq!(iri.as_str(), "scheme:foo/bar//baz/?q#f");
+
+		assert!(iri.path_mut().extend(vec!["a", "b/c"]).is_err());
+		assert!(iri.path_mut().extend(vec!["a", "b?"]).is_err());
+		assert_eq!(iri.as_str(), "scheme:foo/bar//baz/?q#f");
+
+		let mut iri_ref = IriRefBuf::new("").unwrap();
+		iri_ref.path_mut().extend(vec!["a:b", "c"]).unwrap();
+		assert_eq!(iri_ref.as_str(), "./a:b/c");
+	}
+
+	#[test]
+	fn push_str() {
+		let mut iri = IriBuf::new("http://example.org?q#f").unwrap();
+		iri.path_mut().push_str("a/b//c/").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a/b//c/?q#f");
+
+		iri.path_mut().push_str("/d").unwrap();
+		assert_eq!(iri.as_str(), "http://example.org/a/b//c/d?q#f");
+
+		assert!(iri.path_mut().push_str("e?").is_err());
+		assert!(iri.path_mut().push_str("%").is_err());
+		assert_eq!(iri.as_str(), "http://example.org/a/b//c/d?q#f");
+
+		let mut iri = IriBuf::new("scheme:").unwrap();
+		iri.path_mut().push_str("/a").unwrap();
+		assert_eq!(iri.as_str(), "scheme:a");
+	}
+
+	#[test]
+	fn extend_many_segments() {
+		let segments: Vec<String> = (0..1000).map(|i| format!("s{}", i)).collect();
+		let raw = segments.join("/");
+
+		let mut expected = IriBuf::new("http://example.org/root?query#fragment").unwrap();
+		for segment in &segments {
+			expected
+				.path_mut()
+				.push(segment.as_str().try_into().unwrap());
+		}
+
+		let mut iri = IriBuf::new("http://example.org/root?query#fragment").unwrap();
+		iri.reserve(expected.as_str().len() - iri.as_str().len());
+		let capacity = iri.capacity();
+		let ptr = iri.as_bytes().as_ptr();
+		iri.path_mut()
+			.extend(segments.iter().map(String::as_str))
+			.unwrap();
+		assert_eq!(iri.as_str(), expected.as_str());
+		assert_eq!(iri.path().segments().count(), 1001);
+		// The new path is spliced into the buffer at once, without reallocating it.
+		assert_eq!(iri.capacity(), capacity);
+		assert_eq!(iri.as_bytes().as_ptr(), ptr);
+
+		let mut iri = IriBuf::new("http://example.org/root?query#fragment").unwrap();
+		iri.path_mut().push_str(&raw).unwrap();
+		assert_eq!(iri.as_str(), expected.as_str());
+	}
+
+	#[test]
+	fn clear() {
+		let mut iri = IriBuf::new("scheme:/a/b/c?q").unwrap();
+		iri.path_mut().clear();
+		assert_eq!(iri.as_str(), "scheme:/?q");
+		iri.path_mut().push("x".try_into().unwrap());
+		assert_eq!(iri.as_str(), "scheme:/x?q");
+
+		let mut iri = IriBuf::new("scheme:a/b/c/#f").unwrap();
+		iri.path_mut().clear();
+		assert_eq!(iri.as_str(), "scheme:#f");
+		iri.path_mut().push("x".try_into().unwrap());
+		assert_eq!(iri.as_str(), "scheme:x#f");
+
+		let mut iri = IriBuf::new("http://example.org/a/b/c").unwrap();
+		iri.path_mut().clear();
+		assert_eq!(iri.as_str(), "http://example.org/");
+		iri.path_mut().push("x".try_into().unwrap());
+		assert_eq!(iri.as_str(), "http://example.org/x");
+	}
+
+	#[test]
+	fn set_absolute() {
+		let set_absolute = |iri_ref, absolute| {
+			let mut iri_ref = IriRefBuf::new(iri_ref).unwrap();
+			iri_ref.path_mut().set_absolute(absolute);
+			assert!(iri_ref.eq_exact(&IriRefBuf::new(iri_ref.as_str()).unwrap()));
+			iri_ref
+		};
+
+		assert_eq!(set_absolute("a/b", true).as_str(), "/a/b");
+		assert_eq!(set_absolute("/a/b", true).as_str(), "/a/b");
+		assert_eq!(set_absolute("?q", true).as_str(), "/?q");
+		assert_eq!(set_absolute("/a/b#f", false).as_str(), "a/b#f");
+		assert_eq!(set_absolute("a/b", false).as_str(), "a/b");
+		assert_eq!(set_absolute("/a:b", false).as_str(), "./a:b");
+		assert_eq!(set_absolute("s:/a:b", false).as_str(), "s:a:b");
+		assert_eq!(set_absolute("s:/", false).as_str(), "s:");
+		assert_eq!(set_absolute("//h/a", false).as_str(), "//h/a");
+		assert_eq!(set_absolute("//h/", false).as_str(), "//h");
+		assert_eq!(set_absolute("//h", true).as_str(), "//h/");
+	}
+
 	#[test]
 	fn pop() {
 		let mut iri = IriBuf::new("scheme:foo/bar").unwrap();
@@ -1151,6 +1943,46 @@ mod tests {
 		assert_eq!(segments.next(), None);
 	}
 
+	#[test]
+	fn segment_count() {
+		let counts = [
+			("", 0),
+			("/", 0),
+			("/a", 1),
+			("/a/", 1),
+			("/a/b/c", 3),
+			("/a//", 2),
+			("//", 1),
+			("///", 2),
+			("a", 1),
+			("a/b%2Fc/", 2),
+			("a//b", 3),
+			("./", 1),
+		];
+
+		for (input, count) in &counts {
+			let path = Path::try_from(*input).unwrap();
+			assert_eq!(path.segment_count(), *count, "{}", input);
+			assert_eq!(path.segments().count(), *count, "{}", input);
+		}
+	}
+
+	#[test]
+	fn decoded_segments() {
+		use std::borrow::Cow;
+
+		let path = Path::try_from("/a%20b/abc//%C3%A9%FF/").unwrap();
+		let segments: Vec<_> = path.decoded_segments().collect();
+		assert_eq!(segments, ["a b", "abc", "", "é\u{FFFD}"]);
+		assert!(matches!(&segments[0], Cow::Owned(s) if s == "a b"));
+		assert!(matches!(segments[1], Cow::Borrowed("abc")));
+		assert!(matches!(segments[2], Cow::Borrowed("")));
+
+		let reversed: Vec<_> = path.decoded_segments().rev().collect();
+		assert_eq!(reversed, ["é\u{FFFD}", "", "abc", "a b"]);
+		assert_eq!(Path::try_from("/").unwrap().decoded_segments().count(), 0);
+	}
+
 	#[test]
 	fn reverse_segments() {
 		let path = Path::try_from("//a/b/foo//bar/").unwrap();
@@ -1191,7 +2023,90 @@ mod tests {
 	#[test]
 	fn file_name() {
 		let path = Path::try_from("//a/b/foo//bar/").unwrap();
-		assert_eq!(path.file_name().unwrap(), "bar");
+		assert_eq!(path.file_name().unwrap().as_str(), "bar");
+
+		let file_names = [
+			("", None),
+			("/", None),
+			("//", None),
+			("a", Some("a")),
+			("/a/b", Some("b")),
+			("/a/b/", Some("b")),
+			("/a/b//", Some("b")),
+			("a/b%20c", Some("b%20c")),
+			("/a/..", Some("..")),
+		];
+
+		for (path, expected) in &file_names {
+			let path = Path::try_from(*path).unwrap();
+			assert_eq!(path.file_name().map(PctStr::as_str), *expected);
+		}
+	}
+
+	#[test]
+	fn decoded() {
+		let path = Path::try_from("/a%2Fb/%E6%97%A5%E6%9C%AC/%FF%41/").unwrap();
+		assert_eq!(path.decoded(), "/a/b/日本/\u{FFFD}A/");
+
+		let segments: Vec<_> = path.segments().map(|s| s.decoded()).collect();
+		assert_eq!(segments, ["a/b", "日本", "\u{FFFD}A"]);
+	}
+
+	#[test]
+	fn extension() {
+		let extensions = [
+			("", None),
+			("/", None),
+			("file", None),
+			("/a/file.txt", Some("txt")),
+			("archive.tar.gz", Some("gz")),
+			("file.", Some("")),
+			("/a.d/", None),
+			("/a.d//", None),
+			("/a/.hidden", None),
+			("/a/.hidden.txt", Some("txt")),
+			("/a/.", None),
+			("/a/..", None),
+			("/a/b%2Etxt", None),
+			("/a/b%2E.txt", Some("txt")),
+		];
+
+		for (path, expected) in &extensions {
+			let path = Path::try_from(*path).unwrap();
+			assert_eq!(path.extension(), *expected);
+		}
+	}
+
+	#[test]
+	fn rsegments() {
+		let path = Path::try_from("/a/b//c/").unwrap();
+		let segments: Vec<_> = path.rsegments().map(|s| s.into_str()).collect();
+		assert_eq!(segments, ["c", "", "b", "a"]);
+
+		let mut segments = path.rsegments();
+		assert_eq!(segments.next().unwrap(), "c");
+		assert_eq!(segments.next_back().unwrap(), "a");
+		assert_eq!(segments.next().unwrap(), "");
+		assert_eq!(segments.next_back().unwrap(), "b");
+		assert_eq!(segments.next(), None);
+
+		assert_eq!(Path::try_from("/").unwrap().rsegments().next(), None);
+	}
+
+	#[test]
+	fn segment() {
+		let path = Path::try_from("/a/b//c/").unwrap();
+		assert_eq!(path.segment(0).unwrap(), "a");
+		assert_eq!(path.segment(1).unwrap(), "b");
+		assert_eq!(path.segment(2).unwrap(), "");
+		assert_eq!(path.segment(3).unwrap(), "c");
+		assert!(path.segment(3).unwrap().is_open());
+		assert_eq!(path.segment(4), None);
+
+		let path = Path::try_from("/a//").unwrap();
+		assert_eq!(path.segment(0).unwrap(), "a");
+		assert_eq!(path.segment(1).unwrap(), "");
+		assert_eq!(path.segment(2), None);
 	}
 
 	#[test]
@@ -1,9 +1,9 @@
 use super::Error;
 use crate::parsing;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::{cmp, fmt};
 
 #[derive(Clone, Copy)]
 pub struct Port<'a> {
@@ -21,7 +21,7 @@ impl<'a> Port<'a> {
 	/// Get the underlying port slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(&self.data) }
+		unsafe { core::str::from_utf8_unchecked(&self.data) }
 	}
 
 	/// Checks if the port is empty.
@@ -29,6 +29,15 @@ impl<'a> Port<'a> {
 	pub fn is_empty(&self) -> bool {
 		self.data.is_empty()
 	}
+
+	/// Get the port number.
+	///
+	/// Returns `None` if the port is empty or does not fit in a `u16`
+	/// (the syntax allows any number of digits).
+	#[inline]
+	pub fn as_u16(&self) -> Option<u16> {
+		self.as_str().parse().ok()
+	}
 }
 
 impl<'a> AsRef<[u8]> for Port<'a> {
@@ -1,10 +1,11 @@
 use super::Error;
 use crate::parsing;
-use pct_str::PctStr;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::{cmp, fmt};
+use pct_str::{Encoder, IriReserved, PctStr};
 
 #[derive(Clone, Copy)]
 pub struct Query<'a> {
@@ -22,7 +23,7 @@ impl<'a> Query<'a> {
 	/// Get the underlying query slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(&self.data) }
+		unsafe { core::str::from_utf8_unchecked(&self.data) }
 	}
 
 	/// Get the underlying query slice as a percent-encoded string slice.
@@ -36,6 +37,202 @@ impl<'a> Query<'a> {
 	pub fn is_empty(&self) -> bool {
 		self.data.is_empty()
 	}
+
+	/// Percent-decode the whole query.
+	///
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`, and `+` is kept as is.
+	/// Note that decoded `&` and `=` cannot be distinguished from pair delimiters:
+	/// use [`pairs`](Query::pairs) to decode each key and value separately.
+	#[inline]
+	pub fn decoded(&self) -> String {
+		decode(self.data, false).into_owned()
+	}
+
+	/// Checks if the percent-decoded query contains `needle`.
+	///
+	/// This is the same as `query.decoded().contains(needle)`, without allocating,
+	/// except that decoded bytes that are not valid UTF-8 are compared as is.
+	/// As with [`decoded`](Query::decoded), `+` is not decoded as a space.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// use iref::Query;
+	///
+	/// let query = Query::try_from("tok%65n=a%20b").unwrap();
+	/// assert!(query.contains_decoded("token"));
+	/// assert!(query.contains_decoded("=a b"));
+	/// assert!(!query.contains_decoded("%65"));
+	/// ```
+	#[inline]
+	pub fn contains_decoded(&self, needle: &str) -> bool {
+		crate::pct_contains(self.data, needle)
+	}
+
+	/// Iterate over the `key=value` pairs of the query.
+	///
+	/// The query is split on `&`, then each pair on its first `=`.
+	/// Both the key and value are percent-decoded, but `+` is kept as is
+	/// (see [`form_pairs`](Query::form_pairs) to decode it as a space).
+	/// A key without `=` has no value, and empty pairs (as in `a=1&&b=2`) are skipped.
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use iref::Query;
+	/// let query = Query::try_from("a=1&b&c=x%3Dy").unwrap();
+	/// let pairs: Vec<_> = query.pairs().collect();
+	/// assert_eq!(pairs[0], ("a".into(), Some("1".into())));
+	/// assert_eq!(pairs[1], ("b".into(), None));
+	/// assert_eq!(pairs[2], ("c".into(), Some("x=y".into())));
+	/// ```
+	#[inline]
+	pub fn pairs(&self) -> QueryPairs<'a> {
+		QueryPairs {
+			data: self.data,
+			plus_as_space: false,
+		}
+	}
+
+	/// Iterate over the `key=value` pairs of the query, decoding `+` as a space.
+	///
+	/// This is the `application/x-www-form-urlencoded` convention used by HTML forms.
+	/// Apart from the `+` handling, this is the same as [`pairs`](Query::pairs).
+	#[inline]
+	pub fn form_pairs(&self) -> QueryPairs<'a> {
+		QueryPairs {
+			data: self.data,
+			plus_as_space: true,
+		}
+	}
+}
+
+impl<'a> Query<'a> {
+	/// Split the query on `&`, returning each raw pair along with its decoded key.
+	///
+	/// Contrarily to [`Query::pairs`], empty pairs are not skipped.
+	pub(crate) fn raw_pairs(&self) -> Vec<(&'a str, Cow<'a, str>)> {
+		let data: &'a [u8] = self.data;
+		if data.is_empty() {
+			return Vec::new();
+		}
+
+		data.split(|b| *b == b'&')
+			.map(|pair| {
+				let key = match pair.iter().position(|b| *b == b'=') {
+					Some(i) => &pair[..i],
+					None => pair,
+				};
+
+				(
+					unsafe { core::str::from_utf8_unchecked(pair) },
+					decode(key, false),
+				)
+			})
+			.collect()
+	}
+
+	/// Percent-encode the given key and value into a `key=value` query pair.
+	pub(crate) fn encode_pair(key: &str, value: Option<&str>) -> String {
+		let mut pair = String::new();
+		crate::pct_encode(key, PairEncoder, &mut pair);
+		if let Some(value) = value {
+			pair.push('=');
+			crate::pct_encode(value, PairEncoder, &mut pair);
+		}
+
+		pair
+	}
+}
+
+/// Encode characters that are not allowed in a query,
+/// or that are delimiters of query pairs.
+struct PairEncoder;
+
+impl Encoder for PairEncoder {
+	fn encode(&self, c: char) -> bool {
+		matches!(c, '&' | '=' | '+') || IriReserved::Query.encode(c)
+	}
+}
+
+/// Iterator over the `key=value` pairs of a [`Query`].
+///
+/// See [`Query::pairs`] and [`Query::form_pairs`].
+#[derive(Clone)]
+pub struct QueryPairs<'a> {
+	/// The remaining query slice.
+	data: &'a [u8],
+
+	/// Decode `+` as a space.
+	plus_as_space: bool,
+}
+
+impl<'a> Iterator for QueryPairs<'a> {
+	type Item = (Cow<'a, str>, Option<Cow<'a, str>>);
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		while !self.data.is_empty() {
+			let (pair, rest) = match self.data.iter().position(|b| *b == b'&') {
+				Some(i) => (&self.data[..i], &self.data[(i + 1)..]),
+				None => (self.data, &[][..]),
+			};
+
+			self.data = rest;
+
+			if !pair.is_empty() {
+				return Some(decode_pair(pair, self.plus_as_space));
+			}
+		}
+
+		None
+	}
+}
+
+/// Split a raw `key=value` query pair, and percent-decode its key and value.
+pub(crate) fn decode_pair<'a>(
+	pair: &'a [u8],
+	plus_as_space: bool,
+) -> (Cow<'a, str>, Option<Cow<'a, str>>) {
+	let (key, value) = match pair.iter().position(|b| *b == b'=') {
+		Some(i) => (&pair[..i], Some(&pair[(i + 1)..])),
+		None => (pair, None),
+	};
+
+	(
+		decode(key, plus_as_space),
+		value.map(|value| decode(value, plus_as_space)),
+	)
+}
+
+/// Percent-decode a valid query slice.
+fn decode<'a>(data: &'a [u8], plus_as_space: bool) -> Cow<'a, str> {
+	if !data
+		.iter()
+		.any(|b| *b == b'%' || (plus_as_space && *b == b'+'))
+	{
+		return Cow::Borrowed(unsafe { core::str::from_utf8_unchecked(data) });
+	}
+
+	let hex = |b: u8| (b as char).to_digit(16).unwrap() as u8;
+	let mut bytes = Vec::with_capacity(data.len());
+	let mut i = 0;
+	while i < data.len() {
+		match data[i] {
+			b'%' => {
+				bytes.push(hex(data[i + 1]) << 4 | hex(data[i + 2]));
+				i += 3;
+				continue;
+			}
+			b'+' if plus_as_space => bytes.push(b' '),
+			b => bytes.push(b),
+		}
+
+		i += 1;
+	}
+
+	Cow::Owned(String::from_utf8_lossy(&bytes).into_owned())
 }
 
 impl<'a> AsRef<[u8]> for Query<'a> {
@@ -109,3 +306,88 @@ impl<'a> Hash for Query<'a> {
 		self.as_pct_str().hash(hasher)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pairs(query: &str) -> Vec<(String, Option<String>)> {
+		Query::try_from(query)
+			.unwrap()
+			.pairs()
+			.map(|(k, v)| (k.into_owned(), v.map(Cow::into_owned)))
+			.collect()
+	}
+
+	fn pair(key: &str, value: Option<&str>) -> (String, Option<String>) {
+		(key.to_string(), value.map(str::to_string))
+	}
+
+	#[test]
+	fn pairs_splitting() {
+		assert_eq!(pairs(""), vec![]);
+		assert_eq!(
+			pairs("a=1&&b=2&"),
+			vec![pair("a", Some("1")), pair("b", Some("2"))]
+		);
+		assert_eq!(
+			pairs("key&empty=&a=b=c"),
+			vec![
+				pair("key", None),
+				pair("empty", Some("")),
+				pair("a", Some("b=c"))
+			]
+		);
+	}
+
+	#[test]
+	fn pairs_decoding() {
+		assert_eq!(pairs("k%3Dy=v%26w"), vec![pair("k=y", Some("v&w"))]);
+		assert_eq!(pairs("a+b=c+d"), vec![pair("a+b", Some("c+d"))]);
+		assert_eq!(pairs("bad=%FF"), vec![pair("bad", Some("\u{FFFD}"))]);
+
+		let query = Query::try_from("a+b=c+d%2B").unwrap();
+		let form: Vec<_> = query.form_pairs().collect();
+		assert_eq!(form, vec![("a b".into(), Some("c d+".into()))]);
+	}
+
+	#[test]
+	fn pairs_borrowing() {
+		let query = Query::try_from("a=1&b=%20").unwrap();
+		let mut pairs = query.pairs();
+		assert!(matches!(
+			pairs.next(),
+			Some((Cow::Borrowed("a"), Some(Cow::Borrowed("1"))))
+		));
+		assert!(matches!(
+			pairs.next(),
+			Some((Cow::Borrowed("b"), Some(Cow::Owned(_))))
+		));
+		assert!(pairs.next().is_none());
+	}
+
+	#[test]
+	fn contains_decoded() {
+		let query = Query::try_from("tok%65n=x&%C3%A9t%C3%A9=a+b%20c").unwrap();
+		assert!(query.contains_decoded("token"));
+		assert!(query.contains_decoded("token=x"));
+		assert!(query.contains_decoded("été"));
+		assert!(query.contains_decoded("a+b c"));
+		assert!(query.contains_decoded(""));
+		assert!(query.contains_decoded("c"));
+		assert!(!query.contains_decoded("%65"));
+		assert!(!query.contains_decoded("a b"));
+		assert!(!query.contains_decoded("c "));
+
+		for needle in &["ok", "n=x&é", "t%C3%A9", "x&&"] {
+			assert_eq!(
+				query.contains_decoded(needle),
+				query.decoded().contains(needle)
+			);
+		}
+
+		let empty = Query::try_from("").unwrap();
+		assert!(empty.contains_decoded(""));
+		assert!(!empty.contains_decoded("a"));
+	}
+}
@@ -0,0 +1,283 @@
+use alloc::vec::Vec;
+
+use crate::parsing::ParsedIriRef;
+use crate::{Iri, IriBuf, IriRef, IriRefBuf, Path, Segment};
+
+/// Resolver of IRI references against a fixed base IRI.
+///
+/// Returned by [`Iri::resolver`].
+/// The scheme, authority and directory of the base IRI, to which relative paths are
+/// appended, are copied once into a prefix reused for every resolved reference,
+/// and each result is built in a single allocation.
+/// The result is the same as with [`IriRef::resolved`].
+///
+/// # Example
+/// ```
+/// # use iref::{Iri, IriRef};
+/// let base = Iri::new("http://a/b/c/d;p?q").unwrap();
+/// let resolver = base.resolver();
+///
+/// assert_eq!(resolver.resolve(IriRef::new("g").unwrap()), "http://a/b/c/g");
+/// assert_eq!(resolver.resolve(IriRef::new("../g").unwrap()), "http://a/b/g");
+///
+/// let links = ["g?y", "#s", "//g"].iter().map(|link| IriRef::new(link).unwrap());
+/// let resolved: Vec<_> = resolver.resolve_all(links).collect();
+/// assert_eq!(resolved, ["http://a/b/c/g?y", "http://a/b/c/d;p?q#s", "http://g"]);
+/// ```
+#[derive(Clone)]
+pub struct Resolver<'a> {
+	base: Iri<'a>,
+
+	/// Scheme, authority and directory of the base IRI.
+	prefix: Vec<u8>,
+}
+
+impl<'a> Resolver<'a> {
+	pub(crate) fn new(base: Iri<'a>) -> Resolver<'a> {
+		let path_offset = base.p.path_offset();
+		let mut prefix = Vec::with_capacity(path_offset + base.p.path_len);
+		prefix.extend_from_slice(&base.data[..path_offset]);
+
+		if base.authority().is_some() && base.path().is_empty() {
+			prefix.push(b'/');
+		} else {
+			prefix.extend_from_slice(base.path().directory().as_bytes());
+		}
+
+		Resolver { base, prefix }
+	}
+
+	/// Returns the base IRI.
+	#[inline]
+	pub fn base(&self) -> Iri<'a> {
+		self.base
+	}
+
+	/// Resolve the given IRI reference against the base IRI.
+	pub fn resolve<'b, R: Into<IriRef<'b>>>(&self, reference: R) -> IriBuf {
+		let reference = reference.into();
+		let offset = reference.p.path_offset();
+		let path = Path {
+			data: &reference.data[offset..(offset + reference.p.path_len)],
+		};
+
+		if reference.scheme().is_some()
+			|| reference.authority().is_some()
+			|| path.is_absolute()
+			|| path.is_empty()
+		{
+			// The base path is either ignored or copied verbatim.
+			return reference.resolved(self.base);
+		}
+
+		let mut data = Vec::with_capacity(self.prefix.len() + reference.data.len() - offset);
+		data.extend_from_slice(&self.prefix);
+
+		let path_offset = self.base.p.path_offset();
+		let mut merged = MergedPath {
+			data: &mut data,
+			start: path_offset,
+		};
+		merged.symbolic_append(path);
+		if path.is_open() {
+			merged.open();
+		}
+
+		let path_len = data.len() - path_offset;
+		let query = reference.query();
+		let fragment = reference.fragment();
+
+		if let Some(query) = query {
+			data.push(b'?');
+			data.extend_from_slice(query.as_bytes());
+		}
+
+		if let Some(fragment) = fragment {
+			data.push(b'#');
+			data.extend_from_slice(fragment.as_bytes());
+		}
+
+		let p = ParsedIriRef {
+			scheme_len: self.base.p.scheme_len,
+			authority: self.base.p.authority,
+			path_len,
+			query_len: query.map(|q| q.as_bytes().len()),
+			fragment_len: fragment.map(|f| f.as_bytes().len()),
+		};
+
+		let mut iri_ref = IriRefBuf { p, data };
+		if p.authority.is_none() {
+			iri_ref.path_mut().disambiguate();
+		}
+
+		IriBuf(iri_ref)
+	}
+
+	/// Resolve each of the given IRI references against the base IRI.
+	#[inline]
+	pub fn resolve_all<'s, 'b, I>(&'s self, references: I) -> impl Iterator<Item = IriBuf> + 's
+	where
+		I: IntoIterator + 's,
+		I::Item: Into<IriRef<'b>>,
+	{
+		references
+			.into_iter()
+			.map(move |reference| self.resolve(reference))
+	}
+}
+
+/// Path at the end of a buffer, starting at `start`.
+///
+/// Same as [`PathMut::symbolic_append`](crate::PathMut::symbolic_append) on a path
+/// without scheme nor authority, as done by [`IriRefBuf::resolve`],
+/// but only ever appending to or truncating the buffer.
+struct MergedPath<'v> {
+	data: &'v mut Vec<u8>,
+	start: usize,
+}
+
+impl<'v> MergedPath<'v> {
+	fn path(&self) -> Path<'_> {
+		Path {
+			data: &self.data[self.start..],
+		}
+	}
+
+	fn open(&mut self) {
+		let path = self.path();
+		if !path.is_empty() && path.is_closed() {
+			self.data.push(b'/')
+		}
+	}
+
+	fn push(&mut self, segment: Segment) {
+		if segment.is_empty() {
+			if self.path().is_empty() {
+				self.push(Segment::current())
+			}
+
+			self.open();
+			self.data.push(b'/');
+		} else {
+			let path = self.path();
+			if path.is_relative() && path.is_empty() && segment.as_ref().contains(&b':') {
+				self.push(Segment::current())
+			}
+
+			self.open();
+			self.data.extend_from_slice(segment.as_ref());
+		}
+
+		if segment.is_open() {
+			self.open()
+		}
+	}
+
+	fn pop(&mut self) {
+		let path = self.path();
+		if !path.is_empty() {
+			let data = path.as_bytes();
+			let mut start = data.len() - 1;
+
+			// We remove the terminating `/`.
+			if path.is_open() {
+				start -= 1;
+			}
+
+			// Find the last segment start position.
+			while start > 0 && data[start] != b'/' {
+				start -= 1;
+			}
+
+			if start > 0 || data[start] == b'/' {
+				start += 1;
+			}
+
+			self.data.truncate(self.start + start);
+		} else if path.is_relative() {
+			self.push(Segment::parent())
+		}
+	}
+
+	fn symbolic_append(&mut self, path: Path) {
+		for segment in path {
+			match segment.as_ref() {
+				b"." => self.open(),
+				b".." => {
+					if self.path().rsegments().next().map(|s| s.data) == Some(b"..") {
+						// the previous `..` could not be removed.
+						self.push(segment)
+					} else {
+						self.pop()
+					}
+
+					if segment.is_open() {
+						self.open()
+					}
+				}
+				_ => self.push(segment),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parsing::ParsedIriRef;
+	use crate::test_vectors::RFC3986_RESOLUTION_EXAMPLES;
+	use crate::{Iri, IriRef, IriRefBuf};
+
+	#[test]
+	fn same_as_resolved() {
+		let mut references: Vec<&str> = RFC3986_RESOLUTION_EXAMPLES
+			.iter()
+			.map(|(_, reference, _)| *reference)
+			.collect();
+		references.extend(&["a:b", "./c:d", "x/../..//y", "", "//", "?", "#", "%41/%2e"]);
+		references.extend(&["../..", "../../x/", "..//", ".//a", "a/../b:c"]);
+
+		let bases = [
+			"http://a/b/c/d;p?q",
+			"http://a",
+			"http://a?q#f",
+			"foo:a/b",
+			"foo:a",
+			"foo:",
+			"foo:/a",
+			"foo:/a/b/",
+			"foo://a/",
+		];
+
+		for base in &bases {
+			let base = Iri::new(base).unwrap();
+			let resolver = base.resolver();
+
+			for reference in &references {
+				let reference = IriRef::new(reference).unwrap();
+				let expected = reference.resolved(base);
+				let resolved = resolver.resolve(reference);
+				assert!(
+					resolved.eq_exact(&expected),
+					"resolving {} against {}: {} instead of {}",
+					reference,
+					base,
+					resolved,
+					expected
+				);
+				assert_eq!(
+					format!("{:?}", resolved.as_iri_ref().parsing_data()),
+					format!("{:?}", ParsedIriRef::new(resolved.as_str()).unwrap())
+				);
+			}
+
+			let references: Vec<_> = references
+				.iter()
+				.map(|r| IriRefBuf::new(r).unwrap())
+				.collect();
+			assert!(resolver
+				.resolve_all(&references)
+				.zip(&references)
+				.all(|(resolved, reference)| resolved.eq_exact(&reference.resolved(base))));
+		}
+	}
+}
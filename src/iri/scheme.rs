@@ -1,10 +1,13 @@
 use super::Error;
 use crate::parsing;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::{cmp, fmt};
 
+/// IRI scheme.
+///
+/// Schemes are case-insensitive: comparison, ordering and hashing ignore ASCII case.
 #[derive(Clone, Copy)]
 pub struct Scheme<'a> {
 	/// The scheme slice.
@@ -21,7 +24,7 @@ impl<'a> Scheme<'a> {
 	/// Get the underlying scheme slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(&self.data) }
+		unsafe { core::str::from_utf8_unchecked(&self.data) }
 	}
 
 	/// Checks if the scheme is empty.
@@ -29,8 +32,19 @@ impl<'a> Scheme<'a> {
 	pub fn is_empty(&self) -> bool {
 		self.data.is_empty()
 	}
+
+	/// Default port of the scheme, ignoring case, if known.
+	pub(crate) fn default_port(&self) -> Option<u16> {
+		DEFAULT_PORTS
+			.iter()
+			.find(|(name, _)| self.as_str().eq_ignore_ascii_case(name))
+			.map(|(_, port)| *port)
+	}
 }
 
+/// Default ports of the schemes that have one.
+const DEFAULT_PORTS: [(&str, u16); 3] = [("http", 80), ("https", 443), ("ftp", 21)];
+
 impl<'a> AsRef<[u8]> for Scheme<'a> {
 	#[inline]
 	fn as_ref(&self) -> &[u8] {
@@ -69,7 +83,7 @@ impl<'a> fmt::Debug for Scheme<'a> {
 impl<'a> cmp::PartialEq for Scheme<'a> {
 	#[inline]
 	fn eq(&self, other: &Scheme) -> bool {
-		self.as_str() == other.as_str()
+		self.as_bytes().eq_ignore_ascii_case(other.as_bytes())
 	}
 }
 
@@ -85,20 +99,190 @@ impl<'a> PartialOrd for Scheme<'a> {
 impl<'a> Ord for Scheme<'a> {
 	#[inline]
 	fn cmp(&self, other: &Scheme<'a>) -> Ordering {
-		self.as_str().cmp(other.as_str())
+		self.as_bytes()
+			.iter()
+			.map(u8::to_ascii_lowercase)
+			.cmp(other.as_bytes().iter().map(u8::to_ascii_lowercase))
 	}
 }
 
 impl<'a> cmp::PartialEq<&'a str> for Scheme<'a> {
 	#[inline]
 	fn eq(&self, other: &&'a str) -> bool {
-		self.as_str() == *other
+		self.as_str().eq_ignore_ascii_case(other)
 	}
 }
 
 impl<'a> Hash for Scheme<'a> {
 	#[inline]
 	fn hash<H: Hasher>(&self, hasher: &mut H) {
-		self.as_str().hash(hasher)
+		for b in self.as_bytes() {
+			b.to_ascii_lowercase().hash(hasher)
+		}
+	}
+}
+
+/// Kind of [`Scheme`], for the most common schemes.
+///
+/// Returned by [`Scheme::kind`].
+/// This is purely advisory: parsing and comparison do not depend on the scheme kind.
+#[cfg(feature = "schemes")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SchemeKind {
+	/// `http` scheme.
+	Http,
+
+	/// `https` scheme.
+	Https,
+
+	/// `ftp` scheme.
+	Ftp,
+
+	/// `mailto` scheme.
+	Mailto,
+
+	/// `file` scheme.
+	File,
+
+	/// `urn` scheme.
+	Urn,
+
+	/// `data` scheme.
+	Data,
+
+	/// Any other scheme.
+	Other,
+}
+
+/// Names of the scheme kinds, in lowercase.
+#[cfg(feature = "schemes")]
+const KINDS: [(&str, SchemeKind); 7] = [
+	("http", SchemeKind::Http),
+	("https", SchemeKind::Https),
+	("ftp", SchemeKind::Ftp),
+	("mailto", SchemeKind::Mailto),
+	("file", SchemeKind::File),
+	("urn", SchemeKind::Urn),
+	("data", SchemeKind::Data),
+];
+
+#[cfg(feature = "schemes")]
+impl SchemeKind {
+	/// Checks if the scheme is `http` or `https`.
+	#[inline]
+	pub fn is_http_like(&self) -> bool {
+		matches!(self, SchemeKind::Http | SchemeKind::Https)
+	}
+
+	/// Returns the default port of the scheme, if any.
+	#[inline]
+	pub fn default_port(&self) -> Option<u16> {
+		let (name, _) = KINDS.iter().find(|(_, kind)| kind == self)?;
+		DEFAULT_PORTS
+			.iter()
+			.find(|(default, _)| default == name)
+			.map(|(_, port)| *port)
+	}
+}
+
+#[cfg(feature = "schemes")]
+impl<'a> Scheme<'a> {
+	/// Classify the scheme among the most common ones, ignoring case.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Iri, SchemeKind};
+	/// let iri = Iri::new("HTTPS://example.org/").unwrap();
+	/// let kind = iri.scheme().kind();
+	/// assert_eq!(kind, SchemeKind::Https);
+	/// assert!(kind.is_http_like());
+	/// assert_eq!(kind.default_port(), Some(443));
+	///
+	/// let iri = Iri::new("urn:isbn:0451450523").unwrap();
+	/// assert_eq!(iri.scheme().kind(), SchemeKind::Urn);
+	/// assert_eq!(iri.scheme().kind().default_port(), None);
+	/// ```
+	pub fn kind(&self) -> SchemeKind {
+		KINDS
+			.iter()
+			.find(|(name, _)| self.as_str().eq_ignore_ascii_case(name))
+			.map(|(_, kind)| *kind)
+			.unwrap_or(SchemeKind::Other)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_port() {
+		let ports = [
+			("http", Some(80)),
+			("HTTP", Some(80)),
+			("https", Some(443)),
+			("HttpS", Some(443)),
+			("ftp", Some(21)),
+			("FTP", Some(21)),
+			("ftps", None),
+			("mailto", None),
+			("foo", None),
+		];
+
+		for (scheme, port) in &ports {
+			let scheme = Scheme::try_from(*scheme).unwrap();
+			assert_eq!(scheme.default_port(), *port, "default port of {}", scheme);
+		}
+	}
+
+	#[cfg(feature = "schemes")]
+	#[test]
+	fn kind() {
+		let kinds = [
+			("http", SchemeKind::Http),
+			("HTTP", SchemeKind::Http),
+			("https", SchemeKind::Https),
+			("hTTpS", SchemeKind::Https),
+			("ftp", SchemeKind::Ftp),
+			("Ftp", SchemeKind::Ftp),
+			("mailto", SchemeKind::Mailto),
+			("MAILTO", SchemeKind::Mailto),
+			("file", SchemeKind::File),
+			("File", SchemeKind::File),
+			("urn", SchemeKind::Urn),
+			("URN", SchemeKind::Urn),
+			("data", SchemeKind::Data),
+			("Data", SchemeKind::Data),
+			("foo", SchemeKind::Other),
+			("http+unix", SchemeKind::Other),
+			("htt", SchemeKind::Other),
+			("httpss", SchemeKind::Other),
+		];
+
+		for (scheme, kind) in &kinds {
+			let scheme = Scheme::try_from(*scheme).unwrap();
+			assert_eq!(scheme.kind(), *kind, "kind of {}", scheme);
+			assert_eq!(scheme.kind().default_port(), scheme.default_port());
+		}
+	}
+
+	#[cfg(feature = "schemes")]
+	#[test]
+	fn kind_properties() {
+		let kinds = [
+			(SchemeKind::Http, true, Some(80)),
+			(SchemeKind::Https, true, Some(443)),
+			(SchemeKind::Ftp, false, Some(21)),
+			(SchemeKind::Mailto, false, None),
+			(SchemeKind::File, false, None),
+			(SchemeKind::Urn, false, None),
+			(SchemeKind::Data, false, None),
+			(SchemeKind::Other, false, None),
+		];
+
+		for (kind, http_like, port) in &kinds {
+			assert_eq!(kind.is_http_like(), *http_like, "{:?} is HTTP-like", kind);
+			assert_eq!(kind.default_port(), *port, "default port of {:?}", kind);
+		}
 	}
 }
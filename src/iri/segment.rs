@@ -1,10 +1,11 @@
 use super::Error;
 use crate::parsing;
+use alloc::string::String;
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::{cmp, fmt};
 use pct_str::PctStr;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
 
 #[derive(Clone, Copy)]
 pub struct Segment<'a> {
@@ -48,13 +49,13 @@ impl<'a> Segment<'a> {
 	/// Get the underlying segment slice as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(self.data) }
+		unsafe { core::str::from_utf8_unchecked(self.data) }
 	}
 
 	/// Get the underlying segment slice as a string slice by consuming the segment reference.
 	#[inline]
 	pub fn into_str(self) -> &'a str {
-		unsafe { std::str::from_utf8_unchecked(self.data) }
+		unsafe { core::str::from_utf8_unchecked(self.data) }
 	}
 
 	/// Get the underlying segment slice as a percent-encoded string slice.
@@ -63,6 +64,22 @@ impl<'a> Segment<'a> {
 		unsafe { PctStr::new_unchecked(self.as_str()) }
 	}
 
+	/// Percent-decode the segment.
+	///
+	/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+	#[inline]
+	pub fn decoded(&self) -> String {
+		crate::pct_decode_lossy(self.as_pct_str())
+	}
+
+	/// Checks if the percent-decoded segment contains `needle`, without allocating.
+	///
+	/// See [`Query::contains_decoded`](crate::Query::contains_decoded).
+	#[inline]
+	pub fn contains_decoded(&self, needle: &str) -> bool {
+		crate::pct_contains(self.data, needle)
+	}
+
 	#[inline]
 	pub fn is_open(&self) -> bool {
 		self.open
@@ -158,13 +175,16 @@ impl<'a> PartialOrd for Segment<'a> {
 impl<'a> Ord for Segment<'a> {
 	#[inline]
 	fn cmp(&self, other: &Segment<'a>) -> Ordering {
-		self.as_pct_str().cmp(other.as_pct_str())
+		self.as_pct_str()
+			.cmp(other.as_pct_str())
+			.then_with(|| self.open.cmp(&other.open))
 	}
 }
 
 impl<'a> Hash for Segment<'a> {
 	#[inline]
 	fn hash<H: Hasher>(&self, hasher: &mut H) {
+		self.open.hash(hasher);
 		self.as_pct_str().hash(hasher)
 	}
 }
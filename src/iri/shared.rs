@@ -0,0 +1,313 @@
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use ::bytes::Bytes;
+
+use crate::parsing::ParsedIriRef;
+use crate::{
+	iri::Iri, AsIri, AsIriRef, Authority, Error, Fragment, IriBuf, IriRef, IriRefBuf, Path, Query,
+	Scheme,
+};
+
+/// Owned IRI sharing its buffer with other values, through a [`Bytes`].
+///
+/// Requires the `bytes` feature.
+/// Cloning a `SharedIriBuf` only increments a reference count,
+/// and many IRIs can be parsed out of a single large buffer without copying them,
+/// using [`Bytes::slice`].
+/// It cannot be modified: convert it into an [`IriBuf`] to do so.
+///
+/// # Example
+/// ```
+/// # use bytes::Bytes;
+/// # use iref::SharedIriBuf;
+/// let buffer = Bytes::from_static(b"<http://a/b> <http://c/d#e> .");
+/// let a = SharedIriBuf::new(buffer.slice(1..11)).unwrap();
+/// let b = SharedIriBuf::new(buffer.slice(14..26)).unwrap();
+///
+/// assert_eq!(a, "http://a/b");
+/// assert_eq!(b.fragment().unwrap(), "e");
+/// assert_eq!(a.as_bytes().as_ptr(), buffer[1..].as_ptr());
+/// ```
+#[derive(Clone)]
+pub struct SharedIriBuf {
+	p: ParsedIriRef,
+	data: Bytes,
+}
+
+impl SharedIriBuf {
+	/// Parse the given buffer as an IRI, without copying it.
+	#[inline]
+	pub fn new(data: Bytes) -> Result<SharedIriBuf, Error> {
+		let p = Iri::new(&data[..])?.p;
+		Ok(SharedIriBuf { p, data })
+	}
+
+	#[inline]
+	pub fn as_iri(&self) -> Iri<'_> {
+		Iri(self.as_iri_ref())
+	}
+
+	#[inline]
+	pub fn as_iri_ref(&self) -> IriRef<'_> {
+		IriRef {
+			p: self.p,
+			data: &self.data,
+		}
+	}
+
+	/// Length in bytes.
+	///
+	/// An IRI is never empty, as it has a scheme.
+	#[allow(clippy::len_without_is_empty)]
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.data.len()
+	}
+
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.data
+	}
+
+	#[inline]
+	pub fn as_str(&self) -> &str {
+		unsafe { core::str::from_utf8_unchecked(&self.data) }
+	}
+
+	/// Returns the underlying shared buffer.
+	#[inline]
+	pub fn into_bytes(self) -> Bytes {
+		self.data
+	}
+
+	/// Get the scheme of the IRI.
+	#[inline]
+	pub fn scheme(&self) -> Scheme<'_> {
+		Scheme {
+			data: &self.data[0..self.p.scheme_len.unwrap()],
+		}
+	}
+
+	/// Get the authority of the IRI, if any.
+	#[inline]
+	pub fn authority(&self) -> Option<Authority<'_>> {
+		let offset = self.p.authority_offset();
+		self.p.authority.map(|authority| Authority {
+			data: &self.data[offset..(offset + authority.len())],
+			p: authority,
+		})
+	}
+
+	/// Get the path of the IRI.
+	#[inline]
+	pub fn path(&self) -> Path<'_> {
+		let offset = self.p.path_offset();
+		Path {
+			data: &self.data[offset..(offset + self.p.path_len)],
+		}
+	}
+
+	/// Get the query of the IRI, if any.
+	#[inline]
+	pub fn query(&self) -> Option<Query<'_>> {
+		let offset = self.p.query_offset();
+		self.p.query_len.map(|len| Query {
+			data: &self.data[offset..(offset + len)],
+		})
+	}
+
+	/// Get the fragment of the IRI, if any.
+	#[inline]
+	pub fn fragment(&self) -> Option<Fragment<'_>> {
+		let offset = self.p.fragment_offset();
+		self.p.fragment_len.map(|len| Fragment {
+			data: &self.data[offset..(offset + len)],
+		})
+	}
+}
+
+impl TryFrom<Bytes> for SharedIriBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(data: Bytes) -> Result<SharedIriBuf, Error> {
+		SharedIriBuf::new(data)
+	}
+}
+
+impl From<IriBuf> for SharedIriBuf {
+	#[inline]
+	fn from(iri: IriBuf) -> SharedIriBuf {
+		SharedIriBuf {
+			p: iri.0.p,
+			data: Bytes::from(iri.0.into_bytes()),
+		}
+	}
+}
+
+impl<'a> From<Iri<'a>> for SharedIriBuf {
+	#[inline]
+	fn from(iri: Iri<'a>) -> SharedIriBuf {
+		SharedIriBuf {
+			p: iri.p,
+			data: Bytes::copy_from_slice(iri.as_bytes()),
+		}
+	}
+}
+
+impl From<SharedIriBuf> for IriBuf {
+	#[inline]
+	fn from(iri: SharedIriBuf) -> IriBuf {
+		IriBuf(IriRefBuf {
+			p: iri.p,
+			data: iri.data.to_vec(),
+		})
+	}
+}
+
+impl AsIri for SharedIriBuf {
+	#[inline]
+	fn as_iri(&self) -> Iri<'_> {
+		self.as_iri()
+	}
+}
+
+impl AsIriRef for SharedIriBuf {
+	#[inline]
+	fn as_iri_ref(&self) -> IriRef<'_> {
+		self.as_iri_ref()
+	}
+}
+
+impl AsRef<[u8]> for SharedIriBuf {
+	#[inline]
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl AsRef<str> for SharedIriBuf {
+	#[inline]
+	fn as_ref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl fmt::Display for SharedIriBuf {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_iri().fmt(f)
+	}
+}
+
+impl fmt::Debug for SharedIriBuf {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_iri().fmt_components("SharedIriBuf", f)
+	}
+}
+
+impl PartialEq for SharedIriBuf {
+	#[inline]
+	fn eq(&self, other: &SharedIriBuf) -> bool {
+		self.as_iri_ref() == other.as_iri_ref()
+	}
+}
+
+impl Eq for SharedIriBuf {}
+
+impl<'a> PartialEq<Iri<'a>> for SharedIriBuf {
+	#[inline]
+	fn eq(&self, other: &Iri<'a>) -> bool {
+		self.as_iri_ref() == other.as_iri_ref()
+	}
+}
+
+impl PartialEq<IriBuf> for SharedIriBuf {
+	#[inline]
+	fn eq(&self, other: &IriBuf) -> bool {
+		self.as_iri_ref() == other.as_iri_ref()
+	}
+}
+
+/// Normalizing comparison with a string.
+///
+/// See [`IriRef`]'s implementation, and compare [`as_str`](SharedIriBuf::as_str)
+/// for a byte-exact comparison.
+impl PartialEq<str> for SharedIriBuf {
+	#[inline]
+	fn eq(&self, other: &str) -> bool {
+		self.as_iri_ref() == *other
+	}
+}
+
+impl PartialEq<&str> for SharedIriBuf {
+	#[inline]
+	fn eq(&self, other: &&str) -> bool {
+		*self == **other
+	}
+}
+
+impl PartialOrd for SharedIriBuf {
+	#[inline]
+	fn partial_cmp(&self, other: &SharedIriBuf) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for SharedIriBuf {
+	#[inline]
+	fn cmp(&self, other: &SharedIriBuf) -> Ordering {
+		self.as_iri_ref().cmp(&other.as_iri_ref())
+	}
+}
+
+impl Hash for SharedIriBuf {
+	#[inline]
+	fn hash<H: Hasher>(&self, hasher: &mut H) {
+		self.as_iri_ref().hash(hasher)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SharedIriBuf;
+	use crate::{Error, Iri, IriBuf};
+	use bytes::Bytes;
+
+	#[test]
+	fn shared_buffer() {
+		let buffer = Bytes::from("HTTP://u@a:8/b/./c?q#f http:d");
+		let iri = SharedIriBuf::new(buffer.slice(..22)).unwrap();
+		assert_eq!(iri.as_str(), "HTTP://u@a:8/b/./c?q#f");
+		assert_eq!(iri.len(), 22);
+		assert_eq!(iri.scheme(), "http");
+		assert_eq!(iri.authority().unwrap().host(), "a");
+		assert_eq!(iri.path(), "/b/c");
+		assert_eq!(iri.query().unwrap(), "q");
+		assert_eq!(iri.fragment().unwrap(), "f");
+		assert_eq!(iri, "http://u@a:8/b/c?q#f");
+
+		let clone = iri.clone();
+		assert_eq!(clone.as_bytes().as_ptr(), buffer.as_ptr());
+		assert_eq!(clone, iri);
+
+		let other = SharedIriBuf::new(buffer.slice(23..)).unwrap();
+		assert_eq!(other.path(), "d");
+		assert!(other.authority().is_none());
+
+		let owned = IriBuf::from(iri.clone());
+		assert_eq!(owned.as_str(), iri.as_str());
+		assert_eq!(SharedIriBuf::from(owned), iri);
+		assert_eq!(SharedIriBuf::from(Iri::new("http:d").unwrap()), other);
+
+		assert!(matches!(
+			SharedIriBuf::new(buffer.slice(15..18)),
+			Err(Error::MissingScheme)
+		));
+		assert!(SharedIriBuf::new(Bytes::from_static(b"http://a b")).is_err());
+	}
+}
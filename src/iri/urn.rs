@@ -0,0 +1,191 @@
+use crate::{Iri, IriBuf};
+
+/// View of a `urn:` URI, as defined by [RFC 8141](https://tools.ietf.org/html/rfc8141).
+///
+/// Returned by [`Iri::as_urn`].
+/// A URN is of the form `urn:<NID>:<NSS>?+<r-component>?=<q-component>#<f-component>`,
+/// where the NID is the namespace identifier and the NSS the namespace-specific string.
+///
+/// # Example
+/// ```
+/// # use iref::Iri;
+/// let iri = Iri::new("urn:example:weather?+CCResolve:cc=uk?=op=map#top").unwrap();
+/// let urn = iri.as_urn().unwrap();
+///
+/// assert_eq!(urn.nid(), "example");
+/// assert_eq!(urn.nss(), "weather");
+/// assert_eq!(urn.r_component(), Some("CCResolve:cc=uk"));
+/// assert_eq!(urn.q_component(), Some("op=map"));
+/// assert_eq!(urn.f_component(), Some("top"));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Urn<'a> {
+	nid: &'a str,
+	nss: &'a str,
+	r_component: Option<&'a str>,
+	q_component: Option<&'a str>,
+	f_component: Option<&'a str>,
+}
+
+impl<'a> Urn<'a> {
+	/// Returns the namespace identifier.
+	///
+	/// Namespace identifiers are case-insensitive.
+	#[inline]
+	pub fn nid(&self) -> &'a str {
+		self.nid
+	}
+
+	/// Returns the namespace-specific string.
+	#[inline]
+	pub fn nss(&self) -> &'a str {
+		self.nss
+	}
+
+	/// Returns the `r-component`, passed to the resolver, if any.
+	///
+	/// It is the part of the query introduced by `?+`.
+	#[inline]
+	pub fn r_component(&self) -> Option<&'a str> {
+		self.r_component
+	}
+
+	/// Returns the `q-component`, passed to the named resource, if any.
+	///
+	/// It is the part of the query introduced by `?=`.
+	#[inline]
+	pub fn q_component(&self) -> Option<&'a str> {
+		self.q_component
+	}
+
+	/// Returns the `f-component`, which is the fragment, if any.
+	#[inline]
+	pub fn f_component(&self) -> Option<&'a str> {
+		self.f_component
+	}
+}
+
+impl<'a> Iri<'a> {
+	/// Returns a view of this IRI as a URN, if it has the `urn` scheme.
+	///
+	/// Returns `None` if the scheme is not `urn`, or if the IRI is not a valid URN:
+	/// it has an authority, its NID is not 2 to 32 letters, digits and hyphens
+	/// (starting and ending with a letter or digit) or its NSS is empty or starts with a `/`.
+	/// If the query is not made of an `r-component` and/or a `q-component`,
+	/// both are `None`.
+	pub fn as_urn(&self) -> Option<Urn<'a>> {
+		if self.scheme() != "urn" || self.p.authority.is_some() {
+			return None;
+		}
+
+		let data: &'a [u8] = self.0.data;
+		let str = |offset: usize, len: usize| unsafe {
+			core::str::from_utf8_unchecked(&data[offset..(offset + len)])
+		};
+
+		let path = str(self.p.path_offset(), self.p.path_len);
+		let (nid, nss) = path.split_at(path.find(':')?);
+		let nss = &nss[1..];
+		if !is_nid(nid) || nss.is_empty() || nss.starts_with('/') {
+			return None;
+		}
+
+		let query = self.p.query_len.map(|len| str(self.p.query_offset(), len));
+		let (r_component, q_component) = match query {
+			Some(query) if query.starts_with('+') => match query.find("?=") {
+				Some(i) => (Some(&query[1..i]), Some(&query[(i + 2)..])),
+				None => (Some(&query[1..]), None),
+			},
+			Some(query) if query.starts_with('=') => (None, Some(&query[1..])),
+			_ => (None, None),
+		};
+
+		let f_component = self
+			.p
+			.fragment_len
+			.map(|len| str(self.p.fragment_offset(), len));
+
+		Some(Urn {
+			nid,
+			nss,
+			r_component,
+			q_component,
+			f_component,
+		})
+	}
+}
+
+impl IriBuf {
+	/// Returns a view of this IRI as a URN, if it has the `urn` scheme.
+	///
+	/// See [`Iri::as_urn`].
+	#[inline]
+	pub fn as_urn(&self) -> Option<Urn<'_>> {
+		self.as_iri().as_urn()
+	}
+}
+
+/// Checks if the given string is a valid NID.
+fn is_nid(nid: &str) -> bool {
+	let bytes = nid.as_bytes();
+	(2..=32).contains(&bytes.len())
+		&& bytes[0].is_ascii_alphanumeric()
+		&& bytes[bytes.len() - 1].is_ascii_alphanumeric()
+		&& bytes
+			.iter()
+			.all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Iri;
+
+	#[test]
+	fn nid_and_nss() {
+		let iri = Iri::new("URN:ISBN:0451450523").unwrap();
+		let urn = iri.as_urn().unwrap();
+		assert_eq!(urn.nid(), "ISBN");
+		assert_eq!(urn.nss(), "0451450523");
+		assert!(urn.r_component().is_none());
+		assert!(urn.q_component().is_none());
+		assert!(urn.f_component().is_none());
+
+		let urn = |iri| Iri::new(iri).unwrap().as_urn();
+		assert_eq!(
+			urn("urn:lex:eu:council:directive").unwrap().nss(),
+			"eu:council:directive"
+		);
+		assert_eq!(urn("urn:x-y:a/b").unwrap().nid(), "x-y");
+		assert!(urn("urn:a:b").is_none());
+		assert!(urn("urn:-a:b").is_none());
+		assert!(urn("urn:a-:b").is_none());
+		assert!(urn("urn:a_b:c").is_none());
+		assert!(urn("urn:abc:").is_none());
+		assert!(urn("urn:abc:/d").is_none());
+		assert!(urn("urn:abc").is_none());
+		assert!(urn("urn://ab/cd:e").is_none());
+		assert!(urn("http:abc:d").is_none());
+	}
+
+	#[test]
+	fn components() {
+		let urn = |iri| Iri::new(iri).unwrap().as_urn().unwrap();
+
+		let r = urn("urn:example:a?+r?b");
+		assert_eq!(r.r_component(), Some("r?b"));
+		assert_eq!(r.q_component(), None);
+
+		let q = urn("urn:example:a?=q?=c#f");
+		assert_eq!(q.r_component(), None);
+		assert_eq!(q.q_component(), Some("q?=c"));
+		assert_eq!(q.f_component(), Some("f"));
+
+		let both = urn("urn:example:a?+r?=q");
+		assert_eq!(both.r_component(), Some("r"));
+		assert_eq!(both.q_component(), Some("q"));
+
+		let other = urn("urn:example:a?x");
+		assert_eq!(other.r_component(), None);
+		assert_eq!(other.q_component(), None);
+	}
+}
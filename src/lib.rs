@@ -190,55 +190,263 @@
 //! abnormal use of dot segments in relative paths.
 //! This means that for instance, the IRI `http:a/b/../../../` is equivalent to
 //! `http:../` and **not** `http:`.
+//! The `test-vectors` feature exposes the RFC 3986 resolution examples checked by this crate,
+//! for use in downstream tests.
 //!
 //! #### Percent-encoded characters
 //!
 //! Thanks to the [`pct-str` crate](https://crates.io/crates/pct-str),
 //! percent encoded characters are correctly handled.
 //! The two IRIs `http://example.org` and `http://exa%6dple.org` **are** equivalent.
+//!
+//! #### Case sensitivity
+//!
+//! The scheme and host are case-insensitive,
+//! so `HTTP://Example.org/` and `http://example.org/` **are** equivalent.
+//! The other components are case-sensitive.
+//! IPv6 addresses are compared by their canonical form
+//! ([RFC 5952](https://tools.ietf.org/html/rfc5952#section-4)),
+//! so `http://[2001:DB8:0::1]/` and `http://[2001:db8::1]/` **are** equivalent too.
+//! The `normalized` method returns the normalized copy of an IRI used by the comparison:
+//! its scheme and host in lowercase, its IPv6 address in canonical form,
+//! its percent-encodings normalized and its dot segments removed.
+//!
+//! #### Comparing with strings
+//!
+//! Comparing an IRI with a string (`str`, `&str` or `String`, on either side of `==`)
+//! is normalizing too: the string is parsed, then compared following the rules above,
+//! so `Iri::new("http://a/~")? == "HTTP://A/%7E"` holds.
+//! A string that is not a valid IRI is never equal to an IRI.
+//! For a byte-exact comparison, compare `as_str()` or use the `eq_exact` method instead,
+//! and `differs_only_by_normalization` to detect equal IRIs written differently.
+//!
+//! #### Ordering
+//!
+//! All the IRI types implement `Ord` consistently with their (normalizing) `PartialEq`.
+//! IRIs are compared component by component: scheme, authority (user info, host then port),
+//! normalized path, query and fragment, the first difference deciding the result.
+//! Absent components are ordered before present ones.
+//!
+//! ## `no_std` support
+//!
+//! This crate depends on the standard library through the `std` feature, enabled by default.
+//! Disabling it (`default-features = false`) makes it `no_std`, only requiring the `alloc` crate.
+//! Every type and method of this crate remains available in `no_std` mode,
+//! including the owned buffers, the [`HostKind`] IP address parsing (using `core::net`)
+//! and the `serde`, `idna`, `url`, `bytes`, `test-vectors`, `schemes`, `template` and
+//! `unicode-security` features.
+//! The `arbitrary` feature, meant for fuzzing, and the `http` feature require the standard library,
+//! as do the `write_to` and `write_normalized_to` methods, writing to an `std::io::Write`,
+//! the `from_file_path` and `to_file_path` methods, and `Template::match_iri`.
+//! Apart from them, only the methods of the [`pct_str::PctStr`] type that need the standard library
+//! (such as `PctStr::decode`) are unavailable.
 #![allow(clippy::tabs_in_doc_comments)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
+mod encode;
 mod iri;
 pub mod parsing;
 mod reference;
 
+#[cfg(feature = "serde")]
+mod serde;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+
+#[cfg(feature = "url")]
+pub mod url;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "template")]
+pub mod template;
+
+#[cfg(any(feature = "test-vectors", test))]
+pub mod test_vectors;
+
+pub use crate::encode::*;
 pub use crate::iri::*;
+pub use crate::parsing::{
+	const_validate, is_valid_iri, is_valid_iri_bytes, is_valid_iri_ref, is_valid_iri_ref_bytes,
+};
 pub use crate::reference::*;
-use std::ops::Range;
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+use pct_str::{Encoder, PctStr};
 
 /// Replacement function in IRI-reference buffers.
 ///
 /// Replace the given `range` of the input `buffer` with the given `content`.
 /// This function is used in many places to replace parts of an IRI-reference buffer data.
 pub(crate) fn replace(buffer: &mut Vec<u8>, range: Range<usize>, content: &[u8]) {
-	let range_len = range.end - range.start;
+	let len = buffer.len();
+	let new_end = range.start + content.len();
+
+	if content.len() > range.len() {
+		// grow, with a single reallocation, then move the tail forward.
+		buffer.resize(len + content.len() - range.len(), 0);
+		buffer.copy_within(range.end..len, new_end);
+	} else if content.len() < range.len() {
+		// move the tail backward, then shrink.
+		buffer.copy_within(range.end..len, new_end);
+		buffer.truncate(len + content.len() - range.len());
+	}
 
-	// move the content around.
-	if range_len != content.len() {
-		let tail_len = buffer.len() - range.end; // the length of the content in the buffer after [range].
-		let new_end = range.start + content.len();
+	buffer[range.start..new_end].copy_from_slice(content);
+}
 
-		if range_len > content.len() {
-			// shrink
-			for i in 0..tail_len {
-				buffer[new_end + i] = buffer[range.end + i];
-			}
+/// Percent-encode the given string.
+///
+/// Characters selected by the `encoder`, and `%`, are percent-encoded and pushed to `output`,
+/// other characters are pushed unchanged.
+pub(crate) fn pct_encode<E: Encoder>(input: &str, encoder: E, output: &mut String) {
+	for c in input.chars() {
+		if c == '%' || encoder.encode(c) {
+			pct_encode_char(c, output)
+		} else {
+			output.push(c)
+		}
+	}
+}
 
-			buffer.resize(new_end + tail_len, 0);
+/// Percent-encode the ASCII characters that are not allowed in IRIs but are left unencoded by
+/// other URI implementations, such as `|` or `^`, and push the result to `output`.
+///
+/// Existing percent-encoded characters are left unchanged.
+#[cfg(any(feature = "url", feature = "http"))]
+pub(crate) fn pct_encode_lenient(input: &str, output: &mut String) {
+	for c in input.chars() {
+		if matches!(
+			c,
+			' ' | '"' | '<' | '>' | '[' | '\\' | ']' | '^' | '`' | '{' | '|' | '}'
+		) {
+			pct_encode_char(c, output)
 		} else {
-			// grow
-			let tail_len = buffer.len() - range.end;
+			output.push(c)
+		}
+	}
+}
+
+/// Percent-decode the given string, replacing invalid UTF-8 sequences with `U+FFFD`.
+pub(crate) fn pct_decode_lossy(pct_str: &PctStr) -> String {
+	let bytes: Vec<u8> = pct_str.bytes().collect();
+	String::from_utf8(bytes).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+}
+
+/// Decode the percent-encoded UTF-8 character at the start of `bytes`, if any.
+///
+/// Returns the character and the length of its percent-encoded representation,
+/// or `None` if `bytes` does not start with a valid percent-encoded UTF-8 sequence.
+pub(crate) fn pct_decode_char(bytes: &[u8]) -> Option<(char, usize)> {
+	let octet = |i: usize| match bytes.get((i * 3)..(i * 3 + 3)) {
+		Some([b'%', h, l]) => {
+			let hex = |b: &u8| (*b as char).to_digit(16);
+			Some((hex(h)? << 4 | hex(l)?) as u8)
+		}
+		_ => None,
+	};
+
+	let len = match octet(0)? {
+		0x00..=0x7f => 1,
+		0xc0..=0xdf => 2,
+		0xe0..=0xef => 3,
+		0xf0..=0xf7 => 4,
+		_ => return None,
+	};
+
+	let mut buffer = [0; 4];
+	for (i, b) in buffer[..len].iter_mut().enumerate() {
+		*b = octet(i)?;
+	}
+
+	let c = core::str::from_utf8(&buffer[..len]).ok()?.chars().next()?;
+	Some((c, len * 3))
+}
 
-			buffer.resize(new_end + tail_len, 0);
+/// Checks if the percent-decoded bytes of `data` contain `needle`, without allocating.
+///
+/// `data` must be validly percent-encoded.
+pub(crate) fn pct_contains(data: &[u8], needle: &str) -> bool {
+	let hex = |b: u8| (b as char).to_digit(16).unwrap() as u8;
+	let decode_at = |i: usize| match data[i] {
+		b'%' => (hex(data[i + 1]) << 4 | hex(data[i + 2]), i + 3),
+		b => (b, i + 1),
+	};
 
-			for i in 0..tail_len {
-				buffer[new_end + tail_len - i - 1] = buffer[range.end + tail_len - i - 1];
+	let needle = needle.as_bytes();
+	let mut start = 0;
+	loop {
+		let mut i = start;
+		let mut matched = 0;
+		while matched < needle.len() && i < data.len() {
+			let (b, next) = decode_at(i);
+			if b != needle[matched] {
+				break;
 			}
+
+			matched += 1;
+			i = next;
+		}
+
+		if matched == needle.len() {
+			return true;
+		}
+
+		if start >= data.len() {
+			return false;
 		}
+
+		start = decode_at(start).1;
+	}
+}
+
+/// Push the percent-encoded UTF-8 bytes of the given character to `output`.
+pub(crate) fn pct_encode_char(c: char, output: &mut String) {
+	const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+	let mut bytes = [0; 4];
+	for b in c.encode_utf8(&mut bytes).bytes() {
+		output.push('%');
+		output.push(HEX[(b >> 4) as usize] as char);
+		output.push(HEX[(b & 0x0f) as usize] as char);
 	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::replace;
+
+	#[test]
+	fn replace_moves_tail() {
+		let replaced = |range, content: &str| {
+			let mut buffer = b"abcdefgh".to_vec();
+			replace(&mut buffer, range, content.as_bytes());
+			String::from_utf8(buffer).unwrap()
+		};
+
+		// same length, no move.
+		assert_eq!(replaced(2..4, "XY"), "abXYefgh");
+		assert_eq!(replaced(3..3, ""), "abcdefgh");
+
+		// grow, overlapping the tail.
+		assert_eq!(replaced(2..3, "XYZ"), "abXYZdefgh");
+		assert_eq!(replaced(0..0, "XY"), "XYabcdefgh");
+		assert_eq!(replaced(8..8, "XY"), "abcdefghXY");
+		assert_eq!(replaced(0..8, "0123456789"), "0123456789");
+
+		// shrink, overlapping the tail.
+		assert_eq!(replaced(1..5, "X"), "aXfgh");
+		assert_eq!(replaced(0..2, ""), "cdefgh");
+		assert_eq!(replaced(6..8, ""), "abcdef");
+		assert_eq!(replaced(0..8, ""), "");
 
-	// actually replace the content.
-	for i in 0..content.len() {
-		buffer[range.start + i] = content[i]
+		let mut buffer = Vec::with_capacity(4);
+		buffer.extend_from_slice(b"ab");
+		replace(&mut buffer, 1..1, b"0123456789");
+		assert_eq!(buffer, b"a0123456789b");
 	}
 }
@@ -1,20 +1,63 @@
+//! IRI parsing functions.
+//!
+//! This module exposes the parsers used to build the IRI types of this crate,
+//! following the grammar of [RFC 3987](https://tools.ietf.org/html/rfc3987).
+//! They can be used to validate a single IRI component, for instance a user-entered
+//! host name, without building a whole IRI.
+//!
+//! Each `parse_*` function parses the longest prefix of `buffer[i..]` matching its
+//! component and returns the length of this prefix, possibly `0`.
+//! It only fails on invalid UTF-8 or percent-encoding.
+//! To check that an entire input is a valid component, compare the returned length
+//! with the input length:
+//!
+//! ```
+//! use iref::parsing::parse_host;
+//!
+//! fn is_valid_host(host: &str) -> bool {
+//! 	parse_host(host.as_bytes(), 0).map_or(false, |len| len == host.len())
+//! }
+//!
+//! assert!(is_valid_host("example.org"));
+//! assert!(!is_valid_host("example.org/path"));
+//! ```
+//!
+//! Whole IRI references are parsed by [`ParsedIriRef::new`],
+//! or [`ParsedIriRef::parse_detailed`] to locate errors.
+//! Use [`is_valid_iri`] or [`is_valid_iri_ref`] to only check their validity.
 mod utf8;
+mod validate;
+
+use core::error::Error as StdError;
+use core::fmt;
 
 use super::Error;
 
-#[derive(Default, Clone, Copy)]
+pub use self::validate::*;
+
+/// Layout of a parsed authority.
+///
+/// Lengths do not include the `@` and `:` delimiters.
+#[derive(Debug, Default, Clone, Copy)]
 pub struct ParsedAuthority {
+	/// Length of the user info, if any.
 	pub userinfo_len: Option<usize>,
+
+	/// Length of the host.
 	pub host_len: usize,
+
+	/// Length of the port, if any.
 	pub port_len: Option<usize>,
 }
 
 impl ParsedAuthority {
+	/// Checks if the authority is empty.
 	#[inline]
 	pub fn is_empty(&self) -> bool {
 		self.userinfo_len.is_none() && self.host_len == 0 && self.port_len.is_none()
 	}
 
+	/// Length of the authority, delimiters included.
 	#[inline]
 	pub fn len(&self) -> usize {
 		let mut len = 0;
@@ -32,6 +75,7 @@ impl ParsedAuthority {
 		len
 	}
 
+	/// Offset of the host from the start of the authority.
 	#[inline]
 	pub fn host_offset(&self) -> usize {
 		let mut offset = 0;
@@ -43,6 +87,7 @@ impl ParsedAuthority {
 		offset
 	}
 
+	/// Offset of the port from the start of the authority.
 	#[inline]
 	pub fn port_offset(&self) -> usize {
 		let mut offset = 0;
@@ -61,18 +106,55 @@ impl ParsedAuthority {
 	}
 }
 
-#[derive(Default, Clone, Copy)]
+/// Layout of a parsed IRI reference.
+///
+/// Lengths do not include the `:`, `//`, `?` and `#` delimiters.
+#[derive(Debug, Default, Clone, Copy)]
 pub struct ParsedIriRef {
+	/// Length of the scheme, if any.
 	pub scheme_len: Option<usize>,
+
+	/// Authority layout, if any.
 	pub authority: Option<ParsedAuthority>,
+
+	/// Length of the path.
 	pub path_len: usize,
+
+	/// Length of the query, if any.
 	pub query_len: Option<usize>,
+
+	/// Length of the fragment, if any.
 	pub fragment_len: Option<usize>,
 }
 
 impl ParsedIriRef {
+	/// Parse an IRI reference.
+	///
+	/// Use [`parse_detailed`](ParsedIriRef::parse_detailed) to know where the parsing failed.
 	#[inline]
 	pub fn new<S: AsRef<[u8]> + ?Sized>(buffer: &S) -> Result<ParsedIriRef, Error> {
+		Self::parse_detailed(buffer).map_err(Error::from)
+	}
+
+	/// Parse an IRI reference, locating the first invalid byte on failure.
+	///
+	/// The returned [`ParseError`] gives the byte offset of the error, the component being
+	/// parsed and the expected kind of character, along with the coarse [`Error`]
+	/// that [`ParsedIriRef::new`] would return.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::parsing::{Component, Expected, ParsedIriRef};
+	/// let input = "https://example.org/a b";
+	/// let e = ParsedIriRef::parse_detailed(input).unwrap_err();
+	/// assert_eq!(e.offset, 21);
+	/// assert_eq!(e.component, Component::Path);
+	/// assert_eq!(e.expected, Expected::PathChar);
+	///
+	/// // Underline the invalid character.
+	/// println!("{}\n{:>width$}", input, "^", width = e.offset + 1);
+	/// ```
+	pub fn parse_detailed<S: AsRef<[u8]> + ?Sized>(buffer: &S) -> Result<ParsedIriRef, ParseError> {
 		let buffer = buffer.as_ref();
 		let mut scheme_len = None;
 		let mut authority = None;
@@ -80,10 +162,16 @@ impl ParsedIriRef {
 		let mut query_len = None;
 		let mut fragment_len = None;
 
-		let scheme_len_tmp = parse_scheme(buffer, 0)?;
-		let scheme_end = if let Some((':', 1)) = get_char(buffer, scheme_len_tmp)? {
+		let scheme_len_tmp = scan_scheme(buffer, 0)?;
+		let scheme_end = if let Some((':', 1)) = char_at(buffer, scheme_len_tmp, Component::Scheme)?
+		{
 			if scheme_len_tmp == 0 {
-				return Err(Error::MissingScheme);
+				return Err(ParseError::new(
+					0,
+					Component::Scheme,
+					Expected::SchemeStart,
+					Error::MissingScheme,
+				));
 			}
 
 			scheme_len = Some(scheme_len_tmp);
@@ -94,46 +182,79 @@ impl ParsedIriRef {
 
 		let authority_end;
 
-		match get_char(buffer, scheme_end)? {
+		match char_at(buffer, scheme_end, Component::Path)? {
 			Some(('/', 1)) => {
-				match get_char(buffer, scheme_end + 1)? {
+				match char_at(buffer, scheme_end + 1, Component::Path)? {
 					Some(('/', 1)) => {
-						authority = Some(parse_authority(buffer, scheme_end + 2)?);
-						authority_end = scheme_end + 2 + authority.unwrap().len();
+						let parsed_authority = scan_authority(buffer, scheme_end + 2)?;
+						authority = Some(parsed_authority);
+						authority_end = scheme_end + 2 + parsed_authority.len();
 						// path must be absolute.
-						path_len = if let Some(('/', 1)) = get_char(buffer, authority_end)? {
-							parse_path(buffer, authority_end)?
-						} else {
-							0
-						};
+						let (component, _) =
+							after_authority(buffer, scheme_end + 2, &parsed_authority);
+						path_len =
+							if let Some(('/', 1)) = char_at(buffer, authority_end, component)? {
+								scan_path(buffer, authority_end)?
+							} else {
+								0
+							};
 					}
 					_ => {
 						authority_end = scheme_end;
-						path_len = parse_path(buffer, authority_end)?;
+						path_len = scan_path(buffer, authority_end)?;
 					}
 				}
 			}
 			_ => {
 				authority_end = scheme_end;
-				path_len = parse_path(buffer, authority_end)?;
+				path_len = scan_path(buffer, authority_end)?;
 			}
 		}
 
+		// What may follow the last parsed character.
+		let (component, expected) = match authority.as_ref() {
+			Some(parsed_authority) if path_len == 0 => {
+				after_authority(buffer, scheme_end + 2, parsed_authority)
+			}
+			_ => (Component::Path, Expected::PathChar),
+		};
+
 		let i = authority_end + path_len;
 
-		match get_char(buffer, i)? {
-			Some(('#', 1)) => fragment_len = Some(parse_fragment(buffer, i + 1)?),
+		let fragment_start = match char_at(buffer, i, component)? {
+			Some(('#', 1)) => Some(i + 1),
 			Some(('?', 1)) => {
-				let len = parse_query(buffer, i + 1)?;
+				let len = scan_query(buffer, i + 1)?;
 				query_len = Some(len);
-				match get_char(buffer, i + 1 + len)? {
-					Some(('#', 1)) => fragment_len = Some(parse_fragment(buffer, i + 1 + len + 1)?),
-					Some(_) => return Err(Error::InvalidPath),
-					None => (),
+				match char_at(buffer, i + 1 + len, Component::Query)? {
+					Some(('#', 1)) => Some(i + 1 + len + 1),
+					Some(_) => {
+						return Err(ParseError::new(
+							i + 1 + len,
+							Component::Query,
+							Expected::QueryChar,
+							Error::InvalidPath,
+						))
+					}
+					None => None,
 				}
 			}
-			Some(_) => return Err(Error::InvalidPath),
-			None => (),
+			Some(_) => return Err(ParseError::new(i, component, expected, Error::InvalidPath)),
+			None => None,
+		};
+
+		if let Some(start) = fragment_start {
+			let len = scan_fragment(buffer, start)?;
+			if start + len < buffer.len() {
+				return Err(ParseError::new(
+					start + len,
+					Component::Fragment,
+					Expected::FragmentChar,
+					Error::InvalidFragment,
+				));
+			}
+
+			fragment_len = Some(len);
 		}
 
 		Ok(ParsedIriRef {
@@ -145,6 +266,7 @@ impl ParsedIriRef {
 		})
 	}
 
+	/// Length of the IRI reference, delimiters included.
 	#[inline]
 	pub fn len(&self) -> usize {
 		let mut offset = 0;
@@ -170,6 +292,7 @@ impl ParsedIriRef {
 		offset
 	}
 
+	/// Checks if the IRI reference is empty.
 	#[inline]
 	pub fn is_empty(&self) -> bool {
 		self.scheme_len.is_none()
@@ -179,6 +302,7 @@ impl ParsedIriRef {
 			&& self.fragment_len.is_none()
 	}
 
+	/// Offset of the authority, after the `//` delimiter.
 	#[inline]
 	pub fn authority_offset(&self) -> usize {
 		let mut offset = 0;
@@ -194,6 +318,7 @@ impl ParsedIriRef {
 		offset
 	}
 
+	/// Offset of the path.
 	#[inline]
 	pub fn path_offset(&self) -> usize {
 		let mut offset = 0;
@@ -209,6 +334,7 @@ impl ParsedIriRef {
 		offset
 	}
 
+	/// Offset of the query, after the `?` delimiter if any.
 	#[inline]
 	pub fn query_offset(&self) -> usize {
 		let mut offset = self.path_offset() + self.path_len;
@@ -220,6 +346,7 @@ impl ParsedIriRef {
 		offset
 	}
 
+	/// Offset of the fragment, after the `#` delimiter if any.
 	#[inline]
 	pub fn fragment_offset(&self) -> usize {
 		let mut offset = self.path_offset() + self.path_len;
@@ -236,6 +363,154 @@ impl ParsedIriRef {
 	}
 }
 
+/// IRI component being parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Component {
+	Scheme,
+	UserInfo,
+	Host,
+	Port,
+	Path,
+	Query,
+	Fragment,
+}
+
+impl fmt::Display for Component {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Component::Scheme => "scheme",
+			Component::UserInfo => "user info",
+			Component::Host => "host",
+			Component::Port => "port",
+			Component::Path => "path",
+			Component::Query => "query",
+			Component::Fragment => "fragment",
+		})
+	}
+}
+
+/// Character class expected where a parsing error occurred.
+///
+/// Classes of component characters also include the delimiters
+/// that may follow the component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expected {
+	/// A valid UTF-8 encoded character.
+	Utf8,
+
+	/// An hexadecimal digit, in a percent-encoded character.
+	HexDigit,
+
+	/// An ASCII letter, starting the scheme.
+	SchemeStart,
+
+	/// A registered name character, or `:`, `/`, `?`, `#` or the end of the input.
+	HostChar,
+
+	/// An IPv6 address literal, enclosed in square brackets.
+	IpLiteral,
+
+	/// A decimal digit, or `/`, `?`, `#` or the end of the input.
+	PortDigit,
+
+	/// A path character, or `?`, `#` or the end of the input.
+	PathChar,
+
+	/// A query character, or `#` or the end of the input.
+	QueryChar,
+
+	/// A fragment character, or the end of the input.
+	FragmentChar,
+}
+
+impl fmt::Display for Expected {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Expected::Utf8 => "a valid UTF-8 encoded character",
+			Expected::HexDigit => "an hexadecimal digit",
+			Expected::SchemeStart => "a letter",
+			Expected::HostChar => "a host character, `:`, `/`, `?`, `#` or the end of the input",
+			Expected::IpLiteral => "an IPv6 address literal",
+			Expected::PortDigit => "a digit, `/`, `?`, `#` or the end of the input",
+			Expected::PathChar => "a path character, `?`, `#` or the end of the input",
+			Expected::QueryChar => "a query character, `#` or the end of the input",
+			Expected::FragmentChar => "a fragment character or the end of the input",
+		})
+	}
+}
+
+/// Detailed parsing error.
+///
+/// Contrarily to [`Error`], it locates the first invalid byte of the input,
+/// which can be used to point at the bad character in an error message.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+	/// Byte offset of the error in the input.
+	///
+	/// It is equal to the input length if the input ended prematurely.
+	pub offset: usize,
+
+	/// Component being parsed.
+	pub component: Component,
+
+	/// What was expected at `offset`.
+	pub expected: Expected,
+
+	/// Coarse error, as returned by [`ParsedIriRef::new`].
+	pub error: Error,
+}
+
+impl ParseError {
+	#[inline]
+	fn new(offset: usize, component: Component, expected: Expected, error: Error) -> ParseError {
+		ParseError {
+			offset,
+			component,
+			expected,
+			error,
+		}
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} at offset {} in {}: expected {}",
+			self.error, self.offset, self.component, self.expected
+		)
+	}
+}
+
+impl StdError for ParseError {}
+
+impl From<ParseError> for Error {
+	#[inline]
+	fn from(e: ParseError) -> Error {
+		e.error
+	}
+}
+
+/// Component and character class to report for an error right after the authority
+/// starting at `offset`.
+fn after_authority(
+	buffer: &[u8],
+	offset: usize,
+	authority: &ParsedAuthority,
+) -> (Component, Expected) {
+	if authority.port_len.is_some() {
+		(Component::Port, Expected::PortDigit)
+	} else if authority.host_len == 0 && buffer.get(offset + authority.host_offset()) == Some(&b'[')
+	{
+		(Component::Host, Expected::IpLiteral)
+	} else {
+		(Component::Host, Expected::HostChar)
+	}
+}
+
+/// Decode the UTF-8 character starting at `i`, returning it with its encoded length.
+///
+/// Returns `None` at the end of the buffer.
 #[inline]
 pub fn get_char(buffer: &[u8], i: usize) -> Result<Option<(char, usize)>, Error> {
 	match utf8::get_char(buffer, i) {
@@ -245,26 +520,45 @@ pub fn get_char(buffer: &[u8], i: usize) -> Result<Option<(char, usize)>, Error>
 	}
 }
 
+/// Same as [`get_char`], reporting an encoding error as part of `component`.
+#[inline]
+fn char_at(
+	buffer: &[u8],
+	i: usize,
+	component: Component,
+) -> Result<Option<(char, usize)>, ParseError> {
+	get_char(buffer, i).map_err(|e| ParseError::new(i, component, Expected::Utf8, e))
+}
+
+/// Checks if `c` is an ASCII letter (`ALPHA`).
 #[inline]
 pub fn is_alpha(c: char) -> bool {
 	c.is_ascii_alphabetic()
 }
 
+/// Checks if `c` is an ASCII digit (`DIGIT`).
 #[inline]
 pub fn is_digit(c: char) -> bool {
 	c.is_ascii_digit()
 }
 
+/// Checks if `c` is an ASCII letter or digit.
 #[inline]
 pub fn is_alphanumeric(c: char) -> bool {
 	c.is_ascii_alphanumeric()
 }
 
-/// Parse the IRI scheme.
+/// Parse an IRI scheme.
+///
+/// `scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )`
 #[inline]
-pub fn parse_scheme(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
+pub fn parse_scheme(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_scheme(buffer, i)?)
+}
+
+fn scan_scheme(buffer: &[u8], mut i: usize) -> Result<usize, ParseError> {
 	loop {
-		match get_char(buffer, i)? {
+		match char_at(buffer, i, Component::Scheme)? {
 			Some((c, len))
 				if (i == 0 && is_alpha(c))
 					|| (i > 0 && (is_alphanumeric(c) || c == '+' || c == '-' || c == '.')) =>
@@ -278,32 +572,36 @@ pub fn parse_scheme(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
 	Ok(i)
 }
 
-fn is_ucschar(c: char) -> bool {
-	let c = c as u32;
-	(0xA0..=0xD7FF).contains(&c)
-		|| (0xF900..=0xFDCF).contains(&c)
-		|| (0xFDF0..=0xFFEF).contains(&c)
-		|| (0x10000..=0x1FFFD).contains(&c)
-		|| (0x20000..=0x2FFFD).contains(&c)
-		|| (0x30000..=0x3FFFD).contains(&c)
-		|| (0x40000..=0x4FFFD).contains(&c)
-		|| (0x50000..=0x5FFFD).contains(&c)
-		|| (0x60000..=0x6FFFD).contains(&c)
-		|| (0x70000..=0x7FFFD).contains(&c)
-		|| (0x80000..=0x8FFFD).contains(&c)
-		|| (0x90000..=0x9FFFD).contains(&c)
-		|| (0xA0000..=0xAFFFD).contains(&c)
-		|| (0xB0000..=0xBFFFD).contains(&c)
-		|| (0xC0000..=0xCFFFD).contains(&c)
-		|| (0xD0000..=0xDFFFD).contains(&c)
-		|| (0xE1000..=0xEFFFD).contains(&c)
-}
-
-fn is_private(c: char) -> bool {
-	let c = c as u32;
-	(0xE000..=0xF8FF).contains(&c)
-		|| (0xF0000..=0xFFFFD).contains(&c)
-		|| (0x100000..=0x10FFFD).contains(&c)
+/// Checks if `c` is a non-ASCII character allowed in IRIs (`ucschar`).
+pub const fn is_ucschar(c: char) -> bool {
+	matches!(
+		c,
+		'\u{A0}'..='\u{D7FF}'
+			| '\u{F900}'..='\u{FDCF}'
+			| '\u{FDF0}'..='\u{FFEF}'
+			| '\u{10000}'..='\u{1FFFD}'
+			| '\u{20000}'..='\u{2FFFD}'
+			| '\u{30000}'..='\u{3FFFD}'
+			| '\u{40000}'..='\u{4FFFD}'
+			| '\u{50000}'..='\u{5FFFD}'
+			| '\u{60000}'..='\u{6FFFD}'
+			| '\u{70000}'..='\u{7FFFD}'
+			| '\u{80000}'..='\u{8FFFD}'
+			| '\u{90000}'..='\u{9FFFD}'
+			| '\u{A0000}'..='\u{AFFFD}'
+			| '\u{B0000}'..='\u{BFFFD}'
+			| '\u{C0000}'..='\u{CFFFD}'
+			| '\u{D0000}'..='\u{DFFFD}'
+			| '\u{E1000}'..='\u{EFFFD}'
+	)
+}
+
+/// Checks if `c` is a private use character (`iprivate`), only allowed in queries.
+pub const fn is_private(c: char) -> bool {
+	matches!(
+		c,
+		'\u{E000}'..='\u{F8FF}' | '\u{F0000}'..='\u{FFFFD}' | '\u{100000}'..='\u{10FFFD}'
+	)
 }
 
 fn is_unreserved(c: char) -> bool {
@@ -317,99 +615,81 @@ fn is_subdelim(c: char) -> bool {
 	)
 }
 
-fn is_hex_digit(buffer: &[u8], i: usize) -> Result<bool, Error> {
-	match get_char(buffer, i)? {
-		Some((c, 1)) => Ok(c.is_digit(16)),
-		_ => Ok(false),
-	}
+pub(crate) fn is_userinfo_char(c: char) -> bool {
+	c == ':' || is_subdelim(c) || is_unreserved(c)
 }
 
-fn parse_pct_encoded(buffer: &[u8], i: usize) -> Result<Option<usize>, Error> {
-	match get_char(buffer, i)? {
-		Some(('%', 1)) => {
-			if is_hex_digit(buffer, i + 1)? && is_hex_digit(buffer, i + 2)? {
-				Ok(Some(3))
-			} else {
-				Err(Error::InvalidPercentEncoding)
-			}
-		}
-		_ => Ok(None),
-	}
+pub(crate) fn is_query_char(c: char) -> bool {
+	is_fragment_char(c) || is_private(c)
 }
 
-#[inline]
-pub fn parse_userinfo(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
-	let offset = i;
+pub(crate) fn is_fragment_char(c: char) -> bool {
+	c == '?' || is_path_char(c)
+}
 
-	loop {
-		match get_char(buffer, i)? {
-			// Some(('@', 1)) => {
-			// 	return Ok(Some(i))
-			// },
-			Some(('%', 1)) => {
-				if let Some(len) = parse_pct_encoded(buffer, i)? {
-					i += len
-				} else {
-					break;
-				}
-			}
-			Some((c, len)) if c == ':' || is_subdelim(c) || is_unreserved(c) => i += len,
-			_ => break,
-		}
-	}
+pub(crate) fn is_reg_name_char(c: char) -> bool {
+	is_subdelim(c) || is_unreserved(c)
+}
 
-	Ok(i - offset)
+pub(crate) fn is_path_char(c: char) -> bool {
+	c == '/' || is_segment_char(c)
 }
 
-#[inline]
-pub fn parse_query(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
-	let offset = i;
+pub(crate) fn is_segment_char(c: char) -> bool {
+	is_subdelim(c) || is_unreserved(c) || c == '@' || c == ':'
+}
 
-	loop {
-		match get_char(buffer, i)? {
-			Some(('%', 1)) => {
-				if let Some(len) = parse_pct_encoded(buffer, i)? {
-					i += len
-				} else {
-					break;
+fn is_hex_digit(buffer: &[u8], i: usize, component: Component) -> Result<bool, ParseError> {
+	match char_at(buffer, i, component)? {
+		Some((c, 1)) => Ok(c.is_ascii_hexdigit()),
+		_ => Ok(false),
+	}
+}
+
+fn parse_pct_encoded(
+	buffer: &[u8],
+	i: usize,
+	component: Component,
+) -> Result<Option<usize>, ParseError> {
+	match char_at(buffer, i, component)? {
+		Some(('%', 1)) => {
+			for j in (i + 1)..(i + 3) {
+				if !is_hex_digit(buffer, j, component)? {
+					return Err(ParseError::new(
+						j,
+						component,
+						Expected::HexDigit,
+						Error::InvalidPercentEncoding,
+					));
 				}
 			}
-			Some((c, len))
-				if c == ':'
-					|| c == '@' || c == '/'
-					|| c == '?' || is_subdelim(c)
-					|| is_unreserved(c) || is_private(c) =>
-			{
-				i += len
-			}
-			_ => break,
+
+			Ok(Some(3))
 		}
+		_ => Ok(None),
 	}
-
-	Ok(i - offset)
 }
 
-#[inline]
-pub fn parse_fragment(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
+/// Parse the characters of `component` accepted by `f`, and percent-encoded characters.
+/// Return the parsed length.
+fn scan_chars(
+	buffer: &[u8],
+	mut i: usize,
+	component: Component,
+	f: impl Fn(char) -> bool,
+) -> Result<usize, ParseError> {
 	let offset = i;
 
 	loop {
-		match get_char(buffer, i)? {
+		match char_at(buffer, i, component)? {
 			Some(('%', 1)) => {
-				if let Some(len) = parse_pct_encoded(buffer, i)? {
+				if let Some(len) = parse_pct_encoded(buffer, i, component)? {
 					i += len
 				} else {
 					break;
 				}
 			}
-			Some((c, len))
-				if c == ':'
-					|| c == '@' || c == '/'
-					|| c == '?' || is_subdelim(c)
-					|| is_unreserved(c) =>
-			{
-				i += len
-			}
+			Some((c, len)) if f(c) => i += len,
 			_ => break,
 		}
 	}
@@ -417,215 +697,89 @@ pub fn parse_fragment(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
 	Ok(i - offset)
 }
 
-fn parse_dec_octet(buffer: &[u8], i: usize) -> Result<Option<(u32, usize)>, Error> {
-	let mut octet = 0u32;
-	let mut len = 0;
-
-	while let Some((c, 1)) = get_char(buffer, i + len)? {
-		if let Some(d) = c.to_digit(10) {
-			if octet == 25 && d > 5 {
-				return Ok(None);
-			} else {
-				octet = octet * 10 + d
-			}
-
-			len += 1;
-
-			if len >= 3 || octet > 25 {
-				break;
-			}
-		} else {
-			break;
-		}
-	}
-
-	if len == 0 {
-		Ok(None)
-	} else {
-		Ok(Some((octet, len)))
-	}
-}
-
-/// Parse an IPv4 literal.
-fn parse_ipv4_literal(buffer: &[u8], mut i: usize) -> Result<Option<(u32, usize)>, Error> {
-	let offset = i;
-	if let Some((a, olen)) = parse_dec_octet(buffer, i)? {
-		i += olen;
-		if let Some(('.', 1)) = get_char(buffer, i)? {
-			i += 1;
-			if let Some((b, olen)) = parse_dec_octet(buffer, i)? {
-				i += olen;
-				if let Some(('.', 1)) = get_char(buffer, i)? {
-					i += 1;
-					if let Some((c, olen)) = parse_dec_octet(buffer, i)? {
-						i += olen;
-						if let Some(('.', 1)) = get_char(buffer, i)? {
-							i += 1;
-							if let Some((d, olen)) = parse_dec_octet(buffer, i)? {
-								i += olen;
-								let ipv4 = (a << 24) | (b << 16) | (c << 8) | d;
-								let len = i - offset;
-								return Ok(Some((ipv4, len)));
-							}
-						}
-					}
-				}
-			}
-		}
-	}
-
-	Ok(None)
+/// Parse an authority user info, without the `@` delimiter.
+///
+/// `iuserinfo = *( iunreserved / pct-encoded / sub-delims / ":" )`
+#[inline]
+pub fn parse_userinfo(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_chars(
+		buffer,
+		i,
+		Component::UserInfo,
+		is_userinfo_char,
+	)?)
 }
 
-fn parse_h16(buffer: &[u8], i: usize) -> Result<Option<(u16, usize)>, Error> {
-	let mut len = 0;
-	let mut h16 = 0;
-
-	while let Some((c, 1)) = get_char(buffer, i + len)? {
-		if let Some(d) = c.to_digit(16) {
-			h16 = (h16 << 4) | d as u16;
-			len += 1;
-			if len >= 4 {
-				break;
-			}
-		} else {
-			break;
-		}
-	}
-
-	Ok(Some((h16, len)))
+/// Parse a query, without the `?` delimiter.
+///
+/// `iquery = *( ipchar / iprivate / "/" / "?" )`
+#[inline]
+pub fn parse_query(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_query(buffer, i)?)
 }
 
-/// Parse an IPv6 literal.
-/// Return the IPv6 and the string length.
-fn parse_ipv6_literal(buffer: &[u8], mut i: usize) -> Result<Option<(u128, usize)>, Error> {
-	let mut lhs = 0u128;
-	let mut lhs_count = 0;
-
-	let mut lit = 0u128;
-	let mut lit_count = 0;
-
-	let mut is_lhs = true;
-	let offset = i;
-
-	loop {
-		if lhs_count + lit_count >= 8 {
-			return Ok(None);
-		}
-
-		if is_lhs {
-			if let Some((':', 1)) = get_char(buffer, i)? {
-				i += 1;
-
-				if lhs_count == 0 {
-					if let Some((':', 1)) = get_char(buffer, i)? {
-						i += 1;
-					} else {
-						return Ok(None); // Invalid IPv6 (unexpected char)
-					}
-				}
-
-				lhs = lit;
-				lhs_count += lit_count;
-
-				is_lhs = false;
-
-				lit = 0;
-				lit_count = 1;
-				continue;
-			}
-		}
-
-		if lhs_count + lit_count <= 6 {
-			if let Some((ipv4, len)) = parse_ipv4_literal(buffer, i)? {
-				lit = (lit << 32) | ipv4 as u128;
-				// lit_count += 2;
-				i += len;
-				break;
-			}
-		}
-
-		if let Some((n, len)) = parse_h16(buffer, i)? {
-			lit = (lit << 16) | n as u128;
-			lit_count += 1;
-			i += len;
-
-			match get_char(buffer, i)? {
-				Some((']', 1)) => break,
-				Some((':', 1)) => i += 1,
-				_ => {
-					return Ok(None); // Invalid IPv6 (unexpected char)
-				}
-			}
-		} else {
-			return Ok(None); // Invalid IPv6 (unexpected char)
-		}
-	}
-
-	if lhs_count > 0 {
-		lit |= lhs << (16 * (8 - lhs_count));
-	}
-
-	let len = i - offset;
-	Ok(Some((lit, len)))
+fn scan_query(buffer: &[u8], i: usize) -> Result<usize, ParseError> {
+	scan_chars(buffer, i, Component::Query, is_query_char)
 }
 
-fn parse_ip_literal(buffer: &[u8], mut i: usize) -> Result<Option<usize>, Error> {
-	let offset = i;
-	if let Some(('[', 1)) = get_char(buffer, i)? {
-		i += 1;
-		if let Some((_, l)) = parse_ipv6_literal(buffer, i)? {
-			i += l;
-		} else {
-			return Ok(None); // TODO Ipv future
-		}
-
-		if let Some((']', 1)) = get_char(buffer, i)? {
-			i += 1;
-			let len = i - offset;
-			return Ok(Some(len));
-		}
-	}
-
-	Ok(None)
+/// Parse a fragment, without the `#` delimiter.
+///
+/// `ifragment = *( ipchar / "/" / "?" )`
+#[inline]
+pub fn parse_fragment(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_fragment(buffer, i)?)
 }
 
-fn parse_ireg_name(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
-	let offset = i;
-	loop {
-		match get_char(buffer, i)? {
-			Some(('%', 1)) => {
-				if let Some(len) = parse_pct_encoded(buffer, i)? {
-					i += len
-				} else {
-					break;
-				}
-			}
-			Some((c, len)) if is_subdelim(c) || is_unreserved(c) => i += len,
-			_ => break,
-		}
-	}
-
-	Ok(i - offset)
+fn scan_fragment(buffer: &[u8], i: usize) -> Result<usize, ParseError> {
+	scan_chars(buffer, i, Component::Fragment, is_fragment_char)
 }
 
+/// Parse an authority host.
+///
+/// `ihost = IP-literal / IPv4address / ireg-name`
+///
+/// IP literals are IPv6 addresses enclosed in square brackets, optionally with
+/// a zone identifier (RFC 6874) such as `[fe80::1%25eth0]`.
+/// `IPvFuture` literals are not supported.
+/// IPv4 addresses are parsed as registered names, which they are syntactically;
+/// use [`Host::kind`](crate::Host::kind) to tell them apart.
+///
+/// # Example
+/// ```
+/// use iref::parsing::parse_host;
+///
+/// assert_eq!(parse_host(b"[2001:db8::1]:8080", 0).unwrap(), 13);
+/// assert_eq!(parse_host(b"192.168.0.1", 0).unwrap(), 11);
+/// assert_eq!(parse_host(b"user@caf%C3%A9.fr", 5).unwrap(), 12);
+/// assert!(parse_host(b"ex%ample.org", 0).is_err());
+/// ```
 #[inline]
 pub fn parse_host(buffer: &[u8], i: usize) -> Result<usize, Error> {
-	if let Some(len) = parse_ip_literal(buffer, i)? {
-		Ok(len)
-	} else if let Some((_, len)) = parse_ipv4_literal(buffer, i)? {
-		Ok(len)
+	Ok(scan_host(buffer, i)?)
+}
+
+fn scan_host(buffer: &[u8], i: usize) -> Result<usize, ParseError> {
+	if let Some(end) = validate::scan_ip_literal(buffer, i) {
+		Ok(end - i)
 	} else {
-		parse_ireg_name(buffer, i)
+		// IPv4 addresses are also syntactically valid registered names.
+		scan_chars(buffer, i, Component::Host, is_reg_name_char)
 	}
 }
 
+/// Parse an authority port, without the `:` delimiter.
+///
+/// `port = *DIGIT`
 #[inline]
-pub fn parse_port(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
+pub fn parse_port(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_port(buffer, i)?)
+}
+
+fn scan_port(buffer: &[u8], mut i: usize) -> Result<usize, ParseError> {
 	let offset = i;
 
-	while let Some((c, 1)) = get_char(buffer, i)? {
-		if c.is_digit(10) {
+	while let Some((c, 1)) = char_at(buffer, i, Component::Port)? {
+		if c.is_ascii_digit() {
 			i += 1
 		} else {
 			break;
@@ -635,26 +789,30 @@ pub fn parse_port(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
 	Ok(i - offset)
 }
 
-/// Parse the IRI authority.
+/// Parse an IRI authority, without the `//` prefix.
+///
+/// `iauthority = [ iuserinfo "@" ] ihost [ ":" port ]`
 #[inline]
-pub fn parse_authority(buffer: &[u8], mut i: usize) -> Result<ParsedAuthority, Error> {
+pub fn parse_authority(buffer: &[u8], i: usize) -> Result<ParsedAuthority, Error> {
+	Ok(scan_authority(buffer, i)?)
+}
+
+fn scan_authority(buffer: &[u8], mut i: usize) -> Result<ParsedAuthority, ParseError> {
 	let mut userinfo_len = None;
-	let host_len;
-	let port_len;
 
-	let userinfo_tmp_len = parse_userinfo(buffer, i)?;
-	if let Some(('@', 1)) = get_char(buffer, i + userinfo_tmp_len)? {
+	let userinfo_tmp_len = scan_chars(buffer, i, Component::UserInfo, is_userinfo_char)?;
+	if let Some(('@', 1)) = char_at(buffer, i + userinfo_tmp_len, Component::UserInfo)? {
 		userinfo_len = Some(userinfo_tmp_len);
 		i += userinfo_tmp_len + 1;
 	}
 
-	host_len = parse_host(buffer, i)?;
+	let host_len = scan_host(buffer, i)?;
 	i += host_len;
 
-	port_len = match get_char(buffer, i)? {
+	let port_len = match char_at(buffer, i, Component::Host)? {
 		Some((':', 1)) => {
 			i += 1;
-			Some(parse_port(buffer, i)?)
+			Some(scan_port(buffer, i)?)
 		}
 		_ => None,
 	};
@@ -666,54 +824,24 @@ pub fn parse_authority(buffer: &[u8], mut i: usize) -> Result<ParsedAuthority, E
 	})
 }
 
-/// Parse IRI path.
+/// Parse an IRI path, stopping at the query or fragment.
+///
+/// Any sequence of path characters and `/` is accepted:
+/// the constraints on the path depending on the presence of an authority or scheme
+/// are checked by [`ParsedIriRef::new`].
 #[inline]
-pub fn parse_path(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
-	let start = i;
-
-	loop {
-		match get_char(buffer, i)? {
-			None | Some(('?', _)) | Some(('#', _)) => break,
-			Some(('%', 1)) => {
-				if let Some(len) = parse_pct_encoded(buffer, i)? {
-					i += len
-				} else {
-					break;
-				}
-			}
-			Some((c, len))
-				if is_subdelim(c) || is_unreserved(c) || c == '@' || c == ':' || c == '/' =>
-			{
-				i += len
-			}
-			_ => break,
-		}
-	}
+pub fn parse_path(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_path(buffer, i)?)
+}
 
-	Ok(i - start)
+fn scan_path(buffer: &[u8], i: usize) -> Result<usize, ParseError> {
+	scan_chars(buffer, i, Component::Path, is_path_char)
 }
 
-/// Parse IRI path segment.
+/// Parse an IRI path segment, stopping at the next `/`.
+///
+/// `isegment = *ipchar`
 #[inline]
-pub fn parse_path_segment(buffer: &[u8], mut i: usize) -> Result<usize, Error> {
-	let start = i;
-
-	loop {
-		match get_char(buffer, i)? {
-			None | Some(('?', _)) | Some(('#', _)) | Some(('/', _)) => break,
-			Some(('%', 1)) => {
-				if let Some(len) = parse_pct_encoded(buffer, i)? {
-					i += len
-				} else {
-					break;
-				}
-			}
-			Some((c, len)) if is_subdelim(c) || is_unreserved(c) || c == '@' || c == ':' => {
-				i += len
-			}
-			_ => break,
-		}
-	}
-
-	Ok(i - start)
+pub fn parse_path_segment(buffer: &[u8], i: usize) -> Result<usize, Error> {
+	Ok(scan_chars(buffer, i, Component::Path, is_segment_char)?)
 }
@@ -6,27 +6,49 @@ pub fn expect_byte(buffer: &[u8], i: usize) -> Result<u32, ()> {
 	get_byte(buffer, i).ok_or(())
 }
 
+/// Get a continuation byte (`10xxxxxx`), without its marker bits.
+pub fn expect_continuation_byte(buffer: &[u8], i: usize) -> Result<u32, ()> {
+	let b = expect_byte(buffer, i)?;
+	if b & 0xC0 == 0x80 {
+		Ok(b & 0x3F)
+	} else {
+		Err(())
+	}
+}
+
 /// Return a char and the size of its UTF-8 encoding.
 pub fn get_codepoint(buffer: &[u8], i: usize) -> Result<Option<(u32, u8)>, ()> {
 	if let Some(a) = get_byte(buffer, i) {
 		let r = if a & 0x80 == 0x00 {
 			(a, 1)
 		} else if a & 0xE0 == 0xC0 {
-			let b = expect_byte(buffer, i + 1)?;
+			let b = expect_continuation_byte(buffer, i + 1)?;
 			((a & 0x1F) << 6 | b, 2)
 		} else if a & 0xF0 == 0xE0 {
-			let b = expect_byte(buffer, i + 1)?;
-			let c = expect_byte(buffer, i + 2)?;
-			((a & 0x0F) << 12 | b << 6 | c, 2)
+			let b = expect_continuation_byte(buffer, i + 1)?;
+			let c = expect_continuation_byte(buffer, i + 2)?;
+			((a & 0x0F) << 12 | b << 6 | c, 3)
 		} else if a & 0xF8 == 0xF0 {
-			let b = expect_byte(buffer, i + 1)?;
-			let c = expect_byte(buffer, i + 2)?;
-			let d = expect_byte(buffer, i + 3)?;
-			((a & 0x07) << 18 | b << 12 | c << 6 | d, 3)
+			let b = expect_continuation_byte(buffer, i + 1)?;
+			let c = expect_continuation_byte(buffer, i + 2)?;
+			let d = expect_continuation_byte(buffer, i + 3)?;
+			((a & 0x07) << 18 | b << 12 | c << 6 | d, 4)
 		} else {
 			return Err(());
 		};
 
+		// reject overlong encodings.
+		let min = match r.1 {
+			1 => 0,
+			2 => 0x80,
+			3 => 0x800,
+			_ => 0x10000,
+		};
+
+		if r.0 < min {
+			return Err(());
+		}
+
 		Ok(Some(r))
 	} else {
 		Ok(None)
@@ -35,7 +57,7 @@ pub fn get_codepoint(buffer: &[u8], i: usize) -> Result<Option<(u32, u8)>, ()> {
 
 pub fn get_char(buffer: &[u8], i: usize) -> Result<Option<(char, u8)>, ()> {
 	match get_codepoint(buffer, i) {
-		Ok(Some((codepoint, len))) => match std::char::from_u32(codepoint) {
+		Ok(Some((codepoint, len))) => match core::char::from_u32(codepoint) {
 			Some(c) => Ok(Some((c, len))),
 			None => Err(()),
 		},
@@ -43,3 +65,29 @@ pub fn get_char(buffer: &[u8], i: usize) -> Result<Option<(char, u8)>, ()> {
 		Err(()) => Err(()),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decode() {
+		for s in &["a", "é", "例", "\u{10FFFD}"] {
+			let c = s.chars().next().unwrap();
+			assert_eq!(get_char(s.as_bytes(), 0), Ok(Some((c, s.len() as u8))));
+		}
+
+		let invalid: [&[u8]; 6] = [
+			b"\xC3",
+			b"\xC3a",
+			b"\xE4\xBE",
+			b"\xC0\xAF",
+			b"\xED\xA0\x80",
+			b"\xF8\x88\x80\x80\x80",
+		];
+
+		for bytes in &invalid {
+			assert_eq!(get_char(bytes, 0), Err(()));
+		}
+	}
+}
@@ -0,0 +1,602 @@
+//! Validation-only recognizer.
+//!
+//! It accepts exactly the inputs accepted by [`ParsedIriRef::new`],
+//! but scans bytes with a lookup table instead of decoding every character,
+//! and stops at the first violation without recording any error.
+//! Besides validity, it only returns the component layout, which falls out
+//! of the scan (see [`validate`]).
+//! It is made of constant functions, so that IRI literals can be validated at compile time.
+use super::{ParsedAuthority, ParsedIriRef};
+
+const SCHEME: u8 = 0x01;
+const USERINFO: u8 = 0x02;
+const REG_NAME: u8 = 0x04;
+const PATH: u8 = 0x08;
+const FRAGMENT: u8 = 0x10;
+const UNRESERVED: u8 = 0x20;
+
+/// Character classes of the ASCII characters.
+const ASCII: [u8; 128] = ascii_classes();
+
+const fn ascii_classes() -> [u8; 128] {
+	let mut table = [0; 128];
+	let mut b = 0;
+	while b < 128 {
+		let c = b as u8;
+		let alphanumeric = c.is_ascii_alphanumeric();
+		let unreserved = alphanumeric || matches!(c, b'-' | b'.' | b'_' | b'~');
+		let subdelim = matches!(
+			c,
+			b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+		);
+		let segment = unreserved || subdelim || c == b'@' || c == b':';
+
+		let mut classes = 0;
+		if alphanumeric || matches!(c, b'+' | b'-' | b'.') {
+			classes |= SCHEME
+		}
+		if unreserved || subdelim || c == b':' {
+			classes |= USERINFO
+		}
+		if unreserved || subdelim {
+			classes |= REG_NAME
+		}
+		if segment || c == b'/' {
+			classes |= PATH
+		}
+		if segment || c == b'/' || c == b'?' {
+			classes |= FRAGMENT
+		}
+		if unreserved {
+			classes |= UNRESERVED
+		}
+
+		table[b] = classes;
+		b += 1
+	}
+
+	table
+}
+
+/// Checks if the given bytes are a valid IRI reference.
+///
+/// Same as `ParsedIriRef::new(buffer).is_ok()`, but faster since the component
+/// layout is not computed.
+///
+/// # Example
+/// ```
+/// use iref::is_valid_iri_ref_bytes;
+///
+/// assert!(is_valid_iri_ref_bytes(b"../a/b?q"));
+/// assert!(!is_valid_iri_ref_bytes(b"\xFF"));
+/// ```
+#[inline]
+pub fn is_valid_iri_ref_bytes(buffer: &[u8]) -> bool {
+	validate(buffer).is_some()
+}
+
+/// Checks if the given string is a valid IRI reference.
+///
+/// Same as `IriRef::new(s).is_ok()`, but faster since the component layout is not computed.
+///
+/// # Example
+/// ```
+/// use iref::is_valid_iri_ref;
+///
+/// assert!(!is_valid_iri_ref("//example.org/a b?q"));
+/// assert!(is_valid_iri_ref("//example.org/a%20b?q"));
+/// ```
+#[inline]
+pub fn is_valid_iri_ref(s: &str) -> bool {
+	is_valid_iri_ref_bytes(s.as_bytes())
+}
+
+/// Checks if the given bytes are a valid IRI.
+///
+/// Same as `Iri::new(buffer).is_ok()`, but faster since the component layout is not computed.
+///
+/// # Example
+/// ```
+/// use iref::is_valid_iri_bytes;
+///
+/// assert!(is_valid_iri_bytes(b"https://example.org/"));
+/// assert!(!is_valid_iri_bytes(b"//example.org/"));
+/// ```
+#[inline]
+pub fn is_valid_iri_bytes(buffer: &[u8]) -> bool {
+	validate(buffer).is_some_and(|p| p.scheme_len.is_some())
+}
+
+/// Checks if the given string is a valid IRI.
+///
+/// Same as `Iri::new(s).is_ok()`, but faster since the component layout is not computed.
+///
+/// # Example
+/// ```
+/// use iref::is_valid_iri;
+///
+/// assert!(is_valid_iri("https://example.org/日本"));
+/// assert!(!is_valid_iri("https://example.org/#a#b"));
+/// ```
+#[inline]
+pub fn is_valid_iri(s: &str) -> bool {
+	is_valid_iri_bytes(s.as_bytes())
+}
+
+/// Checks if the given string is a valid IRI, in a constant context.
+///
+/// Same as [`is_valid_iri`], but usable to validate IRI constants at compile time.
+/// See also the [`iri!`](crate::iri) macro, building an [`Iri`](crate::Iri) from a literal
+/// validated at compile time.
+///
+/// # Example
+/// ```
+/// use iref::const_validate;
+///
+/// const HOME: &str = "https://example.org/";
+/// const _: () = assert!(const_validate(HOME));
+/// ```
+#[inline]
+pub const fn const_validate(s: &str) -> bool {
+	matches!(
+		validate(s.as_bytes()),
+		Some(ParsedIriRef {
+			scheme_len: Some(_),
+			..
+		})
+	)
+}
+
+/// Unwrap an option, returning `None` from the enclosing function if it is `None`.
+///
+/// The `?` operator is not available in constant functions.
+macro_rules! try_opt {
+	($e:expr) => {
+		match $e {
+			Some(value) => value,
+			None => return None,
+		}
+	};
+}
+
+/// Validate an IRI reference, following the same steps as
+/// [`ParsedIriRef::parse_detailed`].
+///
+/// Returns the layout of the IRI reference, or `None` if it is invalid.
+/// The layout is only made of the component ends the scan goes through anyway,
+/// so returning it costs a few subtractions: no character is decoded and no
+/// error is recorded. This lets `Iri::from_literal` (used by `iri!`) build
+/// IRI literals at compile time from the same scan, while the `is_valid_*`
+/// functions only keep whether it succeeded.
+pub(crate) const fn validate(bytes: &[u8]) -> Option<ParsedIriRef> {
+	if core::str::from_utf8(bytes).is_err() {
+		return None;
+	}
+
+	let scheme_len = scan_scheme(bytes);
+	let has_scheme = byte_at(bytes, scheme_len, b':');
+	let scheme_end = if has_scheme {
+		if scheme_len == 0 {
+			return None;
+		}
+
+		scheme_len + 1
+	} else {
+		0
+	};
+
+	let mut authority = None;
+	let mut path_start = scheme_end;
+	let mut i = scheme_end;
+	if byte_at(bytes, i, b'/') && byte_at(bytes, i + 1, b'/') {
+		let (parsed_authority, end) = try_opt!(scan_authority(bytes, i + 2));
+		authority = Some(parsed_authority);
+		path_start = end;
+		i = end;
+		if byte_at(bytes, i, b'/') {
+			i = try_opt!(scan(bytes, i, PATH, false));
+		}
+	} else {
+		i = try_opt!(scan(bytes, i, PATH, false));
+	}
+
+	let path_len = i - path_start;
+
+	let mut query_len = None;
+	if byte_at(bytes, i, b'?') {
+		let end = try_opt!(scan(bytes, i + 1, FRAGMENT, true));
+		query_len = Some(end - i - 1);
+		i = end;
+	}
+
+	let mut fragment_len = None;
+	if byte_at(bytes, i, b'#') {
+		let end = try_opt!(scan(bytes, i + 1, FRAGMENT, false));
+		fragment_len = Some(end - i - 1);
+		i = end;
+	}
+
+	if i == bytes.len() {
+		Some(ParsedIriRef {
+			scheme_len: if has_scheme { Some(scheme_len) } else { None },
+			authority,
+			path_len,
+			query_len,
+			fragment_len,
+		})
+	} else {
+		None
+	}
+}
+
+/// Checks if the byte at `i` is `b`.
+#[inline]
+const fn byte_at(bytes: &[u8], i: usize, b: u8) -> bool {
+	i < bytes.len() && bytes[i] == b
+}
+
+/// Checks if the byte at `i` is an ASCII character of class `class`.
+#[inline]
+const fn class_at(bytes: &[u8], i: usize, class: u8) -> bool {
+	i < bytes.len() && bytes[i] < 0x80 && ASCII[bytes[i] as usize] & class != 0
+}
+
+const fn scan_scheme(bytes: &[u8]) -> usize {
+	if bytes.is_empty() || !bytes[0].is_ascii_alphabetic() {
+		return 0;
+	}
+
+	let mut i = 1;
+	while class_at(bytes, i, SCHEME) {
+		i += 1
+	}
+
+	i
+}
+
+/// Scan the authority starting at `i`.
+///
+/// Returns its layout and end.
+const fn scan_authority(bytes: &[u8], mut i: usize) -> Option<(ParsedAuthority, usize)> {
+	let mut userinfo_len = None;
+	let userinfo_end = try_opt!(scan(bytes, i, USERINFO, false));
+	if byte_at(bytes, userinfo_end, b'@') {
+		userinfo_len = Some(userinfo_end - i);
+		i = userinfo_end + 1;
+	}
+
+	let host_end = match scan_ip_literal(bytes, i) {
+		Some(end) => end,
+		None => try_opt!(scan(bytes, i, REG_NAME, false)),
+	};
+
+	let host_len = host_end - i;
+	i = host_end;
+
+	let mut port_len = None;
+	if byte_at(bytes, i, b':') {
+		i += 1;
+		let port_start = i;
+		while i < bytes.len() && bytes[i].is_ascii_digit() {
+			i += 1
+		}
+
+		port_len = Some(i - port_start);
+	}
+
+	let authority = ParsedAuthority {
+		userinfo_len,
+		host_len,
+		port_len,
+	};
+
+	Some((authority, i))
+}
+
+/// Scan the characters of class `class`, percent-encoded characters and non-ASCII characters
+/// allowed in IRIs (`ucschar`, and `iprivate` if `private` is true).
+///
+/// Returns the end of the scanned characters, or `None` on invalid percent-encoding.
+const fn scan(bytes: &[u8], mut i: usize, class: u8, private: bool) -> Option<usize> {
+	while i < bytes.len() {
+		let b = bytes[i];
+		if b < 0x80 {
+			if ASCII[b as usize] & class != 0 {
+				i += 1
+			} else if b == b'%' {
+				i = try_opt!(scan_pct_encoded(bytes, i))
+			} else {
+				break;
+			}
+		} else {
+			let (c, len) = decode_char(bytes, i);
+			if super::is_ucschar(c) || (private && super::is_private(c)) {
+				i += len
+			} else {
+				break;
+			}
+		}
+	}
+
+	Some(i)
+}
+
+/// Scan the percent-encoded character at `i`.
+///
+/// Returns its end, or `None` if it is invalid.
+#[inline]
+const fn scan_pct_encoded(bytes: &[u8], i: usize) -> Option<usize> {
+	if i + 2 < bytes.len() && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit() {
+		Some(i + 3)
+	} else {
+		None
+	}
+}
+
+/// Decode the non-ASCII character starting at `i` in valid UTF-8 bytes.
+const fn decode_char(bytes: &[u8], i: usize) -> (char, usize) {
+	let a = bytes[i] as u32;
+	let (mut c, len) = if a & 0xE0 == 0xC0 {
+		(a & 0x1F, 2)
+	} else if a & 0xF0 == 0xE0 {
+		(a & 0x0F, 3)
+	} else {
+		(a & 0x07, 4)
+	};
+
+	let mut j = 1;
+	while j < len {
+		c = c << 6 | (bytes[i + j] & 0x3F) as u32;
+		j += 1
+	}
+
+	match char::from_u32(c) {
+		Some(c) => (c, len),
+		None => ('\0', len),
+	}
+}
+
+/// Scan the IP literal starting at `i`, if any, returning its end.
+///
+/// `IP-literal = "[" IPv6address [ "%25" ZoneID ] "]"`
+///
+/// This is also used by the parser, so that the IP literal grammar
+/// is only defined here.
+pub(super) const fn scan_ip_literal(bytes: &[u8], i: usize) -> Option<usize> {
+	if !byte_at(bytes, i, b'[') {
+		return None;
+	}
+
+	let mut i = try_opt!(scan_ipv6_literal(bytes, i + 1));
+
+	// Zone identifier (RFC 6874).
+	if i + 2 < bytes.len() && bytes[i] == b'%' && bytes[i + 1] == b'2' && bytes[i + 2] == b'5' {
+		let start = i + 3;
+		let mut end = start;
+		loop {
+			if class_at(bytes, end, UNRESERVED) {
+				end += 1
+			} else if byte_at(bytes, end, b'%') {
+				end = try_opt!(scan_pct_encoded(bytes, end))
+			} else {
+				break;
+			}
+		}
+
+		if end > start {
+			i = end
+		}
+	}
+
+	if byte_at(bytes, i, b']') {
+		Some(i + 1)
+	} else {
+		None
+	}
+}
+
+/// Scan the IPv6 address starting at `i`, returning its end.
+const fn scan_ipv6_literal(bytes: &[u8], mut i: usize) -> Option<usize> {
+	let mut lhs_count = 0;
+	let mut lit_count = 0;
+	let mut is_lhs = true;
+
+	loop {
+		if lhs_count + lit_count >= 8 {
+			return None;
+		}
+
+		if is_lhs && byte_at(bytes, i, b':') {
+			i += 1;
+
+			if lit_count == 0 {
+				if byte_at(bytes, i, b':') {
+					i += 1;
+				} else {
+					return None;
+				}
+			}
+
+			lhs_count += lit_count;
+			is_lhs = false;
+			lit_count = 1;
+			continue;
+		}
+
+		if lhs_count + lit_count <= 6 {
+			if let Some(end) = scan_ipv4_literal(bytes, i) {
+				return Some(end);
+			}
+		}
+
+		let h16_end = scan_h16(bytes, i);
+		if h16_end > i {
+			lit_count += 1;
+			i = h16_end;
+
+			if byte_at(bytes, i, b':') {
+				i += 1
+			} else if byte_at(bytes, i, b']') || byte_at(bytes, i, b'%') {
+				return Some(i);
+			} else {
+				return None;
+			}
+		} else if !is_lhs && lit_count == 1 && (byte_at(bytes, i, b']') || byte_at(bytes, i, b'%'))
+		{
+			// `::` at the end of the address.
+			return Some(i);
+		} else {
+			return None;
+		}
+	}
+}
+
+/// Scan up to 4 hexadecimal digits starting at `i`, returning their end.
+const fn scan_h16(bytes: &[u8], i: usize) -> usize {
+	let mut end = i;
+	while end < bytes.len() && bytes[end].is_ascii_hexdigit() && end - i < 4 {
+		end += 1
+	}
+
+	end
+}
+
+/// Scan the IPv4 address starting at `i`, if any, returning its end.
+const fn scan_ipv4_literal(bytes: &[u8], mut i: usize) -> Option<usize> {
+	let mut n = 0;
+	loop {
+		i = try_opt!(scan_dec_octet(bytes, i));
+		n += 1;
+
+		if n == 4 {
+			return Some(i);
+		}
+
+		if !byte_at(bytes, i, b'.') {
+			return None;
+		}
+
+		i += 1
+	}
+}
+
+/// Scan the decimal octet starting at `i`, if any, returning its end.
+const fn scan_dec_octet(bytes: &[u8], i: usize) -> Option<usize> {
+	let mut octet = 0;
+	let mut len = 0;
+
+	while i + len < bytes.len() && bytes[i + len].is_ascii_digit() {
+		let d = (bytes[i + len] - b'0') as u32;
+		if octet == 25 && d > 5 {
+			break;
+		}
+
+		octet = octet * 10 + d;
+		len += 1;
+
+		if len >= 3 || octet > 25 {
+			break;
+		}
+	}
+
+	if len == 0 {
+		None
+	} else {
+		Some(i + len)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parsing::ParsedIriRef;
+
+	#[test]
+	fn same_as_parser() {
+		let inputs = [
+			"",
+			"a",
+			":",
+			":a",
+			"1a:b",
+			"a:",
+			"http://example.org",
+			"http://example.org:",
+			"http://example.org:80/a",
+			"http://example.org:8x/a",
+			"http://u:p@example.org/",
+			"http://u@p@example.org/",
+			"http://[::1]:8080/",
+			"http://[fe80::1%25eth0]/",
+			"http://[::1/",
+			"http://[v1.x]/",
+			"http://exa%2Emple.org/",
+			"http://exa%2mple.org/",
+			"http://ex ample.org/",
+			"http://example.org?q#f",
+			"http://example.org#f?q",
+			"http://example.org/#a#b",
+			"//a",
+			"//",
+			"///a",
+			"a//b",
+			"?q",
+			"#f",
+			"a b",
+			"a%",
+			"a%4",
+			"a%41",
+			"a|b",
+			"https://example.org/日本?é#ü",
+			"https://例え.jp/",
+			"a/\u{E000}",
+			"a?\u{E000}",
+			"a#\u{E000}",
+			"a?\u{FFFE}",
+			"foo:a/b/../c;p?q=1&r=2#s",
+			"mailto:me@example.org",
+			"urn:isbn:0451450523",
+			"http://[1:2:3:4:5:6:7:8]/",
+			"http://[1:2:3:4:5:6:7:8:9]/",
+			"http://[1::2:3]/",
+			"http://[1::2::3]/",
+			"http://[::ffff:192.0.2.1]/",
+			"http://[::ffff:192.0.2]/",
+			"http://[::ffff:256.0.2.1]/",
+			"http://[::]/",
+			"http://[:1]/",
+			"http://[12345::]/",
+			"http://[fe80::1%25]/",
+			"http://[fe80::1%25a%zz]/",
+			"http://[fe80::1%25a%41]/",
+			"http://[v1.x]:80/",
+			"http://a%41:b@[::1]:/",
+		];
+
+		for input in &inputs {
+			let expected = ParsedIriRef::new(input).ok();
+			assert_eq!(
+				is_valid_iri_ref(input),
+				expected.is_some(),
+				"IRI reference {:?}",
+				input
+			);
+			assert_eq!(
+				is_valid_iri(input),
+				expected.is_some_and(|p| p.scheme_len.is_some()),
+				"IRI {:?}",
+				input
+			);
+			assert_eq!(
+				format!("{:?}", validate(input.as_bytes())),
+				format!("{:?}", expected),
+				"layout of {:?}",
+				input
+			);
+		}
+
+		assert!(!is_valid_iri_ref_bytes(b"a/\xC3"));
+		assert!(!is_valid_iri_ref_bytes(b"a/\xC3\x28"));
+		assert!(is_valid_iri_ref_bytes(b"a/\xC3\xA9"));
+	}
+}
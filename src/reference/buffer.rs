@@ -1,17 +1,22 @@
-use std::{
+use core::{
 	cmp::{Ord, Ordering, PartialOrd},
-	convert::TryInto,
+	convert::{TryFrom, TryInto},
 	fmt,
 	hash::{Hash, Hasher},
 	ops::Range,
 	str::FromStr,
 };
 
-use pct_str::PctStr;
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
+};
+use pct_str::{IriReserved, PctStr};
 
 use crate::{
-	parsing::ParsedIriRef, AsIriRef, Authority, AuthorityMut, Error, Fragment, Iri, IriBuf, IriRef,
-	Path, PathBuf, PathMut, Query, Scheme,
+	parsing::ParsedIriRef, AsIriRef, Authority, AuthorityMut, Comparison, ComponentRanges, Error,
+	Fragment, IntoSegments, Iri, IriBuf, IriRef, NormalizedDisplay, Path, PathBuf, PathMut,
+	PctOctets, Query, ResolutionKind, ResolveOptions, Scheme, Segment,
 };
 
 /// Owned IRI-reference.
@@ -40,7 +45,11 @@ use crate::{
 /// ```
 ///
 /// See the [`IriRef`] type for more informations about IRI-references.
-#[derive(Default, Clone)]
+///
+/// Like [`IriBuf`], this type cannot dereference to the borrowed [`IriRef<'a>`](IriRef),
+/// which is a value holding a `&'a [u8]` rather than a view type:
+/// use [`as_iri_ref`](IriRefBuf::as_iri_ref) or the [`AsIriRef`] trait instead.
+#[derive(Default)]
 pub struct IriRefBuf {
 	pub(crate) p: ParsedIriRef,
 	pub(crate) data: Vec<u8>,
@@ -55,6 +64,230 @@ impl IriRefBuf {
 		})
 	}
 
+	/// Build an IRI reference from its components.
+	///
+	/// Each component is validated once, then the IRI reference is assembled in a single
+	/// allocation of the exact resulting length, without parsing it again.
+	/// This is faster than a sequence of `set_*` calls, each one moving the
+	/// following components.
+	///
+	/// Fails with the error of the first invalid component, or with [`Error::InvalidPath`]
+	/// if the path cannot follow the other components: with an authority, the path must be
+	/// empty or start with `/`. Without authority, it must not start with `//`, and without
+	/// scheme either, its first segment must not contain a `:`.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let iri_ref = IriRefBuf::from_parts(None, Some("example.org"), "/a", Some("q"), None)?;
+	/// assert_eq!(iri_ref.as_str(), "//example.org/a?q");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn from_parts(
+		scheme: Option<&str>,
+		authority: Option<&str>,
+		path: &str,
+		query: Option<&str>,
+		fragment: Option<&str>,
+	) -> Result<IriRefBuf, Error> {
+		let scheme = match scheme {
+			Some(scheme) => Some(Scheme::try_from(scheme)?),
+			None => None,
+		};
+
+		let authority = match authority {
+			Some(authority) => Some(Authority::try_from(authority)?),
+			None => None,
+		};
+
+		let path = Path::try_from(path)?;
+		if is_ambiguous_path(path, scheme.is_some(), authority.is_some()) {
+			return Err(Error::InvalidPath);
+		}
+
+		let query = match query {
+			Some(query) => Some(Query::try_from(query)?),
+			None => None,
+		};
+
+		let fragment = match fragment {
+			Some(fragment) => Some(Fragment::try_from(fragment)?),
+			None => None,
+		};
+
+		let p = ParsedIriRef {
+			scheme_len: scheme.map(|s| s.as_bytes().len()),
+			authority: authority.map(|a| a.p),
+			path_len: path.len(),
+			query_len: query.map(|q| q.as_bytes().len()),
+			fragment_len: fragment.map(|f| f.as_bytes().len()),
+		};
+
+		let mut data = Vec::with_capacity(p.len());
+		if let Some(scheme) = scheme {
+			data.extend_from_slice(scheme.as_bytes());
+			data.push(b':');
+		}
+
+		if let Some(authority) = authority {
+			data.extend_from_slice(b"//");
+			data.extend_from_slice(authority.as_bytes());
+		}
+
+		data.extend_from_slice(path.as_bytes());
+
+		if let Some(query) = query {
+			data.push(b'?');
+			data.extend_from_slice(query.as_bytes());
+		}
+
+		if let Some(fragment) = fragment {
+			data.push(b'#');
+			data.extend_from_slice(fragment.as_bytes());
+		}
+
+		Ok(IriRefBuf { p, data })
+	}
+
+	/// Create an empty IRI reference with at least the given capacity, in bytes.
+	///
+	/// The IRI reference can then grow up to `capacity` bytes, for instance by pushing path
+	/// segments, without reallocating.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// let mut iri_ref = IriRefBuf::with_capacity(64);
+	/// assert!(iri_ref.is_empty());
+	/// assert!(iri_ref.capacity() >= 64);
+	///
+	/// iri_ref.path_mut().extend(["a", "b", "c"]).unwrap();
+	/// assert_eq!(iri_ref, "a/b/c");
+	/// ```
+	#[inline]
+	pub fn with_capacity(capacity: usize) -> IriRefBuf {
+		IriRefBuf {
+			p: ParsedIriRef::default(),
+			data: Vec::with_capacity(capacity),
+		}
+	}
+
+	/// Create a fragment-only reference, such as `#frag`, with an empty path.
+	///
+	/// Resolved against a base IRI, it keeps the whole base but its fragment.
+	/// Returns [`Error::InvalidFragment`] if `fragment` is not a valid fragment.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Iri, IriRefBuf};
+	/// let iri_ref = IriRefBuf::fragment_only("section-2").unwrap();
+	/// assert_eq!(iri_ref, "#section-2");
+	///
+	/// let base = Iri::new("http://a/b/c?q#f").unwrap();
+	/// assert_eq!(iri_ref.resolved(base), "http://a/b/c?q#section-2");
+	/// ```
+	#[inline]
+	pub fn fragment_only(fragment: &str) -> Result<IriRefBuf, Error> {
+		IriRefBuf::from_parts(None, None, "", None, Some(fragment))
+	}
+
+	/// Create a query-only reference, such as `?q`, with an empty path.
+	///
+	/// Resolved against a base IRI, it keeps the scheme, authority and path of the base,
+	/// with the new query and no fragment.
+	/// Returns [`Error::InvalidQuery`] if `query` is not a valid query.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Iri, IriRefBuf};
+	/// let iri_ref = IriRefBuf::query_only("page=2").unwrap();
+	/// assert_eq!(iri_ref, "?page=2");
+	///
+	/// let base = Iri::new("http://a/b/c?q#f").unwrap();
+	/// assert_eq!(iri_ref.resolved(base), "http://a/b/c?page=2");
+	/// ```
+	#[inline]
+	pub fn query_only(query: &str) -> Result<IriRefBuf, Error> {
+		IriRefBuf::from_parts(None, None, "", Some(query), None)
+	}
+
+	/// Create a path-only reference from the given segments.
+	///
+	/// The segments are added as with [`PathMut::push`], so that
+	/// [`segments`](Path::segments) yields them back: an empty last segment gives a path
+	/// ending with `//`.
+	/// Each segment must be valid and already percent-encoded (see
+	/// [`encode_path_segment`](crate::encode_path_segment)):
+	/// a segment containing a `/` is rejected with [`Error::InvalidSegment`].
+	/// The segments are all validated before the buffer is allocated, at once.
+	/// A `./` is added in front of the path when needed to keep it unambiguous,
+	/// for instance if its first segment contains a `:`.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// let iri_ref = IriRefBuf::from_segments(["a", "b%2Fc", "d"], true).unwrap();
+	/// assert_eq!(iri_ref, "/a/b%2Fc/d");
+	///
+	/// let iri_ref = IriRefBuf::from_segments(vec!["a:b".to_string()], false).unwrap();
+	/// assert_eq!(iri_ref, "./a:b");
+	///
+	/// assert!(IriRefBuf::from_segments(["a/b"], false).is_err());
+	/// ```
+	pub fn from_segments<I, S>(segments: I, absolute: bool) -> Result<IriRefBuf, Error>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let segments: Vec<S> = segments.into_iter().collect();
+		let mut len = 2;
+		for segment in &segments {
+			if Segment::try_from(segment.as_ref())?.is_open() {
+				return Err(Error::InvalidSegment);
+			}
+
+			len += segment.as_ref().len() + 1;
+		}
+
+		let mut iri_ref = IriRefBuf::with_capacity(len);
+		let mut path = iri_ref.path_mut();
+		path.set_absolute(absolute);
+		for segment in &segments {
+			path.push(Segment::try_from(segment.as_ref())?);
+		}
+
+		Ok(iri_ref)
+	}
+
+	/// Returns the number of bytes the IRI reference can hold without reallocating.
+	#[inline]
+	pub fn capacity(&self) -> usize {
+		self.data.capacity()
+	}
+
+	/// Reserve capacity for at least `additional` more bytes.
+	///
+	/// See [`Vec::reserve`].
+	#[inline]
+	pub fn reserve(&mut self, additional: usize) {
+		self.data.reserve(additional)
+	}
+
+	/// Shrink the capacity of the underlying buffer as much as possible.
+	///
+	/// Removing components or path segments never releases memory:
+	/// call this method to free the unused capacity of a long-lived IRI reference.
+	///
+	/// See [`Vec::shrink_to_fit`].
+	#[inline]
+	pub fn shrink_to_fit(&mut self) {
+		let len = self.len();
+		self.data.truncate(len);
+		self.data.shrink_to_fit()
+	}
+
 	/// Consume the IRI buffer and return its constituing parts:
 	/// the internal buffer and parsing data.
 	#[inline]
@@ -62,12 +295,24 @@ impl IriRefBuf {
 		(self.data, self.p)
 	}
 
-	/// Consume the IRI reference and return its internal buffer.
+	/// Consume the IRI reference and return its internal buffer,
+	/// truncated to the [length](IriRefBuf::len) of the IRI reference.
 	#[inline]
-	pub fn into_bytes(self) -> Vec<u8> {
+	pub fn into_bytes(mut self) -> Vec<u8> {
+		let len = self.len();
+		self.data.truncate(len);
 		self.data
 	}
 
+	/// Consume the IRI reference and return its internal buffer as a string,
+	/// truncated to the [length](IriRefBuf::len) of the IRI reference.
+	///
+	/// The buffer is moved, not copied.
+	#[inline]
+	pub fn into_string(self) -> String {
+		unsafe { String::from_utf8_unchecked(self.into_bytes()) }
+	}
+
 	#[inline]
 	pub fn as_iri_ref(&self) -> IriRef {
 		IriRef {
@@ -76,6 +321,65 @@ impl IriRefBuf {
 		}
 	}
 
+	/// Return the syntax-normalized form of this IRI reference.
+	///
+	/// See [`IriRef::normalized`].
+	#[inline]
+	pub fn normalized(&self) -> IriRefBuf {
+		self.as_iri_ref().normalized()
+	}
+
+	/// Return the canonical form of this IRI reference.
+	///
+	/// See [`IriRef::canonical`].
+	#[inline]
+	pub fn canonical(&self) -> IriRefBuf {
+		self.as_iri_ref().canonical()
+	}
+
+	/// See [`IriRef::display_normalized`].
+	#[inline]
+	pub fn display_normalized(&self) -> NormalizedDisplay<'_> {
+		self.as_iri_ref().display_normalized()
+	}
+
+	/// Write the bytes of this IRI reference to `writer`, returning the number of bytes written.
+	///
+	/// See [`IriRef::write_to`].
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<usize> {
+		self.as_iri_ref().write_to(writer)
+	}
+
+	/// Write the syntax-normalized form of this IRI reference to `writer`,
+	/// returning the number of bytes written.
+	///
+	/// See [`IriRef::write_normalized_to`].
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn write_normalized_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<usize> {
+		self.as_iri_ref().write_normalized_to(writer)
+	}
+
+	/// Map this IRI reference to a URI reference,
+	/// percent-encoding every non-ASCII character.
+	///
+	/// See [`IriRef::to_uri`].
+	#[inline]
+	pub fn to_uri(&self) -> IriRefBuf {
+		self.as_iri_ref().to_uri()
+	}
+
+	/// Map this URI reference to an IRI reference,
+	/// decoding percent-encoded non-ASCII characters where allowed.
+	///
+	/// See [`IriRef::to_iri`].
+	#[inline]
+	pub fn to_iri(&self) -> Result<IriRefBuf, Error> {
+		self.as_iri_ref().to_iri()
+	}
+
 	#[inline]
 	pub fn as_iri(&self) -> Result<Iri, Error> {
 		self.try_into()
@@ -87,19 +391,93 @@ impl IriRefBuf {
 		self.p.len()
 	}
 
+	/// See [`IriRef::scheme_len`].
+	#[inline]
+	pub fn scheme_len(&self) -> usize {
+		self.as_iri_ref().scheme_len()
+	}
+
+	/// See [`IriRef::authority_len`].
+	#[inline]
+	pub fn authority_len(&self) -> usize {
+		self.as_iri_ref().authority_len()
+	}
+
+	/// See [`IriRef::path_len`].
+	#[inline]
+	pub fn path_len(&self) -> usize {
+		self.p.path_len
+	}
+
+	/// See [`IriRef::query_len`].
+	#[inline]
+	pub fn query_len(&self) -> usize {
+		self.as_iri_ref().query_len()
+	}
+
+	/// See [`IriRef::fragment_len`].
+	#[inline]
+	pub fn fragment_len(&self) -> usize {
+		self.as_iri_ref().fragment_len()
+	}
+
 	#[inline]
 	pub fn is_empty(&self) -> bool {
 		self.p.is_empty()
 	}
 
+	/// Checks if the IRI-reference is absolute, meaning that it has a scheme.
+	///
+	/// See [`IriRef::is_absolute`].
+	#[inline]
+	pub fn is_absolute(&self) -> bool {
+		self.p.scheme_len.is_some()
+	}
+
+	/// Checks if the IRI-reference is an absolute IRI without fragment.
+	///
+	/// See [`IriRef::is_absolute_uri`].
+	#[inline]
+	pub fn is_absolute_uri(&self) -> bool {
+		self.p.scheme_len.is_some() && self.p.fragment_len.is_none()
+	}
+
+	/// Checks if the IRI-reference is a relative reference, meaning that it has no scheme.
+	///
+	/// See [`IriRef::is_relative`].
+	#[inline]
+	pub fn is_relative(&self) -> bool {
+		!self.is_absolute()
+	}
+
+	/// Checks if the IRI-reference is a network-path reference,
+	/// with an authority but no scheme.
+	///
+	/// See [`IriRef::is_protocol_relative`].
+	#[inline]
+	pub fn is_protocol_relative(&self) -> bool {
+		self.p.scheme_len.is_none() && self.p.authority.is_some()
+	}
+
+	/// Checks if the path would escape the directory of the base IRI it is resolved against.
+	///
+	/// See [`IriRef::path_escapes_root`].
+	#[inline]
+	pub fn path_escapes_root(&self) -> bool {
+		self.as_iri_ref().path_escapes_root()
+	}
+
+	/// Returns a reference to the byte representation of the IRI reference.
+	///
+	/// Only the first [`len`](IriRefBuf::len) bytes of the internal buffer are returned.
 	#[inline]
 	pub fn as_bytes(&self) -> &[u8] {
-		self.data.as_ref()
+		&self.data[0..self.len()]
 	}
 
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(&self.data[0..self.len()]) }
+		unsafe { core::str::from_utf8_unchecked(&self.data[0..self.len()]) }
 	}
 
 	#[inline]
@@ -170,8 +548,9 @@ impl IriRefBuf {
 
 	/// Set the authority of the IRI.
 	///
-	/// It must be a syntactically correct authority. If not,
-	/// this method returns an error, and the IRI is unchanged.
+	/// The authority is already validated: it is copied as is, along with its layout,
+	/// without being parsed again.
+	/// To change only one of its subcomponents, use [`authority_mut`](IriRefBuf::authority_mut).
 	#[inline]
 	pub fn set_authority(&mut self, authority: Option<Authority>) {
 		let offset = self.p.authority_offset();
@@ -195,6 +574,17 @@ impl IriRefBuf {
 		}
 	}
 
+	/// Parse and set the host of the authority.
+	///
+	/// Returns [`Error::MissingAuthority`] if the IRI reference has no authority.
+	/// See [`AuthorityMut::set_host_str`].
+	#[inline]
+	pub fn set_host_str(&mut self, host: &str) -> Result<(), Error> {
+		self.authority_mut()
+			.ok_or(Error::MissingAuthority)?
+			.set_host_str(host)
+	}
+
 	#[inline]
 	pub fn path(&self) -> Path {
 		let offset = self.p.path_offset();
@@ -208,11 +598,246 @@ impl IriRefBuf {
 		PathMut { buffer: self }
 	}
 
+	/// Consume the IRI reference and iterate over the segments of its path.
+	///
+	/// The segments are yielded as owned `String`s, still percent-encoded, from the front
+	/// or the back.
+	/// To iterate over borrowed segments, iterate over the [`path`](IriRefBuf::path) instead.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// let iri_ref = IriRefBuf::new("//h/a/b%20c/d?q").unwrap();
+	/// let segments: Vec<String> = iri_ref.into_segments().rev().collect();
+	/// assert_eq!(segments, ["d", "b%20c", "a"]);
+	/// ```
+	#[inline]
+	pub fn into_segments(mut self) -> IntoSegments {
+		let offset = self.p.path_offset();
+		self.data.truncate(offset + self.p.path_len);
+		self.data.drain(..offset);
+		IntoSegments::new(self.data)
+	}
+
+	/// Set the path of the IRI reference.
+	///
+	/// The path is replaced in place: the existing buffer is reused, and only grows if the new
+	/// path does not fit in its capacity, which never shrinks.
+	/// A path of the same length as the current one is overwritten in place, with
+	/// [`replace_path_in_place`](IriRefBuf::replace_path_in_place), without moving the
+	/// following components.
+	/// Setting many paths in a row thus only reallocates when a path longer than all the
+	/// previous ones is set.
 	#[inline]
 	pub fn set_path(&mut self, path: Path) {
+		if !self.replace_path_in_place(path) {
+			let offset = self.p.path_offset();
+			self.replace(offset..(offset + self.p.path_len), path.as_ref());
+			self.p.path_len = path.as_ref().len()
+		}
+	}
+
+	/// Overwrite the path of the IRI reference with a path of the same length.
+	///
+	/// Only the bytes of the path are copied: the query and fragment stay in place.
+	/// Returns `false`, and leaves the IRI reference unchanged, if `path` does not have the
+	/// same length as the current path.
+	///
+	/// # Example
+	/// ```
+	/// # use std::convert::TryFrom;
+	/// # use iref::{IriRefBuf, Path};
+	/// let mut iri_ref = IriRefBuf::new("//h/a/1?q").unwrap();
+	/// assert!(iri_ref.replace_path_in_place(Path::try_from("/b/2").unwrap()));
+	/// assert_eq!(iri_ref, "//h/b/2?q");
+	/// assert!(!iri_ref.replace_path_in_place(Path::try_from("/b/22").unwrap()));
+	/// assert_eq!(iri_ref, "//h/b/2?q");
+	/// ```
+	#[inline]
+	pub fn replace_path_in_place(&mut self, path: Path) -> bool {
+		if path.len() != self.p.path_len {
+			return false;
+		}
+
 		let offset = self.p.path_offset();
-		self.replace(offset..(offset + self.p.path_len), path.as_ref());
-		self.p.path_len = path.as_ref().len()
+		self.data[offset..(offset + self.p.path_len)].copy_from_slice(path.as_ref());
+		true
+	}
+
+	/// Set the path and query of the IRI reference from a single `path?query` string,
+	/// such as the target of an HTTP request.
+	///
+	/// The string is split at its first `?`: the query is removed if there is none.
+	/// Both parts are validated, then spliced into the buffer in a single operation,
+	/// leaving the fragment untouched.
+	///
+	/// This fails with [`Error::InvalidPath`] if the path is invalid or would change the
+	/// meaning of the IRI reference (for instance a relative path after an authority),
+	/// and with [`Error::InvalidQuery`] if the query is invalid.
+	/// The IRI reference is then unchanged.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("https://example.org/a?q#f")?;
+	/// iri_ref.set_path_and_query("/new/path?x=1")?;
+	/// assert_eq!(iri_ref, "https://example.org/new/path?x=1#f");
+	///
+	/// iri_ref.set_path_and_query("/b")?;
+	/// assert_eq!(iri_ref, "https://example.org/b#f");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn set_path_and_query(&mut self, path_and_query: &str) -> Result<(), Error> {
+		let (path, query) = match path_and_query.find('?') {
+			Some(i) => (&path_and_query[..i], Some(&path_and_query[(i + 1)..])),
+			None => (path_and_query, None),
+		};
+
+		let path = Path::try_from(path)?;
+		if let Some(query) = query {
+			Query::try_from(query)?;
+		}
+
+		if is_ambiguous_path(
+			path,
+			self.p.scheme_len.is_some(),
+			self.p.authority.is_some(),
+		) {
+			return Err(Error::InvalidPath);
+		}
+
+		let offset = self.p.path_offset();
+		let end = match self.p.query_len {
+			Some(query_len) => self.p.query_offset() + query_len,
+			None => offset + self.p.path_len,
+		};
+
+		self.replace(offset..end, path_and_query.as_bytes());
+		self.p.path_len = path.len();
+		self.p.query_len = query.map(str::len);
+		Ok(())
+	}
+
+	/// Return a copy of this IRI reference with `path` appended to its path,
+	/// in the manner of [`std::path::Path::join`].
+	///
+	/// See [`join_mut`](IriRefBuf::join_mut).
+	#[inline]
+	pub fn join(&self, path: &str) -> Result<IriRefBuf, Error> {
+		let mut result = self.clone();
+		result.join_mut(path)?;
+		Ok(result)
+	}
+
+	/// Append `path` to the path of this IRI reference.
+	///
+	/// A single `/` separator is inserted between the current path and `path`,
+	/// unless the current path ends with `/` or is empty (with no authority).
+	/// If `path` starts with `/`, it replaces the whole path instead.
+	/// Repeated slashes at the junction are collapsed into one.
+	/// The query and fragment are preserved.
+	///
+	/// Contrarily to [reference resolution](IriRefBuf::resolve), the last segment of the
+	/// current path is never removed, and dot segments are kept as is.
+	///
+	/// This fails with [`Error::InvalidPath`] if `path` is not a valid IRI path,
+	/// in which case the IRI reference is unchanged.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("https://example.org/a/b?q")?;
+	/// iri_ref.join_mut("c/d")?;
+	/// assert_eq!(iri_ref, "https://example.org/a/b/c/d?q");
+	///
+	/// iri_ref.join_mut("/e")?;
+	/// assert_eq!(iri_ref, "https://example.org/e?q");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn join_mut(&mut self, path: &str) -> Result<(), Error> {
+		let current = self.path();
+		let mut new_path = String::new();
+
+		let relative = match path.strip_prefix('/') {
+			Some(rest) => rest,
+			None => {
+				new_path.push_str(current.as_str());
+				path
+			}
+		};
+
+		if !new_path.ends_with('/')
+			&& (!new_path.is_empty() || self.p.authority.is_some() || path.starts_with('/'))
+		{
+			new_path.push('/')
+		}
+
+		new_path.push_str(relative.trim_start_matches('/'));
+
+		let new_path = Path::try_from(new_path.as_str())?;
+		self.set_path(new_path);
+		Ok(())
+	}
+
+	/// Replace or remove the extension of the final path segment,
+	/// in the manner of [`std::path::PathBuf::set_extension`].
+	///
+	/// The current extension, as returned by [`Path::extension`], is replaced by `extension`,
+	/// or added if there is none. If `extension` is `None` or empty, the current extension
+	/// is removed with its `.` separator.
+	/// The extension is percent-encoded as needed, except for `.`.
+	///
+	/// Returns `false` and does nothing if the final segment cannot have an extension:
+	/// if the path is empty, ends with a `/`, or ends with a `.` or `..` segment.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("/doc/report.html?lang=en")?;
+	/// iri_ref.set_extension(Some("json"));
+	/// assert_eq!(iri_ref, "/doc/report.json?lang=en");
+	///
+	/// iri_ref.set_extension(None);
+	/// assert_eq!(iri_ref, "/doc/report?lang=en");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn set_extension(&mut self, extension: Option<&str>) -> bool {
+		let path = self.path();
+		if path.is_open() {
+			return false;
+		}
+
+		let name = match path.segments().next_back() {
+			Some(name) => name.into_str(),
+			None => return false,
+		};
+
+		if name.is_empty() || name == "." || name == ".." {
+			return false;
+		}
+
+		let stem_len = match crate::split_extension(name) {
+			Some((stem, _)) => stem.len(),
+			None => name.len(),
+		};
+
+		let mut new_extension = String::new();
+		if let Some(extension) = extension.filter(|e| !e.is_empty()) {
+			new_extension.push('.');
+			crate::pct_encode(extension, IriReserved::Path, &mut new_extension);
+		}
+
+		let end = self.p.path_offset() + self.p.path_len;
+		let start = end - name.len() + stem_len;
+		self.replace(start..end, new_extension.as_bytes());
+		self.p.path_len = self.p.path_len - (end - start) + new_extension.len();
+		true
 	}
 
 	#[inline]
@@ -235,31 +860,390 @@ impl IriRefBuf {
 			if let Some(query_len) = self.p.query_len {
 				self.replace(offset..(offset + query_len), new_query.as_ref());
 			} else {
-				self.replace(offset..offset, b":");
+				self.replace(offset..offset, b"?");
 				self.replace((offset + 1)..(offset + 1), new_query.as_ref());
 			}
 
-			self.p.query_len = Some(new_query.as_ref().len());
-		} else {
-			if let Some(query_len) = self.p.query_len {
-				self.replace((offset - 1)..(offset + query_len), &[]);
-			}
+			self.p.query_len = Some(new_query.as_ref().len());
+		} else {
+			if let Some(query_len) = self.p.query_len {
+				self.replace((offset - 1)..(offset + query_len), &[]);
+			}
+
+			self.p.query_len = None;
+		}
+	}
+
+	/// Convert the host to its ASCII form, using the IDNA `ToASCII` operation.
+	///
+	/// Does nothing if there is no authority.
+	/// See [`AuthorityMut::host_to_ascii`].
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("//例え.jp/path")?;
+	/// iri_ref.host_to_ascii()?;
+	/// assert_eq!(iri_ref, "//xn--r8jz45g.jp/path");
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "idna")]
+	#[inline]
+	pub fn host_to_ascii(&mut self) -> Result<(), Error> {
+		match self.authority_mut() {
+			Some(mut authority) => authority.host_to_ascii(),
+			None => Ok(()),
+		}
+	}
+
+	/// Convert the host to its Unicode form, using the IDNA `ToUnicode` operation.
+	///
+	/// Does nothing if there is no authority.
+	/// See [`AuthorityMut::host_to_unicode`].
+	#[cfg(feature = "idna")]
+	#[inline]
+	pub fn host_to_unicode(&mut self) -> Result<(), Error> {
+		match self.authority_mut() {
+			Some(mut authority) => authority.host_to_unicode(),
+			None => Ok(()),
+		}
+	}
+
+	/// Remove the port if its number is `default`.
+	///
+	/// This crate knows nothing about schemes and their default ports,
+	/// hence the default port is given by the caller.
+	/// The port number is compared numerically, so `:080` is removed for `80`
+	/// (see [`Authority::port_u16`]).
+	/// Returns `true` if the port was removed.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("https://example.org:443/")?;
+	/// assert!(!iri_ref.remove_default_port(80));
+	/// assert!(iri_ref.remove_default_port(443));
+	/// assert_eq!(iri_ref, "https://example.org/");
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[inline]
+	pub fn remove_default_port(&mut self, default: u16) -> bool {
+		match self.authority_mut() {
+			Some(mut authority) if authority.port_u16() == Some(default) => {
+				authority.set_port(None);
+				true
+			}
+			_ => false,
+		}
+	}
+
+	/// Normalize the percent-encoded characters of every component.
+	///
+	/// This is the percent-encoding normalization of
+	/// [RFC 3986 section 6.2.2.2](https://tools.ietf.org/html/rfc3986#section-6.2.2.2):
+	/// percent-encoded unreserved ASCII characters (letters, digits, `-`, `.`, `_` and `~`)
+	/// are decoded, and the hexadecimal digits of the remaining percent-encoded octets
+	/// are uppercased.
+	/// Reserved characters stay encoded since their encoding is significant:
+	/// `%2F` in a path segment is not a segment separator.
+	/// Use [`to_iri`](IriRefBuf::to_iri) to also decode non-ASCII characters.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("/%7euser/a%2fb?%41%3d")?;
+	/// iri_ref.normalize_percent_encoding();
+	/// assert_eq!(iri_ref.as_str(), "/~user/a%2Fb?A%3D");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn normalize_percent_encoding(&mut self) {
+		let bytes = self.as_bytes();
+		let mut data = Vec::with_capacity(bytes.len());
+		let mut i = 0;
+		while i < bytes.len() {
+			if bytes[i] == b'%' {
+				// The parser guarantees two hexadecimal digits.
+				let hex = &bytes[(i + 1)..(i + 3)];
+				let byte = hex.iter().fold(0, |acc, d| {
+					acc << 4 | (*d as char).to_digit(16).unwrap() as u8
+				});
+				if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+					data.push(byte)
+				} else {
+					data.push(b'%');
+					data.extend(hex.iter().map(u8::to_ascii_uppercase))
+				}
+
+				i += 3
+			} else {
+				data.push(bytes[i]);
+				i += 1
+			}
+		}
+
+		// Decoded unreserved characters are valid anywhere a percent-encoded one is.
+		*self = IriRefBuf::new(&data).unwrap()
+	}
+
+	/// Set the value of the query parameter `key`, or add it at the end of the query.
+	///
+	/// If the parameter appears multiple times, the first occurrence is updated and the
+	/// others are removed. Other parameters are left untouched.
+	/// The key and value are percent-encoded as needed.
+	/// A `None` value gives a parameter without `=`.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// # fn main() -> Result<(), iref::Error> {
+	/// let mut iri_ref = IriRefBuf::new("/search?q=iri&page=1")?;
+	/// iri_ref.set_query_param("page", Some("2"));
+	/// iri_ref.set_query_param("lang", Some("en&fr"));
+	/// assert_eq!(iri_ref, "/search?q=iri&page=2&lang=en%26fr");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn set_query_param(&mut self, key: &str, value: Option<&str>) {
+		let new_pair = Query::encode_pair(key, value);
+		let mut pairs = Vec::new();
+		let mut found = false;
+
+		if let Some(query) = self.query() {
+			for (pair, pair_key) in query.raw_pairs() {
+				if pair_key == key {
+					if !found {
+						pairs.push(new_pair.clone());
+						found = true;
+					}
+				} else {
+					pairs.push(pair.to_string())
+				}
+			}
+		}
+
+		if !found {
+			pairs.push(new_pair)
+		}
+
+		self.set_query_pairs(&pairs)
+	}
+
+	/// Add the query parameter `key` at the end of the query,
+	/// even if it is already present.
+	///
+	/// The key and value are percent-encoded as needed.
+	pub fn append_query_param(&mut self, key: &str, value: Option<&str>) {
+		let mut pairs: Vec<String> = match self.query() {
+			Some(query) => query
+				.raw_pairs()
+				.into_iter()
+				.map(|(pair, _)| pair.to_string())
+				.collect(),
+			None => Vec::new(),
+		};
+
+		pairs.push(Query::encode_pair(key, value));
+		self.set_query_pairs(&pairs)
+	}
+
+	/// Remove every occurrence of the query parameter `key`.
+	///
+	/// The query is removed (including the `?` delimiter) if it becomes empty.
+	pub fn remove_query_param(&mut self, key: &str) {
+		let pairs: Vec<String> = match self.query() {
+			Some(query) => {
+				let raw_pairs = query.raw_pairs();
+				let len = raw_pairs.len();
+				let pairs: Vec<String> = raw_pairs
+					.into_iter()
+					.filter(|(_, pair_key)| pair_key != key)
+					.map(|(pair, _)| pair.to_string())
+					.collect();
+
+				if pairs.len() == len {
+					return;
+				}
+
+				pairs
+			}
+			None => return,
+		};
+
+		self.set_query_pairs(&pairs)
+	}
+
+	/// Only keep the query parameters for which `f` returns `true`.
+	///
+	/// `f` is given the percent-decoded key and value of each parameter,
+	/// the value being `None` for a parameter without `=`.
+	/// Empty parameters, as between `&&`, are given as an empty key without value.
+	/// The retained parameters keep their order and original encoding.
+	/// The query is removed (including the `?` delimiter) if no parameter is retained.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// let mut iri_ref = IriRefBuf::new("/a?utm_source=x&id=5&utm_medium=y#f").unwrap();
+	/// iri_ref.retain_query_params(|key, _| !key.starts_with("utm_"));
+	/// assert_eq!(iri_ref, "/a?id=5#f");
+	/// ```
+	pub fn retain_query_params<F: FnMut(&str, Option<&str>) -> bool>(&mut self, mut f: F) {
+		let pairs: Vec<String> = match self.query() {
+			Some(query) => {
+				let raw_pairs = query.raw_pairs();
+				let len = raw_pairs.len();
+				let pairs: Vec<String> = raw_pairs
+					.into_iter()
+					.filter(|(pair, _)| {
+						let (key, value) = crate::iri::decode_pair(pair.as_bytes(), false);
+						f(&key, value.as_deref())
+					})
+					.map(|(pair, _)| pair.to_string())
+					.collect();
+
+				if pairs.len() == len {
+					return;
+				}
+
+				pairs
+			}
+			None => return,
+		};
+
+		self.set_query_pairs(&pairs)
+	}
+
+	/// Sort the query parameters by key, then by value for parameters with the same key.
+	///
+	/// Keys and values are compared once percent-decoded, so that `%61` and `a` are equal,
+	/// by code point (which is also the order of their UTF-8 bytes).
+	/// A parameter without value comes before the same key with a value.
+	/// The sort is stable, and each parameter keeps its original encoding:
+	/// call [`normalize_percent_encoding`](IriRefBuf::normalize_percent_encoding) beforehand
+	/// if a canonical encoding is needed too.
+	/// Empty parameters, as between `&&`, are removed.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRefBuf;
+	/// let mut iri_ref = IriRefBuf::new("/a?b=2&a=1&a=0#f").unwrap();
+	/// iri_ref.sort_query_params();
+	/// assert_eq!(iri_ref, "/a?a=0&a=1&b=2#f");
+	/// ```
+	pub fn sort_query_params(&mut self) {
+		let mut pairs: Vec<_> = match self.query() {
+			Some(query) => query
+				.raw_pairs()
+				.into_iter()
+				.filter(|(pair, _)| !pair.is_empty())
+				.map(|(pair, _)| (pair, crate::iri::decode_pair(pair.as_bytes(), false)))
+				.collect(),
+			None => return,
+		};
+
+		pairs.sort_by(|(_, a), (_, b)| a.cmp(b));
+		let pairs: Vec<String> = pairs
+			.into_iter()
+			.map(|(pair, _)| pair.to_string())
+			.collect();
+		self.set_query_pairs(&pairs)
+	}
+
+	fn set_query_pairs(&mut self, pairs: &[String]) {
+		if pairs.is_empty() {
+			self.set_query(None)
+		} else {
+			let query = pairs.join("&");
+			self.set_query(Some(Query {
+				data: query.as_bytes(),
+			}))
+		}
+	}
+
+	#[inline]
+	pub fn fragment(&self) -> Option<Fragment> {
+		if let Some(len) = self.p.fragment_len {
+			let offset = self.p.fragment_offset();
+			Some(Fragment {
+				data: &self.data[offset..(offset + len)],
+			})
+		} else {
+			None
+		}
+	}
+
+	/// See [`IriRef::has_empty_authority`].
+	#[inline]
+	pub fn has_empty_authority(&self) -> bool {
+		self.as_iri_ref().has_empty_authority()
+	}
+
+	/// See [`IriRef::has_empty_query`].
+	#[inline]
+	pub fn has_empty_query(&self) -> bool {
+		self.as_iri_ref().has_empty_query()
+	}
+
+	/// See [`IriRef::has_empty_fragment`].
+	#[inline]
+	pub fn has_empty_fragment(&self) -> bool {
+		self.as_iri_ref().has_empty_fragment()
+	}
+
+	/// See [`IriRef::component_ranges`].
+	#[inline]
+	pub fn component_ranges(&self) -> ComponentRanges {
+		self.as_iri_ref().component_ranges()
+	}
+
+	/// See [`IriRef::scheme_str`].
+	#[inline]
+	pub fn scheme_str(&self) -> Option<&str> {
+		self.as_iri_ref().scheme_str()
+	}
+
+	/// See [`IriRef::path_str`].
+	#[inline]
+	pub fn path_str(&self) -> &str {
+		self.as_iri_ref().path_str()
+	}
+
+	/// See [`IriRef::query_str`].
+	#[inline]
+	pub fn query_str(&self) -> Option<&str> {
+		self.as_iri_ref().query_str()
+	}
+
+	/// See [`IriRef::fragment_str`].
+	#[inline]
+	pub fn fragment_str(&self) -> Option<&str> {
+		self.as_iri_ref().fragment_str()
+	}
+
+	/// See [`IriRef::authority_and_path`].
+	#[inline]
+	pub fn authority_and_path(&self) -> &str {
+		self.as_iri_ref().authority_and_path()
+	}
 
-			self.p.query_len = None;
-		}
+	/// See [`IriRef::path_and_query`].
+	#[inline]
+	pub fn path_and_query(&self) -> &str {
+		self.as_iri_ref().path_and_query()
 	}
 
+	/// See [`IriRef::suffix_after_authority`].
 	#[inline]
-	pub fn fragment(&self) -> Option<Fragment> {
-		if let Some(len) = self.p.fragment_len {
-			let offset = self.p.fragment_offset();
-			Some(Fragment {
-				data: &self.data[offset..(offset + len)],
-			})
-		} else {
-			None
-		}
+	pub fn suffix_after_authority(&self) -> &str {
+		self.as_iri_ref().suffix_after_authority()
 	}
+
 	#[inline]
 	pub fn set_fragment(&mut self, fragment: Option<Fragment>) {
 		let offset = self.p.fragment_offset();
@@ -287,23 +1271,54 @@ impl IriRefBuf {
 	/// ## Abnormal use of dot segments.
 	///
 	/// https://www.rfc-editor.org/errata/eid4547
+	#[inline]
 	pub fn resolve<'b, Base: Into<Iri<'b>>>(&mut self, base_iri: Base) {
+		self.resolve_with_kind(base_iri);
+	}
+
+	/// Resolve the IRI reference, with the given options.
+	///
+	/// See [`IriRef::resolved_with`].
+	pub fn resolve_with<'b, Base: Into<Iri<'b>>>(
+		&mut self,
+		base_iri: Base,
+		options: ResolveOptions,
+	) {
+		let base_iri: Iri<'b> = base_iri.into();
+		let has_fragment = self.fragment().is_some();
+		self.resolve(base_iri);
+
+		if options.inherit_base_fragment && !has_fragment {
+			self.set_fragment(base_iri.fragment());
+		}
+	}
+
+	/// Resolve the IRI reference, returning the branch of the resolution algorithm that
+	/// was taken.
+	///
+	/// See [`resolve`](IriRefBuf::resolve).
+	pub fn resolve_with_kind<'b, Base: Into<Iri<'b>>>(&mut self, base_iri: Base) -> ResolutionKind {
 		let base_iri: Iri<'b> = base_iri.into();
 
 		if self.scheme().is_some() {
 			self.path_mut().normalize();
+			ResolutionKind::Absolute
 		} else {
 			self.set_scheme(Some(base_iri.scheme()));
 			if self.authority().is_some() {
 				self.path_mut().normalize();
+				ResolutionKind::NetworkPath
 			} else {
-				if self.path().is_relative() && self.path().is_empty() {
+				let kind = if self.path().is_relative() && self.path().is_empty() {
 					self.set_path(base_iri.path());
 					if self.query().is_none() {
 						self.set_query(base_iri.query());
 					}
+
+					ResolutionKind::Empty
 				} else if self.path().is_absolute() {
 					self.path_mut().normalize();
+					ResolutionKind::AbsolutePath
 				} else {
 					let mut path_buffer = IriRefBuf::default();
 					if base_iri.authority().is_some() && base_iri.path().is_empty() {
@@ -316,8 +1331,10 @@ impl IriRefBuf {
 						path_buffer.path_mut().open();
 					}
 					self.set_path(path_buffer.path());
-				}
+					ResolutionKind::RelativePath
+				};
 				self.set_authority(base_iri.authority());
+				kind
 			}
 		}
 	}
@@ -326,6 +1343,103 @@ impl IriRefBuf {
 	pub fn resolved<'b, Base: Into<Iri<'b>>>(&self, base_iri: Base) -> IriBuf {
 		self.as_iri_ref().resolved(base_iri)
 	}
+
+	/// See [`IriRef::resolved_with`].
+	#[inline]
+	pub fn resolved_with<'b, Base: Into<Iri<'b>>>(
+		&self,
+		base_iri: Base,
+		options: ResolveOptions,
+	) -> IriBuf {
+		self.as_iri_ref().resolved_with(base_iri, options)
+	}
+
+	/// See [`IriRef::resolved_with_kind`].
+	#[inline]
+	pub fn resolved_with_kind<'b, Base: Into<Iri<'b>>>(
+		&self,
+		base_iri: Base,
+	) -> (IriBuf, ResolutionKind) {
+		self.as_iri_ref().resolved_with_kind(base_iri)
+	}
+
+	/// Resolve the given IRI reference against this IRI reference, used as base.
+	///
+	/// See [`IriRef::resolve_ref`].
+	#[inline]
+	pub fn resolve_ref<'b, R: Into<IriRef<'b>>>(&self, reference: R) -> Result<IriBuf, Error> {
+		self.as_iri_ref().resolve_ref(reference)
+	}
+
+	/// Compare this IRI reference with `other`, ignoring their fragments.
+	///
+	/// See [`IriRef::eq_ignoring_fragment`].
+	#[inline]
+	pub fn eq_ignoring_fragment<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
+		self.as_iri_ref().eq_ignoring_fragment(other)
+	}
+
+	/// Start a comparison of this IRI reference with optional relaxations.
+	///
+	/// See [`IriRef::compare`].
+	#[inline]
+	pub fn compare(&self) -> Comparison<'_> {
+		self.as_iri_ref().compare()
+	}
+
+	/// Iterate over the percent-encoded octets of this IRI reference, in order.
+	///
+	/// See [`IriRef::pct_octets`].
+	#[inline]
+	pub fn pct_octets(&self) -> PctOctets<'_> {
+		self.as_iri_ref().pct_octets()
+	}
+
+	/// Byte-exact comparison with `other`, without any normalization.
+	///
+	/// See [`IriRef::eq_exact`].
+	#[inline]
+	pub fn eq_exact<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
+		self.as_iri_ref().eq_exact(other)
+	}
+
+	/// Checks if this IRI reference and `other` are equal (with `==`), but not byte-exact.
+	///
+	/// See [`IriRef::differs_only_by_normalization`].
+	#[inline]
+	pub fn differs_only_by_normalization<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
+		self.as_iri_ref().differs_only_by_normalization(other)
+	}
+}
+
+/// Checks if `path` cannot follow the other components of an IRI reference, with or
+/// without scheme and authority.
+///
+/// With an authority, the path must be empty or start with `/`.
+/// Without authority, it must not start with `//`, and without scheme either,
+/// its first segment must not contain a `:`.
+fn is_ambiguous_path(path: Path, has_scheme: bool, has_authority: bool) -> bool {
+	if has_authority {
+		!path.is_empty() && path.is_relative()
+	} else {
+		path.as_bytes().starts_with(b"//")
+			|| (!has_scheme
+				&& path.is_relative()
+				&& path.first().is_some_and(|s| s.as_bytes().contains(&b':')))
+	}
+}
+
+impl Clone for IriRefBuf {
+	/// Clone the IRI reference, copying only its `len()` bytes.
+	///
+	/// The clone does not inherit the spare capacity of the buffer.
+	#[inline]
+	fn clone(&self) -> IriRefBuf {
+		IriRefBuf {
+			p: self.p,
+			data: self.data[..self.len()].to_vec(),
+		}
+	}
 }
 
 impl AsRef<[u8]> for IriRefBuf {
@@ -335,14 +1449,63 @@ impl AsRef<[u8]> for IriRefBuf {
 	}
 }
 
+impl AsRef<str> for IriRefBuf {
+	#[inline]
+	fn as_ref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+/// Parse an owned IRI reference.
+///
+/// This is equivalent to [`IriRefBuf::new`].
 impl FromStr for IriRefBuf {
 	type Err = Error;
 
+	#[inline]
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		Self::new(s)
 	}
 }
 
+/// Parse an owned IRI reference.
+///
+/// This is equivalent to [`IriRefBuf::new`].
+impl<'a> TryFrom<&'a str> for IriRefBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(s: &'a str) -> Result<IriRefBuf, Error> {
+		IriRefBuf::new(s)
+	}
+}
+
+/// Parse an owned IRI reference.
+///
+/// This is equivalent to [`IriRefBuf::new`].
+impl<'a> TryFrom<&'a [u8]> for IriRefBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(bytes: &'a [u8]) -> Result<IriRefBuf, Error> {
+		IriRefBuf::new(bytes)
+	}
+}
+
+/// Parse an owned IRI reference, reusing the string buffer.
+impl TryFrom<String> for IriRefBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(s: String) -> Result<IriRefBuf, Error> {
+		let p = ParsedIriRef::new(&s)?;
+		Ok(IriRefBuf {
+			p,
+			data: s.into_bytes(),
+		})
+	}
+}
+
 impl AsIriRef for IriRefBuf {
 	#[inline]
 	fn as_iri_ref(&self) -> IriRef {
@@ -360,7 +1523,7 @@ impl fmt::Display for IriRefBuf {
 impl fmt::Debug for IriRefBuf {
 	#[inline]
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		self.as_iri_ref().fmt(f)
+		self.as_iri_ref().fmt_components("IriRefBuf", f)
 	}
 }
 
@@ -394,17 +1557,49 @@ impl PartialEq<IriBuf> for IriRefBuf {
 	}
 }
 
+/// Normalizing comparison with a string.
+///
+/// See [`IriRef`]'s implementation, and compare [`as_str`](IriRefBuf::as_str)
+/// for a byte-exact comparison.
 impl PartialEq<str> for IriRefBuf {
 	#[inline]
 	fn eq(&self, other: &str) -> bool {
-		self.as_iri_ref() == other
+		self.as_iri_ref() == *other
 	}
 }
 
-impl<'a> PartialEq<&'a str> for IriRefBuf {
+impl PartialEq<&str> for IriRefBuf {
 	#[inline]
-	fn eq(&self, other: &&'a str) -> bool {
-		self.as_iri_ref() == *other
+	fn eq(&self, other: &&str) -> bool {
+		*self == **other
+	}
+}
+
+impl PartialEq<String> for IriRefBuf {
+	#[inline]
+	fn eq(&self, other: &String) -> bool {
+		*self == *other.as_str()
+	}
+}
+
+impl PartialEq<IriRefBuf> for str {
+	#[inline]
+	fn eq(&self, other: &IriRefBuf) -> bool {
+		*other == *self
+	}
+}
+
+impl PartialEq<IriRefBuf> for &str {
+	#[inline]
+	fn eq(&self, other: &IriRefBuf) -> bool {
+		*other == **self
+	}
+}
+
+impl PartialEq<IriRefBuf> for String {
+	#[inline]
+	fn eq(&self, other: &IriRefBuf) -> bool {
+		*other == *self.as_str()
 	}
 }
 
@@ -446,11 +1641,10 @@ impl PartialOrd<IriBuf> for IriRefBuf {
 impl<'a> From<IriRef<'a>> for IriRefBuf {
 	#[inline]
 	fn from(iri_ref: IriRef<'a>) -> IriRefBuf {
-		let mut data = Vec::new();
-		data.resize(iri_ref.as_ref().len(), 0);
-		data.copy_from_slice(iri_ref.as_ref());
-
-		IriRefBuf { p: iri_ref.p, data }
+		IriRefBuf {
+			p: iri_ref.p,
+			data: iri_ref.as_bytes().to_vec(),
+		}
 	}
 }
 
@@ -505,7 +1699,308 @@ impl Hash for IriRefBuf {
 
 #[cfg(test)]
 mod tests {
-	use crate::{Iri, IriRef, IriRefBuf};
+	use crate::{
+		Error, Iri, IriBuf, IriRef, IriRefBuf, Path, Query, ResolutionKind, ResolveOptions,
+	};
+	use std::convert::TryFrom;
+
+	#[test]
+	fn set_query_param() {
+		let mut iri_ref = IriRefBuf::new("http://host/path?a=1&b=2&c=3#frag").unwrap();
+		iri_ref.set_query_param("b", Some("two"));
+		assert_eq!(iri_ref.as_str(), "http://host/path?a=1&b=two&c=3#frag");
+
+		iri_ref.set_query_param("d", None);
+		assert_eq!(iri_ref.as_str(), "http://host/path?a=1&b=two&c=3&d#frag");
+
+		iri_ref.set_query_param("k=y", Some("a b&c+d=e"));
+		assert_eq!(
+			iri_ref.as_str(),
+			"http://host/path?a=1&b=two&c=3&d&k%3Dy=a%20b%26c%2Bd%3De#frag"
+		);
+		let pairs: Vec<_> = iri_ref.query().unwrap().pairs().collect();
+		assert_eq!(pairs[4], ("k=y".into(), Some("a b&c+d=e".into())));
+
+		let mut iri_ref = IriRefBuf::new("/path").unwrap();
+		iri_ref.set_query_param("a", Some("1"));
+		assert_eq!(iri_ref.as_str(), "/path?a=1");
+
+		let mut iri_ref = IriRefBuf::new("?a=1&b=2&a=3&%61=4").unwrap();
+		iri_ref.set_query_param("a", Some("5"));
+		assert_eq!(iri_ref.as_str(), "?a=5&b=2");
+	}
+
+	#[test]
+	fn append_query_param() {
+		let mut iri_ref = IriRefBuf::new("/path#frag").unwrap();
+		iri_ref.append_query_param("a", Some("1"));
+		iri_ref.append_query_param("a", Some("2"));
+		assert_eq!(iri_ref.as_str(), "/path?a=1&a=2#frag");
+	}
+
+	#[test]
+	fn remove_query_param() {
+		let mut iri_ref = IriRefBuf::new("/path?a=1&b=2&&a=3&c=3#frag").unwrap();
+		iri_ref.remove_query_param("a");
+		assert_eq!(iri_ref.as_str(), "/path?b=2&&c=3#frag");
+
+		iri_ref.remove_query_param("z");
+		assert_eq!(iri_ref.as_str(), "/path?b=2&&c=3#frag");
+
+		iri_ref.remove_query_param("b");
+		iri_ref.remove_query_param("");
+		iri_ref.remove_query_param("c");
+		assert_eq!(iri_ref.as_str(), "/path#frag");
+		assert!(iri_ref.query().is_none());
+	}
+
+	#[test]
+	fn retain_query_params() {
+		let mut iri_ref = IriRefBuf::new("?utm_source=x&id=5&utm_medium=y").unwrap();
+		iri_ref.retain_query_params(|key, _| !key.starts_with("utm_"));
+		assert_eq!(iri_ref.as_str(), "?id=5");
+
+		let mut iri_ref = IriRefBuf::new("/p?a%3D=1&b&&c=%41+&a=x#f").unwrap();
+		let mut seen = Vec::new();
+		iri_ref.retain_query_params(|key, value| {
+			seen.push((key.to_string(), value.map(str::to_string)));
+			key != "b" && value != Some("x")
+		});
+		assert_eq!(
+			seen,
+			[
+				("a=".to_string(), Some("1".to_string())),
+				("b".to_string(), None),
+				("".to_string(), None),
+				("c".to_string(), Some("A+".to_string())),
+				("a".to_string(), Some("x".to_string())),
+			]
+		);
+		assert_eq!(iri_ref.as_str(), "/p?a%3D=1&&c=%41+#f");
+
+		iri_ref.retain_query_params(|_, _| true);
+		assert_eq!(iri_ref.as_str(), "/p?a%3D=1&&c=%41+#f");
+		iri_ref.retain_query_params(|_, _| false);
+		assert_eq!(iri_ref.as_str(), "/p#f");
+		iri_ref.retain_query_params(|_, _| panic!("no query"));
+	}
+
+	#[test]
+	fn sort_query_params() {
+		let sorted = |iri_ref| {
+			let mut iri_ref = IriRefBuf::new(iri_ref).unwrap();
+			iri_ref.sort_query_params();
+			iri_ref.as_str().to_string()
+		};
+
+		assert_eq!(sorted("?b=2&a=1&a=0"), "?a=0&a=1&b=2");
+		assert_eq!(
+			sorted("/p?b&%61=2&a=&a&é=1&z=1#f"),
+			"/p?a&a=&%61=2&b&z=1&é=1#f"
+		);
+		assert_eq!(sorted("?a=%31&a=1&a=0"), "?a=0&a=%31&a=1");
+		assert_eq!(sorted("?&b&&a&"), "?a&b");
+		assert_eq!(sorted("?&&"), "");
+		assert_eq!(sorted("/p#f"), "/p#f");
+	}
+
+	#[test]
+	fn normalize_percent_encoding() {
+		let mut iri_ref = IriRefBuf::new("http://host/%7euser/%2f").unwrap();
+		iri_ref.normalize_percent_encoding();
+		assert_eq!(iri_ref.as_str(), "http://host/~user/%2F");
+
+		let mut iri_ref =
+			IriRefBuf::new("http://%75s%65r@%45x.org/%2E/%3a?%41%26=%5f#%7e%e9").unwrap();
+		iri_ref.normalize_percent_encoding();
+		assert_eq!(iri_ref.as_str(), "http://user@Ex.org/./%3A?A%26=_#~%E9");
+		assert_eq!(iri_ref.authority().unwrap().host(), "Ex.org");
+		assert_eq!(iri_ref.query().unwrap(), "A%26=_");
+		assert_eq!(iri_ref.fragment().unwrap(), "~%E9");
+
+		let mut iri_ref = IriRefBuf::new("//[fe80::1%25eth%30]").unwrap();
+		iri_ref.normalize_percent_encoding();
+		assert_eq!(iri_ref.as_str(), "//[fe80::1%25eth0]");
+	}
+
+	#[test]
+	fn join() {
+		let iri_ref = IriRefBuf::new("http://host/a/b?q#f").unwrap();
+		assert_eq!(iri_ref.join("c").unwrap().as_str(), "http://host/a/b/c?q#f");
+		assert_eq!(
+			iri_ref.join("c/d/").unwrap().as_str(),
+			"http://host/a/b/c/d/?q#f"
+		);
+		assert_eq!(iri_ref.join("").unwrap().as_str(), "http://host/a/b/?q#f");
+		assert_eq!(iri_ref.join("/c").unwrap().as_str(), "http://host/c?q#f");
+		assert_eq!(iri_ref.join("//c").unwrap().as_str(), "http://host/c?q#f");
+		assert!(iri_ref.join("c?d").is_err());
+
+		let iri_ref = IriRefBuf::new("/a/b/").unwrap();
+		assert_eq!(iri_ref.join("c").unwrap().as_str(), "/a/b/c");
+		assert_eq!(iri_ref.join("/").unwrap().as_str(), "/");
+
+		let iri_ref = IriRefBuf::new("http://host").unwrap();
+		assert_eq!(iri_ref.join("c").unwrap().as_str(), "http://host/c");
+
+		let mut iri_ref = IriRefBuf::new("").unwrap();
+		assert_eq!(iri_ref.join("c").unwrap().as_str(), "c");
+		assert_eq!(iri_ref.join("/c").unwrap().as_str(), "/c");
+		iri_ref.join_mut("a").unwrap();
+		iri_ref.join_mut("b").unwrap();
+		assert_eq!(iri_ref.as_str(), "a/b");
+	}
+
+	#[test]
+	fn set_extension() {
+		let mut iri_ref = IriRefBuf::new("http://host/a/archive.tar.gz?q#f").unwrap();
+		assert!(iri_ref.set_extension(Some("bz2")));
+		assert_eq!(iri_ref.as_str(), "http://host/a/archive.tar.bz2?q#f");
+		assert!(iri_ref.set_extension(None));
+		assert_eq!(iri_ref.as_str(), "http://host/a/archive.tar?q#f");
+		assert!(iri_ref.set_extension(Some("")));
+		assert_eq!(iri_ref.as_str(), "http://host/a/archive?q#f");
+		assert!(iri_ref.set_extension(None));
+		assert_eq!(iri_ref.as_str(), "http://host/a/archive?q#f");
+		assert!(iri_ref.set_extension(Some("a/b:c.d")));
+		assert_eq!(iri_ref.as_str(), "http://host/a/archive.a%2Fb%3Ac.d?q#f");
+
+		let mut iri_ref = IriRefBuf::new(".hidden").unwrap();
+		assert!(iri_ref.set_extension(Some("txt")));
+		assert_eq!(iri_ref.as_str(), ".hidden.txt");
+
+		let mut iri_ref = IriRefBuf::new("b%2Etxt").unwrap();
+		assert!(iri_ref.set_extension(Some("md")));
+		assert_eq!(iri_ref.as_str(), "b%2Etxt.md");
+
+		for input in &["", "http://host", "/", "/a/", "a/.", "a/..", "a//"] {
+			let mut iri_ref = IriRefBuf::new(input).unwrap();
+			assert!(!iri_ref.set_extension(Some("txt")));
+			assert_eq!(iri_ref.as_str(), *input);
+		}
+	}
+
+	#[test]
+	fn remove_default_port() {
+		let mut iri_ref = IriRefBuf::new("http://user@host:80/a?q#f").unwrap();
+		assert!(!iri_ref.remove_default_port(443));
+		assert_eq!(iri_ref.as_str(), "http://user@host:80/a?q#f");
+		assert!(iri_ref.remove_default_port(80));
+		assert_eq!(iri_ref.as_str(), "http://user@host/a?q#f");
+		assert!(!iri_ref.remove_default_port(80));
+
+		let mut iri_ref = IriRefBuf::new("//host:0080").unwrap();
+		assert!(iri_ref.remove_default_port(80));
+		assert_eq!(iri_ref.as_str(), "//host");
+
+		for input in &["/a", "//host", "//host:", "//host:99999"] {
+			let mut iri_ref = IriRefBuf::new(input).unwrap();
+			assert!(!iri_ref.remove_default_port(80));
+			assert_eq!(iri_ref.as_str(), *input);
+		}
+	}
+
+	#[test]
+	fn as_bytes_after_shrinking() {
+		let mut iri_ref = IriRefBuf::new("http://host/a?q#f").unwrap();
+		iri_ref.set_path(Path::try_from("/a/very/long/path/to/grow/the/buffer").unwrap());
+		iri_ref.set_query(Some(Query::try_from("a=long&query=string").unwrap()));
+		iri_ref.set_path(Path::try_from("/b").unwrap());
+		iri_ref.set_query(None);
+		iri_ref.set_fragment(None);
+
+		let expected = "http://host/b";
+		assert_eq!(iri_ref.len(), expected.len());
+		assert_eq!(iri_ref.as_bytes(), expected.as_bytes());
+		assert_eq!(AsRef::<[u8]>::as_ref(&iri_ref), expected.as_bytes());
+		assert_eq!(AsRef::<str>::as_ref(&iri_ref), expected);
+
+		let iri = IriBuf::try_from(iri_ref).unwrap();
+		assert_eq!(iri.as_bytes(), expected.as_bytes());
+		assert_eq!(iri.as_iri().as_bytes(), expected.as_bytes());
+		assert_eq!(AsRef::<[u8]>::as_ref(&iri), expected.as_bytes());
+		assert_eq!(AsRef::<str>::as_ref(&iri.as_iri()), expected);
+		assert_eq!(iri.0.into_bytes(), expected.as_bytes());
+	}
+
+	#[test]
+	fn from_parts() {
+		let parts = [
+			(
+				Some("https"),
+				Some("www.rust-lang.org:40"),
+				"/foo/bar",
+				Some("query"),
+				Some("fragment"),
+			),
+			(Some("urn"), None, "isbn:0451450523", None, None),
+			(Some("file"), Some(""), "/etc/hosts", None, None),
+			(Some("http"), Some("u@[::1]"), "", Some(""), Some("")),
+			(None, Some("a"), "/b", None, None),
+			(None, None, "a/b:c", Some("q"), None),
+			(None, None, "", None, Some("f")),
+		];
+
+		for (scheme, authority, path, query, fragment) in &parts {
+			let iri_ref =
+				IriRefBuf::from_parts(*scheme, *authority, path, *query, *fragment).unwrap();
+			let expected = IriRefBuf::new(iri_ref.as_str()).unwrap();
+			assert_eq!(iri_ref.as_str(), expected.as_str());
+			assert_eq!(iri_ref.scheme(), expected.scheme());
+			assert_eq!(iri_ref.authority(), expected.authority());
+			assert_eq!(iri_ref.path(), expected.path());
+			assert_eq!(iri_ref.query(), expected.query());
+			assert_eq!(iri_ref.fragment(), expected.fragment());
+			assert_eq!(iri_ref.data.len(), iri_ref.data.capacity());
+		}
+
+		let iri = IriBuf::from_parts(Some("http"), Some("a"), "/b", None, Some("c")).unwrap();
+		assert_eq!(iri.as_str(), "http://a/b#c");
+	}
+
+	#[test]
+	fn from_parts_invalid() {
+		let invalid = [
+			(Some("1http"), None, "", None, None, Error::InvalidScheme),
+			(
+				Some("http"),
+				Some("a/b"),
+				"",
+				None,
+				None,
+				Error::InvalidAuthority,
+			),
+			(Some("http"), Some("a"), "b", None, None, Error::InvalidPath),
+			(Some("http"), None, "//b", None, None, Error::InvalidPath),
+			(None, None, "a:b", None, None, Error::InvalidPath),
+			(Some("http"), None, "/a?b", None, None, Error::InvalidPath),
+			(
+				Some("http"),
+				None,
+				"",
+				Some("a#b"),
+				None,
+				Error::InvalidQuery,
+			),
+			(
+				Some("http"),
+				None,
+				"",
+				None,
+				Some("a#b"),
+				Error::InvalidFragment,
+			),
+		];
+
+		for (scheme, authority, path, query, fragment, expected) in &invalid {
+			let e =
+				IriRefBuf::from_parts(*scheme, *authority, path, *query, *fragment).unwrap_err();
+			assert_eq!(e.to_string(), expected.to_string());
+		}
+
+		let e = IriBuf::from_parts(None, Some("a"), "", None, None).unwrap_err();
+		assert!(matches!(e, Error::MissingScheme));
+	}
 
 	#[test]
 	fn disambiguate1() {
@@ -566,7 +2061,9 @@ mod tests {
 
 		for (relative, absolute) in &tests {
 			// println!("{} => {}", relative, absolute);
-			assert_eq!(IriRef::new(relative).unwrap().resolved(base_iri), *absolute);
+			let relative = IriRef::new(relative).unwrap();
+			assert_eq!(relative.resolved(base_iri), *absolute);
+			assert_eq!(base_iri.resolve_ref(relative), *absolute);
 		}
 	}
 
@@ -600,7 +2097,400 @@ mod tests {
 
 		for (relative, absolute) in &tests {
 			// println!("{} => {}", relative, absolute);
-			assert_eq!(IriRef::new(relative).unwrap().resolved(base_iri), *absolute);
+			let relative = IriRef::new(relative).unwrap();
+			assert_eq!(relative.resolved(base_iri), *absolute);
+			assert_eq!(base_iri.resolve_ref(relative), *absolute);
+		}
+	}
+
+	#[test]
+	fn resolution_errata_4547() {
+		let base_iri = Iri::new("foo:a/b").unwrap();
+
+		let tests = [
+			("../../g", "foo:../g"),
+			("../../../g", "foo:../../g"),
+			("../../../g/../h", "foo:../../h"),
+		];
+
+		for (relative, absolute) in &tests {
+			let resolved = IriRef::new(relative).unwrap().resolved(base_iri);
+			assert_eq!(resolved.as_str(), *absolute);
+		}
+	}
+
+	#[test]
+	fn into_segments() {
+		let segments =
+			|iri_ref| -> Vec<String> { IriRefBuf::new(iri_ref).unwrap().into_segments().collect() };
+
+		assert_eq!(segments("http://h/a/b%20c/?q#f"), ["a", "b%20c"]);
+		assert_eq!(segments("a//b"), ["a", "", "b"]);
+		assert!(segments("http://h").is_empty());
+		assert!(segments("/").is_empty());
+
+		let mut iter = IriBuf::new("foo:a/b/c/d").unwrap().into_segments();
+		assert_eq!(iter.next().unwrap(), "a");
+		assert_eq!(iter.next_back().unwrap(), "d");
+		assert_eq!(iter.clone().collect::<Vec<_>>(), ["b", "c"]);
+		assert_eq!(iter.next_back().unwrap(), "c");
+		assert_eq!(iter.next().unwrap(), "b");
+		assert!(iter.next().is_none());
+		assert!(iter.next_back().is_none());
+	}
+
+	#[test]
+	fn set_path_and_query() {
+		let mut iri_ref = IriRefBuf::new("http://h/a/b?q=1#f").unwrap();
+		iri_ref.set_path_and_query("/new/path?x=1").unwrap();
+		assert_eq!(iri_ref.as_str(), "http://h/new/path?x=1#f");
+		assert_eq!(iri_ref.path(), "/new/path");
+		assert_eq!(iri_ref.query().unwrap(), "x=1");
+		assert_eq!(iri_ref.fragment().unwrap(), "f");
+		assert!(iri_ref.eq_exact(&IriRefBuf::new(iri_ref.as_str()).unwrap()));
+
+		iri_ref.set_path_and_query("/c?").unwrap();
+		assert_eq!(iri_ref.as_str(), "http://h/c?#f");
+		iri_ref.set_path_and_query("").unwrap();
+		assert_eq!(iri_ref.as_str(), "http://h#f");
+		assert!(iri_ref.query().is_none());
+
+		for invalid in &["a/b", "/a b", "/a?b c", "/a#b"] {
+			assert!(iri_ref.set_path_and_query(invalid).is_err());
+		}
+		assert_eq!(iri_ref.as_str(), "http://h#f");
+
+		let mut iri_ref = IriRefBuf::new("?q").unwrap();
+		iri_ref.set_path_and_query("a/b?x?y").unwrap();
+		assert_eq!(iri_ref.as_str(), "a/b?x?y");
+		assert_eq!(iri_ref.query().unwrap(), "x?y");
+		assert!(matches!(
+			iri_ref.set_path_and_query("a:b"),
+			Err(Error::InvalidPath)
+		));
+		assert!(matches!(
+			iri_ref.set_path_and_query("//h"),
+			Err(Error::InvalidPath)
+		));
+	}
+
+	#[test]
+	fn resolve_options() {
+		let base_iri = Iri::new("http://a/b/c?q#f").unwrap();
+		let inherit = ResolveOptions {
+			inherit_base_fragment: true,
+		};
+
+		let tests = [
+			("g", "http://a/b/g", "http://a/b/g#f"),
+			("", "http://a/b/c?q", "http://a/b/c?q#f"),
+			("?y", "http://a/b/c?y", "http://a/b/c?y#f"),
+			("g#s", "http://a/b/g#s", "http://a/b/g#s"),
+			("#", "http://a/b/c?q#", "http://a/b/c?q#"),
+			("//g", "http://g", "http://g#f"),
+		];
+
+		for (relative, strict, inherited) in &tests {
+			let relative = IriRefBuf::new(relative).unwrap();
+			assert_eq!(relative.resolved(base_iri).as_str(), *strict);
+			let resolved = relative.resolved_with(base_iri, ResolveOptions::default());
+			assert_eq!(resolved.as_str(), *strict);
+			assert_eq!(
+				relative.resolved_with(base_iri, inherit).as_str(),
+				*inherited
+			);
+		}
+
+		let relative = IriRefBuf::new("g").unwrap();
+		let base_iri = Iri::new("http://a/b").unwrap();
+		assert_eq!(
+			relative.resolved_with(base_iri, inherit).as_str(),
+			"http://a/g"
+		);
+	}
+
+	#[test]
+	fn resolution_kind() {
+		let base_iri = Iri::new("http://a/b/c/d;p?q").unwrap();
+
+		let tests = [
+			("g:h", "g:h", ResolutionKind::Absolute),
+			("http:g", "http:g", ResolutionKind::Absolute),
+			("//g/./x", "http://g/x", ResolutionKind::NetworkPath),
+			("/g/../h", "http://a/h", ResolutionKind::AbsolutePath),
+			("../g", "http://a/b/g", ResolutionKind::RelativePath),
+			(";x", "http://a/b/c/;x", ResolutionKind::RelativePath),
+			("", "http://a/b/c/d;p?q", ResolutionKind::Empty),
+			("?y", "http://a/b/c/d;p?y", ResolutionKind::Empty),
+			("#s", "http://a/b/c/d;p?q#s", ResolutionKind::Empty),
+		];
+
+		for (relative, absolute, kind) in &tests {
+			let relative = IriRefBuf::new(relative).unwrap();
+			let (resolved, resolved_kind) = relative.resolved_with_kind(base_iri);
+			assert_eq!(resolved.as_str(), *absolute);
+			assert_eq!(resolved_kind, *kind);
+
+			let mut buffer = relative.clone();
+			assert_eq!(buffer.resolve_with_kind(base_iri), *kind);
+			assert_eq!(buffer.as_str(), *absolute);
+		}
+	}
+
+	#[test]
+	fn resolve_ref() {
+		let base = IriBuf::new("http://a/b/c/d;p?q#f").unwrap();
+		let reference = IriRefBuf::new("../g?y").unwrap();
+		assert_eq!(base.resolve_ref(&reference), "http://a/b/g?y");
+		assert_eq!(
+			base.resolve_ref(IriRef::new("").unwrap()),
+			"http://a/b/c/d;p?q"
+		);
+
+		let base = IriRef::new("http://a/b/c/d;p?q").unwrap();
+		assert_eq!(base.resolve_ref(&reference).unwrap(), "http://a/b/g?y");
+
+		let base = IriRefBuf::new("//a/b/c").unwrap();
+		assert!(matches!(
+			base.resolve_ref(&reference),
+			Err(crate::Error::MissingScheme)
+		));
+	}
+
+	#[test]
+	fn capacity() {
+		use crate::{Authority, IriBuf, Scheme, Segment};
+		use std::convert::TryFrom;
+
+		let mut iri_ref = IriRefBuf::with_capacity(64);
+		let capacity = iri_ref.capacity();
+		let ptr = iri_ref.as_bytes().as_ptr();
+		assert!(capacity >= 64);
+
+		iri_ref.set_authority(Some(Authority::try_from("example.org").unwrap()));
+		for _ in 0..10 {
+			iri_ref.path_mut().push(Segment::try_from("abcd").unwrap());
+		}
+
+		assert_eq!(iri_ref.len(), 63);
+		assert_eq!(iri_ref.capacity(), capacity);
+		assert_eq!(iri_ref.as_bytes().as_ptr(), ptr);
+
+		iri_ref.reserve(100);
+		assert!(iri_ref.capacity() >= 163);
+
+		let mut iri = IriBuf::with_capacity(Scheme::try_from("https").unwrap(), 32);
+		assert_eq!(iri.as_str(), "https:");
+		assert!(iri.capacity() >= 32);
+		iri.reserve(64);
+		assert!(iri.capacity() >= 70);
+	}
+
+	#[test]
+	fn from_segments() {
+		use crate::Error;
+
+		let from = |segments: &[&str], absolute| {
+			IriRefBuf::from_segments(segments.iter().copied(), absolute).unwrap()
+		};
+
+		assert_eq!(from(&["a", "b", "c"], false).as_str(), "a/b/c");
+		assert_eq!(from(&["a", "b", "c"], true).as_str(), "/a/b/c");
+		assert_eq!(from(&["a", "", "b", ""], true).as_str(), "/a//b//");
+		assert_eq!(from(&["", "a"], true).as_str(), "/.//a");
+		assert_eq!(from(&["", "a"], false).as_str(), ".//a");
+		assert_eq!(from(&["a:b", "c"], false).as_str(), "./a:b/c");
+		assert_eq!(from(&["é", "%20"], false).as_str(), "é/%20");
+		assert_eq!(from(&[], false).as_str(), "");
+		assert_eq!(from(&[], true).as_str(), "/");
+
+		for (segments, absolute) in &[(&["a", "b"][..], true), (&["", "a:b", ""][..], false)] {
+			let iri_ref = from(segments, *absolute);
+			let path = iri_ref.path();
+			let parsed: Vec<_> = path.segments().map(|s| s.into_str()).collect();
+			assert!(parsed.ends_with(segments));
+			assert!(iri_ref.eq_exact(&IriRefBuf::new(iri_ref.as_str()).unwrap()));
+			assert_eq!(iri_ref.path().is_absolute(), *absolute);
+		}
+
+		for invalid in &["a/b", "a/", "/", "a?b", "a#b", "a b", "%zz"] {
+			assert!(IriRefBuf::from_segments(["x", invalid], false).is_err());
 		}
+
+		assert!(matches!(
+			IriRefBuf::from_segments(vec!["a", "b/c"], true),
+			Err(Error::InvalidSegment)
+		));
+	}
+
+	#[test]
+	fn component_only() {
+		use crate::{Error, Iri};
+
+		let fragment = IriRefBuf::fragment_only("a/b?c").unwrap();
+		assert_eq!(fragment.as_str(), "#a/b?c");
+		assert!(fragment.path().is_empty());
+		assert!(fragment.query().is_none());
+		assert_eq!(fragment.fragment().unwrap(), "a/b?c");
+		assert_eq!(IriRefBuf::fragment_only("").unwrap().as_str(), "#");
+		assert!(matches!(
+			IriRefBuf::fragment_only("a#b"),
+			Err(Error::InvalidFragment)
+		));
+
+		let query = IriRefBuf::query_only("x=1&y").unwrap();
+		assert_eq!(query.as_str(), "?x=1&y");
+		assert!(query.path().is_empty());
+		assert!(query.fragment().is_none());
+		assert!(matches!(
+			IriRefBuf::query_only("x#y"),
+			Err(Error::InvalidQuery)
+		));
+
+		let base = Iri::new("http://u@a:8/b/c;p?q#f").unwrap();
+		assert_eq!(fragment.resolved(base), "http://u@a:8/b/c;p?q#a/b?c");
+		assert_eq!(query.resolved(base), "http://u@a:8/b/c;p?x=1&y");
+
+		// The base path is kept as is, without removing its dot segments.
+		let base = Iri::new("foo:/b/./c").unwrap();
+		assert_eq!(fragment.resolved(base).as_str(), "foo:/b/./c#a/b?c");
+		assert_eq!(query.resolved(base).as_str(), "foo:/b/./c?x=1&y");
+	}
+
+	#[test]
+	fn component_lengths() {
+		for iri_ref in &["http://u@h:8/a/b?q=1#f", "//?#", "", "a:", "/a#f%20"] {
+			let buffer = IriRefBuf::new(iri_ref).unwrap();
+			let ranges = buffer.component_ranges();
+			let len = |range: Option<core::ops::Range<usize>>| range.map_or(0, |r| r.len());
+			assert_eq!(buffer.scheme_len(), len(ranges.scheme()));
+			assert_eq!(buffer.authority_len(), len(ranges.authority()));
+			assert_eq!(buffer.path_len(), ranges.path().len());
+			assert_eq!(buffer.query_len(), len(ranges.query()));
+			assert_eq!(buffer.fragment_len(), len(ranges.fragment()));
+			assert_eq!(buffer.as_iri_ref().path_len(), buffer.path_len());
+		}
+
+		let iri = IriBuf::new("http://h/a?q#f%20").unwrap();
+		let lengths = [
+			iri.scheme_len(),
+			iri.authority_len(),
+			iri.path_len(),
+			iri.query_len(),
+			iri.fragment_len(),
+		];
+		assert_eq!(lengths, [4, 1, 2, 1, 4]);
+		assert_eq!(lengths.iter().sum::<usize>() + "://?#".len(), iri.len());
+	}
+
+	#[test]
+	fn clone_without_spare_capacity() {
+		use crate::{IriBuf, Segment};
+		use std::convert::TryFrom;
+
+		let mut iri = IriBuf::new("http://example.org/a?q").unwrap();
+		for _ in 0..20 {
+			iri.path_mut().push(Segment::try_from("segment").unwrap());
+		}
+		iri.set_query(None);
+		iri.path_mut().clear();
+		assert!(iri.capacity() > iri.len());
+
+		let clone = iri.clone();
+		assert_eq!(clone.as_str(), "http://example.org/");
+		assert_eq!(clone.as_str(), iri.as_str());
+		assert_eq!(clone.capacity(), clone.len());
+		assert_eq!(clone.authority(), iri.authority());
+
+		let iri_ref: &IriRefBuf = &iri;
+		let clone = iri_ref.clone();
+		assert_eq!(clone.capacity(), clone.len());
+	}
+
+	#[test]
+	fn set_path_reuses_buffer() {
+		use crate::{IriBuf, Path};
+		use std::convert::TryFrom;
+
+		let mut iri = IriBuf::new("http://example.org/?q#f").unwrap();
+		iri.set_path(Path::try_from("/a/very/long/path/to/reserve/enough/capacity").unwrap());
+		let capacity = iri.capacity();
+		let ptr = iri.as_bytes().as_ptr();
+
+		let paths: Vec<String> = (0..10_000).map(|i| format!("/{}/{}", i % 7, i)).collect();
+		for path in &paths {
+			iri.set_path(Path::try_from(path.as_str()).unwrap());
+			assert_eq!(iri.path(), path.as_str());
+			assert_eq!(iri.query().unwrap(), "q");
+			assert_eq!(iri.fragment().unwrap(), "f");
+		}
+
+		let end = iri.as_str().len();
+		assert!(iri.replace_path_in_place(Path::try_from("/z/0000").unwrap()));
+		assert_eq!(iri.as_str(), "http://example.org/z/0000?q#f");
+		assert!(!iri.replace_path_in_place(Path::try_from("/z").unwrap()));
+		assert_eq!(iri.as_str().len(), end);
+
+		iri.set_path(Path::try_from("").unwrap());
+		assert_eq!(iri.as_str(), "http://example.org?q#f");
+		assert_eq!(iri.capacity(), capacity);
+		assert_eq!(iri.as_bytes().as_ptr(), ptr);
+	}
+
+	#[test]
+	fn shrink_to_fit() {
+		use crate::{Query, Segment};
+		use std::convert::{TryFrom, TryInto};
+
+		let mut iri_ref = IriRefBuf::new("http://example.org").unwrap();
+		let segment = Segment::try_from("segment").unwrap();
+		for _ in 0..100 {
+			iri_ref.path_mut().push(segment);
+		}
+		iri_ref.set_query(Some(Query::try_from("q").unwrap()));
+
+		let capacity = iri_ref.capacity();
+		assert!(capacity >= 800);
+
+		iri_ref.path_mut().clear();
+		assert_eq!(iri_ref.as_str(), "http://example.org/?q");
+		assert_eq!(iri_ref.capacity(), capacity);
+
+		iri_ref.shrink_to_fit();
+		assert!(iri_ref.capacity() < capacity);
+		assert!(iri_ref.capacity() >= iri_ref.len());
+		assert_eq!(iri_ref.as_str(), "http://example.org/?q");
+
+		let mut iri: IriBuf = iri_ref.try_into().unwrap();
+		iri.reserve(1000);
+		iri.shrink_to_fit();
+		assert!(iri.capacity() < 1000);
+	}
+
+	#[test]
+	fn into_string() {
+		let mut iri_ref = IriRefBuf::new("http://example.org/a/b/c?query#fragment").unwrap();
+		iri_ref.set_query(None);
+		iri_ref.path_mut().clear();
+		assert_eq!(iri_ref.as_str(), "http://example.org/#fragment");
+
+		let ptr = iri_ref.as_bytes().as_ptr();
+		let string = iri_ref.clone().into_string();
+		assert_eq!(string, "http://example.org/#fragment");
+		assert!(!string.contains('\0'));
+
+		let bytes = iri_ref.into_bytes();
+		assert_eq!(bytes, b"http://example.org/#fragment");
+		assert_eq!(bytes.as_ptr(), ptr);
+
+		let iri = IriBuf::new("https://example.org/é").unwrap();
+		let ptr = iri.as_bytes().as_ptr();
+		let string = iri.into_string();
+		assert_eq!(string, "https://example.org/é");
+		assert_eq!(string.as_ptr(), ptr);
+		assert_eq!(
+			IriBuf::new("urn:a").unwrap().into_bytes(),
+			b"urn:a".to_vec()
+		);
 	}
 }
@@ -0,0 +1,186 @@
+use crate::{Authority, IriRef, Path, Port, Scheme};
+
+/// Configurable comparison of an IRI reference.
+///
+/// Returned by [`IriRef::compare`](crate::IriRef::compare).
+/// By default, [`eq`](Comparison::eq) is the same as `==`,
+/// and each relaxation can be enabled independently before comparing.
+///
+/// # Example
+/// ```
+/// # use iref::Iri;
+/// let a = Iri::new("http://example.org:80/a/#x").unwrap();
+/// let b = Iri::new("http://example.org/a").unwrap();
+/// assert!(!a.compare().eq(b));
+/// assert!(a
+///   .compare()
+///   .ignore_trailing_slash()
+///   .ignore_default_port()
+///   .ignore_fragment()
+///   .eq(b));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Comparison<'a> {
+	iri_ref: IriRef<'a>,
+	trailing_slash: bool,
+	default_port: bool,
+	fragment: bool,
+}
+
+impl<'a> Comparison<'a> {
+	#[inline]
+	pub(crate) fn new(iri_ref: IriRef<'a>) -> Comparison<'a> {
+		Comparison {
+			iri_ref,
+			trailing_slash: false,
+			default_port: false,
+			fragment: false,
+		}
+	}
+
+	/// Ignore a trailing `/` at the end of the paths.
+	///
+	/// With this, `http://a/b/` equals `http://a/b`, and `http://a/` equals `http://a`.
+	#[inline]
+	pub fn ignore_trailing_slash(mut self) -> Self {
+		self.trailing_slash = true;
+		self
+	}
+
+	/// Ignore the port when it is the default port of the scheme.
+	///
+	/// With this, `http://a:80/` equals `http://a/`.
+	/// The known default ports are 80 for `http`, 443 for `https` and 21 for `ftp`.
+	#[inline]
+	pub fn ignore_default_port(mut self) -> Self {
+		self.default_port = true;
+		self
+	}
+
+	/// Ignore the fragments.
+	///
+	/// See [`IriRef::eq_ignoring_fragment`].
+	#[inline]
+	pub fn ignore_fragment(mut self) -> Self {
+		self.fragment = true;
+		self
+	}
+
+	/// Compare the IRI reference with `other`, with the enabled relaxations.
+	#[allow(clippy::should_implement_trait)]
+	pub fn eq<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
+		let a = self.iri_ref;
+		let b = other.into();
+
+		a.scheme() == b.scheme()
+			&& self.eq_authorities(a.scheme(), a.authority(), b.authority())
+			&& self.eq_paths(a.path(), b.path())
+			&& a.query() == b.query()
+			&& (self.fragment || a.fragment() == b.fragment())
+	}
+
+	fn eq_authorities(
+		&self,
+		scheme: Option<Scheme>,
+		a: Option<Authority>,
+		b: Option<Authority>,
+	) -> bool {
+		match (a, b) {
+			(Some(a), Some(b)) if self.default_port => {
+				let default_port = scheme.and_then(|scheme| scheme.default_port());
+				let port = |port| explicit_port(port, default_port);
+
+				a.userinfo() == b.userinfo()
+					&& a.host() == b.host()
+					&& port(a.port()) == port(b.port())
+			}
+			(a, b) => a == b,
+		}
+	}
+
+	fn eq_paths(&self, a: Path, b: Path) -> bool {
+		if !self.trailing_slash {
+			return a == b;
+		}
+
+		let mut a_segments = a.normalized_segments().peekable();
+		let mut b_segments = b.normalized_segments().peekable();
+
+		match (a_segments.peek(), b_segments.peek()) {
+			// `/` and the empty path only differ by a trailing slash.
+			(None, None) => return true,
+			_ if a.is_absolute() != b.is_absolute() => return false,
+			_ => (),
+		}
+
+		loop {
+			match (a_segments.next(), b_segments.next()) {
+				(None, None) => return true,
+				(Some(a), Some(b)) => {
+					let last = a_segments.peek().is_none() && b_segments.peek().is_none();
+					if a.as_pct_str() != b.as_pct_str() || (!last && a.is_open() != b.is_open()) {
+						return false;
+					}
+				}
+				_ => return false,
+			}
+		}
+	}
+}
+
+/// Port, unless it is empty or the default port.
+fn explicit_port(port: Option<Port>, default_port: Option<u16>) -> Option<Port> {
+	match port {
+		Some(port) if port.is_empty() => None,
+		Some(port) if port.as_u16().is_some() && port.as_u16() == default_port => None,
+		port => port,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{IriBuf, IriRef};
+
+	#[test]
+	fn relaxations() {
+		let iri_ref = |s| IriRef::new(s).unwrap();
+
+		let a = iri_ref("http://a/b/#f");
+		assert!(a.compare().eq(a));
+		assert!(a.compare().eq(iri_ref("HTTP://A/./b/#f")));
+		assert!(!a.compare().eq(iri_ref("http://a/b#f")));
+		assert!(!a.compare().eq(iri_ref("http://a/b/")));
+
+		let slash = a.compare().ignore_trailing_slash();
+		assert!(slash.eq(iri_ref("http://a/b#f")));
+		assert!(slash.eq(iri_ref("http://a/b/c/..#f")));
+		assert!(!slash.eq(iri_ref("http://a/b//#f")));
+		assert!(!slash.eq(iri_ref("http://a/b")));
+		assert!(a.compare().ignore_fragment().eq(iri_ref("http://a/b/#g")));
+		assert!(a.compare().ignore_fragment().eq(iri_ref("http://a/b/")));
+
+		let root = iri_ref("http://a/").compare().ignore_trailing_slash();
+		assert!(root.eq(iri_ref("http://a")));
+		assert!(!iri_ref("/a")
+			.compare()
+			.ignore_trailing_slash()
+			.eq(iri_ref("a/")));
+
+		let port = iri_ref("https://u@a:443/b").compare();
+		assert!(!port.eq(iri_ref("https://u@a/b")));
+		let port = port.ignore_default_port();
+		assert!(port.eq(iri_ref("https://u@a/b")));
+		assert!(port.eq(iri_ref("HTTPS://u@a:/b")));
+		assert!(!port.eq(iri_ref("https://a/b")));
+		assert!(!port.eq(iri_ref("https://u@a:80/b")));
+		let other = iri_ref("foo://a:80").compare().ignore_default_port();
+		assert!(!other.eq(iri_ref("foo://a")));
+
+		let buffer = IriBuf::new("http://a:80/b/#f").unwrap();
+		let loose = buffer
+			.compare()
+			.ignore_trailing_slash()
+			.ignore_default_port();
+		assert!(loose.ignore_fragment().eq(iri_ref("http://a/b")));
+	}
+}
@@ -0,0 +1,88 @@
+use core::ops::Range;
+
+use crate::parsing::ParsedIriRef;
+
+/// Byte ranges of the components of an IRI reference.
+///
+/// Returned by [`IriRef::component_ranges`](crate::IriRef::component_ranges).
+/// Ranges index into the [`as_str`](crate::IriRef::as_str) string of the IRI reference,
+/// and do not include the `:`, `//`, `@`, `?` and `#` delimiters.
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentRanges {
+	p: ParsedIriRef,
+}
+
+impl ComponentRanges {
+	#[inline]
+	pub(crate) fn new(p: ParsedIriRef) -> ComponentRanges {
+		ComponentRanges { p }
+	}
+
+	/// Range of the scheme, if any.
+	#[inline]
+	pub fn scheme(&self) -> Option<Range<usize>> {
+		self.p.scheme_len.map(|len| 0..len)
+	}
+
+	/// Range of the authority, if any.
+	#[inline]
+	pub fn authority(&self) -> Option<Range<usize>> {
+		let offset = self.p.authority_offset();
+		self.p
+			.authority
+			.map(|authority| offset..(offset + authority.len()))
+	}
+
+	/// Range of the authority user info, if any.
+	#[inline]
+	pub fn userinfo(&self) -> Option<Range<usize>> {
+		let offset = self.p.authority_offset();
+		self.p
+			.authority
+			.and_then(|authority| authority.userinfo_len)
+			.map(|len| offset..(offset + len))
+	}
+
+	/// Range of the authority host, if any.
+	#[inline]
+	pub fn host(&self) -> Option<Range<usize>> {
+		let offset = self.p.authority_offset();
+		self.p.authority.map(|authority| {
+			let host_offset = offset + authority.host_offset();
+			host_offset..(host_offset + authority.host_len)
+		})
+	}
+
+	/// Range of the authority port, if any.
+	#[inline]
+	pub fn port(&self) -> Option<Range<usize>> {
+		let offset = self.p.authority_offset();
+		self.p.authority.and_then(|authority| {
+			let port_offset = offset + authority.port_offset();
+			authority
+				.port_len
+				.map(|len| port_offset..(port_offset + len))
+		})
+	}
+
+	/// Range of the path, always defined but possibly empty.
+	#[inline]
+	pub fn path(&self) -> Range<usize> {
+		let offset = self.p.path_offset();
+		offset..(offset + self.p.path_len)
+	}
+
+	/// Range of the query, if any.
+	#[inline]
+	pub fn query(&self) -> Option<Range<usize>> {
+		let offset = self.p.query_offset();
+		self.p.query_len.map(|len| offset..(offset + len))
+	}
+
+	/// Range of the fragment, if any.
+	#[inline]
+	pub fn fragment(&self) -> Option<Range<usize>> {
+		let offset = self.p.fragment_offset();
+		self.p.fragment_len.map(|len| offset..(offset + len))
+	}
+}
@@ -1,18 +1,38 @@
 mod buffer;
+mod comparison;
+mod component_ranges;
+mod normalized_display;
+mod parts;
+mod pct_octets;
+mod resolution_kind;
+mod resolve_options;
 
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::convert::TryInto;
-use std::hash::{Hash, Hasher};
-use std::{cmp, fmt};
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::convert::TryInto;
+use core::hash::{Hash, Hasher};
+use core::{cmp, fmt};
 // use log::*;
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
+};
 use pct_str::PctStr;
 
-use crate::parsing::ParsedIriRef;
+use crate::parsing::{self, ParsedIriRef};
 use crate::{
 	AsIriRef, Authority, Error, Fragment, Iri, IriBuf, Path, PathBuf, Query, Scheme, Segment,
 };
 
 pub use self::buffer::*;
+pub use self::comparison::*;
+pub use self::component_ranges::*;
+pub use self::normalized_display::*;
+pub use self::parts::*;
+pub use self::pct_octets::*;
+pub use self::resolution_kind::*;
+pub use self::resolve_options::*;
+
+pub(crate) use self::normalized_display::CanonicalHost;
 
 /// IRI-reference slice.
 ///
@@ -53,6 +73,15 @@ impl<'a> IriRef<'a> {
 		})
 	}
 
+	/// Create a new IRI-reference slice from raw bytes, validating the IRI-reference grammar
+	/// and the UTF-8 encoding in a single pass.
+	///
+	/// See [`Iri::from_utf8`].
+	#[inline]
+	pub fn from_utf8(bytes: &'a [u8]) -> Result<IriRef<'a>, Error> {
+		Self::new(bytes)
+	}
+
 	/// Create a new IRI-reference from a string.
 	///
 	/// This replaces a [`std::str::FromStr`] implementation as the trait is
@@ -98,6 +127,164 @@ impl<'a> IriRef<'a> {
 		self.data.is_empty()
 	}
 
+	/// Length of the scheme in bytes, without the `:` delimiter, or 0 if there is no scheme.
+	///
+	/// The component lengths are read from the parsed IRI-reference, without building
+	/// the component views, and never include the delimiters (`:`, `//`, `?` and `#`).
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let iri = Iri::new("https://example.org/a?q").unwrap();
+	/// assert_eq!(iri.scheme_len(), 5);
+	/// assert_eq!(iri.authority_len(), 11);
+	/// assert_eq!(iri.path_len(), 2);
+	/// assert_eq!(iri.query_len(), 1);
+	/// assert_eq!(iri.fragment_len(), 0);
+	/// ```
+	#[inline]
+	pub fn scheme_len(&self) -> usize {
+		self.p.scheme_len.unwrap_or(0)
+	}
+
+	/// Length of the authority in bytes, without the `//` delimiter, or 0 if there is none.
+	///
+	/// See [`scheme_len`](IriRef::scheme_len).
+	#[inline]
+	pub fn authority_len(&self) -> usize {
+		self.p.authority.map_or(0, |authority| authority.len())
+	}
+
+	/// Length of the path in bytes.
+	///
+	/// See [`scheme_len`](IriRef::scheme_len).
+	#[inline]
+	pub fn path_len(&self) -> usize {
+		self.p.path_len
+	}
+
+	/// Length of the query in bytes, without the `?` delimiter, or 0 if there is none.
+	///
+	/// See [`scheme_len`](IriRef::scheme_len).
+	#[inline]
+	pub fn query_len(&self) -> usize {
+		self.p.query_len.unwrap_or(0)
+	}
+
+	/// Length of the fragment in bytes, without the `#` delimiter, or 0 if there is none.
+	///
+	/// See [`scheme_len`](IriRef::scheme_len).
+	#[inline]
+	pub fn fragment_len(&self) -> usize {
+		self.p.fragment_len.unwrap_or(0)
+	}
+
+	/// Checks if the IRI-reference is absolute, meaning that it has a scheme.
+	///
+	/// In the terms of [RFC 3986 section 4.1](https://tools.ietf.org/html/rfc3986#section-4.1),
+	/// an IRI-reference is either an IRI, with a scheme, or a relative reference, without.
+	/// Note that an absolute IRI-reference may still have a fragment, contrarily to the
+	/// `absolute-URI` rule of [section 4.3](https://tools.ietf.org/html/rfc3986#section-4.3).
+	/// An absolute IRI-reference can be converted into an [`Iri`] with
+	/// [`into_iri`](IriRef::into_iri).
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("http://example.org/a").unwrap().is_absolute());
+	/// assert!(IriRef::new("urn:isbn:0451450523").unwrap().is_absolute());
+	/// assert!(!IriRef::new("//example.org/a").unwrap().is_absolute());
+	/// assert!(!IriRef::new("/a").unwrap().is_absolute());
+	/// ```
+	#[inline]
+	pub fn is_absolute(&self) -> bool {
+		self.p.scheme_len.is_some()
+	}
+
+	/// Checks if the IRI-reference is an absolute IRI without fragment.
+	///
+	/// This is the `absolute-URI` production of
+	/// [RFC 3986](https://tools.ietf.org/html/rfc3986#section-4.3)
+	/// (`absolute-IRI` in RFC 3987), required for instance of base IRIs:
+	/// a scheme must be present and the fragment absent, even empty.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("http://h/p?q").unwrap().is_absolute_uri());
+	/// assert!(!IriRef::new("http://h/p#f").unwrap().is_absolute_uri());
+	/// assert!(!IriRef::new("http://h/p#").unwrap().is_absolute_uri());
+	/// assert!(!IriRef::new("/p?q").unwrap().is_absolute_uri());
+	/// ```
+	#[inline]
+	pub fn is_absolute_uri(&self) -> bool {
+		self.p.scheme_len.is_some() && self.p.fragment_len.is_none()
+	}
+
+	/// Checks if the IRI-reference is a relative reference, meaning that it has no scheme.
+	///
+	/// [RFC 3986 section 4.2](https://tools.ietf.org/html/rfc3986#section-4.2) distinguishes
+	/// three kinds of relative references:
+	///  - network-path references, starting with `//`, see
+	///    [`is_protocol_relative`](IriRef::is_protocol_relative);
+	///  - absolute-path references, starting with a single `/`;
+	///  - relative-path references, not starting with `/`.
+	///
+	/// A relative reference must be [resolved](IriRef::resolved) against a base IRI.
+	#[inline]
+	pub fn is_relative(&self) -> bool {
+		!self.is_absolute()
+	}
+
+	/// Checks if the IRI-reference is a network-path reference (also called a
+	/// protocol-relative reference), meaning that it has an authority but no scheme,
+	/// as in `//example.org/a`.
+	///
+	/// Such a reference inherits the scheme of the base IRI it is resolved against
+	/// ([RFC 3986 section 4.2](https://tools.ietf.org/html/rfc3986#section-4.2)).
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("//example.org/a").unwrap().is_protocol_relative());
+	/// assert!(IriRef::new("//").unwrap().is_protocol_relative());
+	/// assert!(!IriRef::new("http://example.org/a").unwrap().is_protocol_relative());
+	/// assert!(!IriRef::new("/a//b").unwrap().is_protocol_relative());
+	/// ```
+	#[inline]
+	pub fn is_protocol_relative(&self) -> bool {
+		self.p.scheme_len.is_none() && self.p.authority.is_some()
+	}
+
+	/// Checks if the path would escape the directory of the base IRI it is resolved against.
+	///
+	/// This is the case if the path is relative and still starts with a `..` segment once
+	/// normalized, following the dot segments removal of
+	/// [RFC 3986 Errata 4547](https://www.rfc-editor.org/errata/eid4547).
+	/// Absolute paths never escape, as extra `..` segments stop at the root.
+	/// Percent-encoded dots are not dot segments: normalize the percent-encoding
+	/// beforehand if they may be decoded later.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("a/b/../../../").unwrap().path_escapes_root());
+	/// assert!(!IriRef::new("a/b/../c").unwrap().path_escapes_root());
+	/// assert!(!IriRef::new("/../c").unwrap().path_escapes_root());
+	/// ```
+	#[inline]
+	pub fn path_escapes_root(&self) -> bool {
+		let path = self.path();
+		path.is_relative()
+			&& path
+				.normalized_segments()
+				.next()
+				.is_some_and(|segment| segment.as_bytes() == b"..")
+	}
+
 	/// Returns a reference to the byte representation of the IRI-reference.
 	#[inline]
 	pub fn as_bytes(&self) -> &[u8] {
@@ -113,13 +300,13 @@ impl<'a> IriRef<'a> {
 	/// Get the IRI-reference as a string slice.
 	#[inline]
 	pub fn as_str(&self) -> &str {
-		unsafe { std::str::from_utf8_unchecked(self.data) }
+		unsafe { core::str::from_utf8_unchecked(self.data) }
 	}
 
 	/// Convert the IRI-reference into a string slice.
 	#[inline]
 	pub fn into_str(self) -> &'a str {
-		unsafe { std::str::from_utf8_unchecked(self.data) }
+		unsafe { core::str::from_utf8_unchecked(self.data) }
 	}
 
 	/// Get the IRI-reference as a percent-encoded string slice.
@@ -164,6 +351,10 @@ impl<'a> IriRef<'a> {
 	/// assert_eq!(IriRef::new("foo://example.com:8042").unwrap().authority().unwrap().host(), "example.com");
 	/// assert_eq!(IriRef::new("foo:").unwrap().authority(), None);
 	/// ```
+	///
+	/// An empty authority, as in `foo:///path`, is not the same as no authority:
+	/// it is returned as `Some` empty authority.
+	/// See [`has_empty_authority`](IriRef::has_empty_authority).
 	#[inline]
 	pub fn authority(&self) -> Option<Authority> {
 		if let Some(authority) = self.p.authority {
@@ -207,6 +398,10 @@ impl<'a> IriRef<'a> {
 	/// assert_eq!(IriRef::new("//example.org?query").unwrap().query().unwrap(), "query");
 	/// assert!(IriRef::new("//example.org/foo/bar#fragment").unwrap().query().is_none());
 	/// ```
+	///
+	/// An empty query, as in `//example.org/foo?#fragment`, is not the same as no query:
+	/// it is returned as `Some` empty query.
+	/// See [`has_empty_query`](IriRef::has_empty_query).
 	#[inline]
 	pub fn query(&self) -> Option<Query> {
 		if let Some(len) = self.p.query_len {
@@ -230,6 +425,10 @@ impl<'a> IriRef<'a> {
 	/// assert_eq!(IriRef::new("//example.org#foo").unwrap().fragment().unwrap(), "foo");
 	/// assert!(IriRef::new("//example.org").unwrap().fragment().is_none());
 	/// ```
+	///
+	/// An empty fragment, as in `//example.org#`, is not the same as no fragment:
+	/// it is returned as `Some` empty fragment.
+	/// See [`has_empty_fragment`](IriRef::has_empty_fragment).
 	#[inline]
 	pub fn fragment(&self) -> Option<Fragment> {
 		if let Some(len) = self.p.fragment_len {
@@ -242,6 +441,176 @@ impl<'a> IriRef<'a> {
 		}
 	}
 
+	/// Get the scheme of the IRI-reference, if any, as a string slice.
+	///
+	/// The `*_str` accessors return the raw, still percent-encoded, slice of a component,
+	/// without going through its component type or [`PctStr`].
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("http://a/b%20c?q#f").unwrap();
+	/// assert_eq!(iri_ref.scheme_str(), Some("http"));
+	/// assert_eq!(iri_ref.path_str(), "/b%20c");
+	/// assert_eq!(iri_ref.query_str(), Some("q"));
+	/// assert_eq!(iri_ref.fragment_str(), Some("f"));
+	/// ```
+	#[inline]
+	pub fn scheme_str(&self) -> Option<&'a str> {
+		let ranges = self.component_ranges();
+		ranges.scheme().map(|range| &self.into_str()[range])
+	}
+
+	/// Get the path of the IRI-reference as a string slice.
+	///
+	/// See [`scheme_str`](IriRef::scheme_str).
+	#[inline]
+	pub fn path_str(&self) -> &'a str {
+		&self.into_str()[self.component_ranges().path()]
+	}
+
+	/// Get the query of the IRI-reference, if any, as a string slice.
+	///
+	/// See [`scheme_str`](IriRef::scheme_str).
+	#[inline]
+	pub fn query_str(&self) -> Option<&'a str> {
+		let ranges = self.component_ranges();
+		ranges.query().map(|range| &self.into_str()[range])
+	}
+
+	/// Get the fragment of the IRI-reference, if any, as a string slice.
+	///
+	/// See [`scheme_str`](IriRef::scheme_str).
+	#[inline]
+	pub fn fragment_str(&self) -> Option<&'a str> {
+		let ranges = self.component_ranges();
+		ranges.fragment().map(|range| &self.into_str()[range])
+	}
+
+	/// Checks if the IRI-reference has an authority, and it is empty.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("file:///etc/hosts").unwrap().has_empty_authority());
+	/// assert!(!IriRef::new("file:/etc/hosts").unwrap().has_empty_authority());
+	/// ```
+	#[inline]
+	pub fn has_empty_authority(&self) -> bool {
+		self.p
+			.authority
+			.is_some_and(|authority| authority.is_empty())
+	}
+
+	/// Checks if the IRI-reference has a query, and it is empty.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("//example.org/foo?#fragment").unwrap().has_empty_query());
+	/// assert!(!IriRef::new("//example.org/foo#fragment").unwrap().has_empty_query());
+	/// ```
+	#[inline]
+	pub fn has_empty_query(&self) -> bool {
+		self.p.query_len == Some(0)
+	}
+
+	/// Checks if the IRI-reference has a fragment, and it is empty.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert!(IriRef::new("//example.org#").unwrap().has_empty_fragment());
+	/// assert!(!IriRef::new("//example.org").unwrap().has_empty_fragment());
+	/// ```
+	#[inline]
+	pub fn has_empty_fragment(&self) -> bool {
+		self.p.fragment_len == Some(0)
+	}
+
+	/// Get the byte ranges of the components of the IRI-reference.
+	///
+	/// The ranges index into [`as_str`](IriRef::as_str), and can be used to slice it
+	/// or to highlight its different components.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("https://example.org:80/a?q").unwrap();
+	/// let ranges = iri_ref.component_ranges();
+	/// assert_eq!(ranges.scheme(), Some(0..5));
+	/// assert_eq!(&iri_ref.as_str()[ranges.host().unwrap()], "example.org");
+	/// assert_eq!(ranges.path(), 22..24);
+	/// assert_eq!(ranges.query(), Some(25..26));
+	/// assert_eq!(ranges.fragment(), None);
+	/// ```
+	#[inline]
+	pub fn component_ranges(&self) -> ComponentRanges {
+		ComponentRanges::new(self.p)
+	}
+
+	/// Get the authority and path of the IRI-reference, including the `//` authority delimiter.
+	///
+	/// If the IRI-reference has no authority, this is the path alone.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert_eq!(IriRef::new("https://example.org/a/b?q#f").unwrap().authority_and_path(), "//example.org/a/b");
+	/// assert_eq!(IriRef::new("urn:isbn:0451450523").unwrap().authority_and_path(), "isbn:0451450523");
+	/// ```
+	#[inline]
+	pub fn authority_and_path(&self) -> &'a str {
+		let start = match self.p.authority {
+			Some(_) => self.p.authority_offset() - 2,
+			None => self.p.path_offset(),
+		};
+
+		&self.into_str()[start..(self.p.path_offset() + self.p.path_len)]
+	}
+
+	/// Get the path and query of the IRI-reference, including the `?` query delimiter.
+	///
+	/// This is the request target of an HTTP request line,
+	/// except that the path is not replaced by `/` when empty.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert_eq!(IriRef::new("https://example.org/a/b?q#f").unwrap().path_and_query(), "/a/b?q");
+	/// assert_eq!(IriRef::new("https://example.org#f").unwrap().path_and_query(), "");
+	/// ```
+	#[inline]
+	pub fn path_and_query(&self) -> &'a str {
+		let end = match self.p.query_len {
+			Some(len) => self.p.query_offset() + len,
+			None => self.p.path_offset() + self.p.path_len,
+		};
+
+		&self.into_str()[self.p.path_offset()..end]
+	}
+
+	/// Get everything after the authority (or after the scheme if there is no authority):
+	/// the path, query and fragment, with their delimiters.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use iref::IriRef;
+	/// assert_eq!(IriRef::new("https://example.org/a/b?q#f").unwrap().suffix_after_authority(), "/a/b?q#f");
+	/// assert_eq!(IriRef::new("mailto:me@example.org").unwrap().suffix_after_authority(), "me@example.org");
+	/// ```
+	#[inline]
+	pub fn suffix_after_authority(&self) -> &'a str {
+		&self.into_str()[self.p.path_offset()..]
+	}
+
 	/// Convert the IRI-reference into an IRI, if possible.
 	///
 	/// An IRI-reference is a valid IRI only if it has a defined [`Scheme`].
@@ -261,6 +630,75 @@ impl<'a> IriRef<'a> {
 		iri_ref.try_into().unwrap()
 	}
 
+	/// Resolve the IRI reference against the given *base IRI*, with the given options.
+	///
+	/// With the default options, this is the same as [`resolved`](IriRef::resolved).
+	/// See [`ResolveOptions`] for the non-standard behaviors that can be enabled.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Iri, IriRef, ResolveOptions};
+	/// let base = Iri::new("http://a/b#f").unwrap();
+	/// let reference = IriRef::new("c").unwrap();
+	///
+	/// let mut options = ResolveOptions::default();
+	/// assert_eq!(reference.resolved_with(base, options), "http://a/c");
+	///
+	/// options.inherit_base_fragment = true;
+	/// assert_eq!(reference.resolved_with(base, options), "http://a/c#f");
+	/// ```
+	#[inline]
+	pub fn resolved_with<'b, Base: Into<Iri<'b>>>(
+		&self,
+		base_iri: Base,
+		options: ResolveOptions,
+	) -> IriBuf {
+		let mut iri_ref: IriRefBuf = self.into();
+		iri_ref.resolve_with(base_iri, options);
+		iri_ref.try_into().unwrap()
+	}
+
+	/// Resolve the IRI reference against the given *base IRI*,
+	/// also returning the branch of the resolution algorithm that was taken.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{Iri, IriRef, ResolutionKind};
+	/// let base = Iri::new("http://a/b/c?q").unwrap();
+	///
+	/// let (iri, kind) = IriRef::new("../d").unwrap().resolved_with_kind(base);
+	/// assert_eq!(iri, "http://a/d");
+	/// assert_eq!(kind, ResolutionKind::RelativePath);
+	///
+	/// let (iri, kind) = IriRef::new("#f").unwrap().resolved_with_kind(base);
+	/// assert_eq!(iri, "http://a/b/c?q#f");
+	/// assert_eq!(kind, ResolutionKind::Empty);
+	/// ```
+	#[inline]
+	pub fn resolved_with_kind<'b, Base: Into<Iri<'b>>>(
+		&self,
+		base_iri: Base,
+	) -> (IriBuf, ResolutionKind) {
+		let mut iri_ref: IriRefBuf = self.into();
+		let kind = iri_ref.resolve_with_kind(base_iri);
+		(iri_ref.try_into().unwrap(), kind)
+	}
+
+	/// Resolve the given IRI reference against this IRI reference, used as base.
+	///
+	/// This is [`resolved`](IriRef::resolved) with its operands the other way around.
+	/// Since RFC 3986 only defines the resolution against an absolute base,
+	/// this fails with [`Error::MissingScheme`] if this IRI reference has no scheme.
+	/// The fragment of the base is ignored.
+	/// Use [`Iri::resolve_ref`] to resolve against a base that is known to be an IRI.
+	#[inline]
+	pub fn resolve_ref<'b, R: Into<IriRef<'b>>>(&self, reference: R) -> Result<IriBuf, Error> {
+		match self.into_iri() {
+			Ok(base_iri) => Ok(base_iri.resolve_ref(reference)),
+			Err(_) => Err(Error::MissingScheme),
+		}
+	}
+
 	/// Get the suffix of this IRI reference, if any, with regard to the given prefix IRI reference..
 	///
 	/// Returns `Some((suffix, query, fragment))` if this IRI reference is of the form
@@ -313,112 +751,506 @@ impl<'a> IriRef<'a> {
 		}
 	}
 
-	/// Get this IRI reference relatively to the given one.
+	/// Iterate over the percent-encoded octets of this IRI reference, in order.
+	///
+	/// Each item gives the decoded octet, the component it appears in and the byte range
+	/// of the `%XX` triplet in the IRI reference.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::{parsing::Component, Iri};
+	/// let iri = Iri::new("http://a/b%00?c=%2541#%C3%A9").unwrap();
+	///
+	/// // Find a `%00` in the path.
+	/// assert!(iri
+	/// 	.pct_octets()
+	/// 	.any(|o| o.component == Component::Path && o.byte == 0));
+	///
+	/// // Detect double encoding.
+	/// let double = iri.pct_octets().find(|o| o.byte == b'%').unwrap();
+	/// assert_eq!(double.component, Component::Query);
+	/// assert_eq!(&iri.as_str()[double.range], "%25");
+	/// ```
+	#[inline]
+	pub fn pct_octets(&self) -> PctOctets<'a> {
+		PctOctets::new(&self.p, self.data)
+	}
+
+	/// Compare this IRI reference with `other`, ignoring their fragments.
+	///
+	/// The other components are compared as with `==`, following the usual normalization
+	/// rules (dot segments, case of the scheme and host, percent-encoding).
 	///
 	/// # Example
 	/// ```
 	/// # use iref::IriRef;
-	/// let a = IriRef::new("https://crates.io/").unwrap();
-	/// let b = IriRef::new("https://crates.io/crates/iref").unwrap();
-	/// let c = IriRef::new("https://crates.io/crates/json-ld").unwrap();
-	/// assert_eq!(b.relative_to(a), "crates/iref");
-	/// assert_eq!(a.relative_to(b), "../");
-	/// assert_eq!(b.relative_to(c), "iref");
-	/// assert_eq!(c.relative_to(b), "json-ld");
+	/// let a = IriRef::new("http://a/b#x").unwrap();
+	/// let b = IriRef::new("HTTP://a/c/../b#y").unwrap();
+	/// assert!(a.eq_ignoring_fragment(b));
+	/// assert_ne!(a, b);
 	/// ```
 	#[inline]
-	pub fn relative_to<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> IriRefBuf {
+	pub fn eq_ignoring_fragment<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
 		let other = other.into();
-		let mut result = IriRefBuf::default();
-
-		match (self.scheme(), other.scheme()) {
-			(Some(a), Some(b)) if a == b => (),
-			(Some(_), None) => (),
-			(None, Some(_)) => (),
-			(None, None) => (),
-			_ => return self.into(),
-		}
-
-		match (self.authority(), other.authority()) {
-			(Some(a), Some(b)) if a == b => (),
-			(Some(_), None) => (),
-			(None, Some(_)) => (),
-			(None, None) => (),
-			_ => return self.into(),
-		}
-
-		let mut self_segments = self.path().into_normalized_segments().peekable();
-		let mut base_segments = other.path().into_normalized_segments().peekable();
-
-		loop {
-			match base_segments.peek() {
-				Some(a) if a.is_open() || self.query().is_some() || self.fragment().is_some() => {
-					match self_segments.peek() {
-						Some(b) if a.as_pct_str() == b.as_pct_str() => {
-							base_segments.next();
-							self_segments.next();
-						}
-						_ => break,
-					}
-				}
-				_ => break,
-			}
-		}
-
-		for segment in base_segments {
-			if segment.is_open() {
-				result.path_mut().push(Segment::parent());
-				result.path_mut().open();
-			}
-		}
-
-		for segment in self_segments {
-			result.path_mut().push(segment)
-		}
-
-		result.set_query(self.query());
-		result.set_fragment(self.fragment());
-
-		result
+		self.scheme() == other.scheme()
+			&& self.authority() == other.authority()
+			&& self.path() == other.path()
+			&& self.query() == other.query()
 	}
-}
 
-impl<'a> AsRef<[u8]> for IriRef<'a> {
+	/// Start a comparison of this IRI reference with optional relaxations.
+	///
+	/// Without any relaxation, [`Comparison::eq`] is the same as `==`.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let a = IriRef::new("http://a/b/").unwrap();
+	/// assert!(a.compare().ignore_trailing_slash().eq(IriRef::new("http://a/b").unwrap()));
+	/// ```
 	#[inline]
-	fn as_ref(&self) -> &[u8] {
-		self.as_bytes()
+	pub fn compare(&self) -> Comparison<'a> {
+		Comparison::new(*self)
 	}
-}
 
-impl<'a> AsIriRef for IriRef<'a> {
+	/// Byte-exact comparison with `other`, without any normalization.
+	///
+	/// Unlike `==`, this detects syntactic differences between equivalent IRI references,
+	/// such as different percent-encodings or letter cases.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::Iri;
+	/// let a = Iri::new("http://example.org").unwrap();
+	/// let b = Iri::new("http://exa%6dple.org").unwrap();
+	/// assert_eq!(a, b);
+	/// assert!(!a.eq_exact(b));
+	/// ```
 	#[inline]
-	fn as_iri_ref(&self) -> IriRef {
-		*self
+	pub fn eq_exact<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
+		self.as_bytes() == other.into().as_bytes()
 	}
-}
 
-impl<'a> fmt::Display for IriRef<'a> {
+	/// Checks if this IRI reference and `other` are equal (with `==`), but not byte-exact.
+	///
+	/// This is the case when they only differ by their normalization: percent-encoding,
+	/// letter case of the scheme and host, or dot segments.
 	#[inline]
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	pub fn differs_only_by_normalization<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> bool {
+		let other = other.into();
+		*self == other && !self.eq_exact(other)
+	}
+
+	/// Display the syntax-normalized form of this IRI reference.
+	///
+	/// This is the syntax-based normalization of
+	/// [RFC 3986 section 6.2.2](https://tools.ietf.org/html/rfc3986#section-6.2.2):
+	/// the scheme and host are lowercased, the hexadecimal digits of percent-encoded
+	/// characters are uppercased, percent-encoded unreserved characters are decoded
+	/// and dot segments are removed from the path.
+	/// The normalized form is written directly to the formatter, and is equal to
+	/// this IRI reference.
+	/// The [`Display`](fmt::Display) implementation of `IriRef` is verbatim.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("HTTP://Example.COM/a/./b/../%7Ec%2f?%41#%3a").unwrap();
+	/// assert_eq!(
+	///   iri_ref.display_normalized().to_string(),
+	///   "http://example.com/a/~c%2F?A#%3A"
+	/// );
+	/// ```
+	#[inline]
+	pub fn display_normalized(&self) -> NormalizedDisplay<'a> {
+		NormalizedDisplay::new(*self)
+	}
+
+	/// Write the bytes of this IRI reference to `writer`, returning the number of bytes written.
+	///
+	/// Without the `std` feature, use the [`Display`](fmt::Display) implementation
+	/// with a [`core::fmt::Write`] instead.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("HTTP://a/./b").unwrap();
+	/// let mut buffer = Vec::new();
+	/// assert_eq!(iri_ref.write_to(&mut buffer).unwrap(), 12);
+	/// assert_eq!(buffer, iri_ref.as_bytes());
+	/// ```
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<usize> {
+		writer.write_all(self.data)?;
+		Ok(self.data.len())
+	}
+
+	/// Write the [syntax-normalized](IriRef::display_normalized) form of this IRI reference
+	/// to `writer`, returning the number of bytes written.
+	///
+	/// The normalized form is written component by component, without allocating.
+	/// Without the `std` feature, use [`display_normalized`](IriRef::display_normalized)
+	/// with a [`core::fmt::Write`] instead.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("HTTP://a/./b").unwrap();
+	/// let mut buffer = Vec::new();
+	/// assert_eq!(iri_ref.write_normalized_to(&mut buffer).unwrap(), 10);
+	/// assert_eq!(buffer, b"http://a/b");
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn write_normalized_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<usize> {
+		use fmt::Write;
+
+		let mut adapter = IoAdapter {
+			writer,
+			len: 0,
+			error: None,
+		};
+
+		match write!(adapter, "{}", self.display_normalized()) {
+			Ok(()) => Ok(adapter.len),
+			Err(_) => Err(adapter
+				.error
+				.unwrap_or_else(|| std::io::Error::other("formatter error"))),
+		}
+	}
+
+	/// Return the syntax-normalized form of this IRI reference.
+	///
+	/// This is the owned counterpart of [`display_normalized`](IriRef::display_normalized),
+	/// applying the same normalization as the comparison:
+	/// the scheme and host are lowercased, the hexadecimal digits of percent-encoded
+	/// characters are uppercased, percent-encoded unreserved characters are decoded
+	/// and dot segments are removed from the path, following
+	/// [Errata 4547](https://www.rfc-editor.org/errata/eid4547) for relative paths.
+	/// An IPv6 literal host is also rewritten in its canonical form (see [`Host::canonical`](crate::Host::canonical)).
+	/// The result is equal to this IRI reference.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("HTTP://Example.COM/a/./b/../%7Ec?%41#F").unwrap();
+	/// assert_eq!(iri_ref.normalized().as_str(), "http://example.com/a/~c?A#F");
+	///
+	/// let iri_ref = IriRef::new("//[2001:DB8:0:0::1]:80/").unwrap();
+	/// assert_eq!(iri_ref.normalized().as_str(), "//[2001:db8::1]:80/");
+	/// ```
+	pub fn normalized(&self) -> IriRefBuf {
+		IriRefBuf::new(&self.display_normalized().to_string()).unwrap()
+	}
+
+	/// Return the canonical form of this IRI reference.
+	///
+	/// This is the [syntax-normalized](IriRef::display_normalized) form, where every
+	/// percent-encoded character that may appear unencoded in its component is also decoded.
+	/// Two IRI references are equal (with `==`) if and only if their canonical forms are the
+	/// same byte strings, which makes it a suitable key for external storage.
+	///
+	/// Note that, as with `==`, the canonical form does not preserve the distinction
+	/// between a reserved character and its percent-encoding in the query or fragment,
+	/// such as `%26` and `&`.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let a = IriRef::new("HTTP://Example.COM/a/./b/%C3%A9%2f?q=%26#%3f").unwrap();
+	/// let b = IriRef::new("http://example.com/a/b/é%2F?q=&#?").unwrap();
+	/// assert_eq!(a.canonical().as_str(), "http://example.com/a/b/é%2F?q=&#?");
+	/// assert_eq!(a.canonical().as_bytes(), b.canonical().as_bytes());
+	/// ```
+	pub fn canonical(&self) -> IriRefBuf {
+		let canonical = NormalizedDisplay::canonical(*self).to_string();
+		IriRefBuf::new(&canonical).unwrap()
+	}
+
+	/// Map this IRI reference to a URI reference.
+	///
+	/// Every non-ASCII character is replaced with its percent-encoded UTF-8 bytes,
+	/// as defined in [RFC 3987 section 3.1](https://tools.ietf.org/html/rfc3987#section-3.1).
+	/// Already percent-encoded characters are left untouched.
+	/// Note that non-ASCII host names are percent-encoded like any other component, and not
+	/// converted with IDNA.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let iri_ref = IriRef::new("//例え.jp/日本語?q=%20").unwrap();
+	/// assert_eq!(
+	///   iri_ref.to_uri().as_str(),
+	///   "//%E4%BE%8B%E3%81%88.jp/%E6%97%A5%E6%9C%AC%E8%AA%9E?q=%20"
+	/// );
+	/// ```
+	#[inline]
+	pub fn to_uri(&self) -> IriRefBuf {
+		let mut uri = String::with_capacity(self.as_str().len());
+		for c in self.as_str().chars() {
+			if c.is_ascii() {
+				uri.push(c)
+			} else {
+				crate::pct_encode_char(c, &mut uri)
+			}
+		}
+
+		IriRefBuf::new(&uri).unwrap()
+	}
+
+	/// Map this URI reference to an IRI reference.
+	///
+	/// This is the inverse of [`to_uri`](IriRef::to_uri), following
+	/// [RFC 3987 section 3.2](https://tools.ietf.org/html/rfc3987#section-3.2):
+	/// percent-encoded UTF-8 sequences are decoded when they encode a character allowed in an
+	/// IRI. ASCII characters (such as `%2F`), invalid UTF-8 sequences, bidirectional formatting
+	/// characters and private use characters outside of the query are left percent-encoded.
+	/// IP literals, whose zone identifier may only contain ASCII characters, are left as they are.
+	///
+	/// Fails if the decoded IRI reference is not valid, which should not happen as only
+	/// characters allowed where they appear are decoded.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let uri_ref = IriRef::new("/%E6%97%A5%E6%9C%AC%2F%FF").unwrap();
+	/// assert_eq!(uri_ref.to_iri().unwrap().as_str(), "/日本%2F%FF");
+	/// ```
+	#[inline]
+	pub fn to_iri(&self) -> Result<IriRefBuf, Error> {
+		let bytes = self.as_bytes();
+		let query = self.p.query_len.map(|len| {
+			let offset = self.p.query_offset();
+			offset..(offset + len)
+		});
+		let ip_literal = self.p.authority.and_then(|authority| {
+			let offset = self.p.authority_offset() + authority.host_offset();
+			if bytes.get(offset) == Some(&b'[') {
+				Some(offset..(offset + authority.host_len))
+			} else {
+				None
+			}
+		});
+
+		let mut iri = Vec::with_capacity(bytes.len());
+		let mut i = 0;
+		while i < bytes.len() {
+			let in_ip_literal = ip_literal.as_ref().is_some_and(|range| range.contains(&i));
+			if bytes[i] == b'%' && !in_ip_literal {
+				if let Some((c, len)) = crate::pct_decode_char(&bytes[i..]) {
+					let in_query = query.as_ref().map(|q| q.contains(&i)).unwrap_or(false);
+					let is_bidi = matches!(c, '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}');
+					if !is_bidi && (parsing::is_ucschar(c) || (in_query && parsing::is_private(c)))
+					{
+						let mut buffer = [0; 4];
+						iri.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+						i += len;
+						continue;
+					}
+				}
+			}
+
+			iri.push(bytes[i]);
+			i += 1;
+		}
+
+		IriRefBuf::new(&iri)
+	}
+
+	/// Get this IRI reference relatively to the given one.
+	///
+	/// This is the inverse of [`IriRefBuf::resolve`]: resolving the returned reference against
+	/// `other` gives back an IRI reference equal to `self`.
+	/// When only the fragment (or query) differ, the result is a same-document
+	/// reference such as `#frag` (or `?query`). Otherwise the path is expressed relatively to
+	/// the directory of the base path, using `../` to climb up the base directories.
+	///
+	/// If the scheme or authority differ, or if the path of `self` cannot be expressed
+	/// relatively to the base path, a copy of `self` is returned.
+	///
+	/// # Example
+	/// ```
+	/// # use iref::IriRef;
+	/// let a = IriRef::new("https://crates.io/").unwrap();
+	/// let b = IriRef::new("https://crates.io/crates/iref").unwrap();
+	/// let c = IriRef::new("https://crates.io/crates/json-ld").unwrap();
+	/// assert_eq!(b.relative_to(a), "crates/iref");
+	/// assert_eq!(a.relative_to(b), "../");
+	/// assert_eq!(b.relative_to(c), "iref");
+	/// assert_eq!(c.relative_to(b), "json-ld");
+	/// ```
+	#[inline]
+	pub fn relative_to<'b, Other: Into<IriRef<'b>>>(&self, other: Other) -> IriRefBuf {
+		let other = other.into();
+
+		if let Some(scheme) = self.scheme() {
+			if other.scheme() != Some(scheme) {
+				return self.into();
+			}
+		}
+
+		if self.authority() != other.authority() {
+			return self.into();
+		}
+
+		let mut result = String::new();
+		let same_path = self.path() == other.path();
+
+		if same_path && self.query() == other.query() && self.fragment().is_some() {
+			// same document, only the fragment is needed.
+		} else if same_path && self.query().is_some() {
+			result.push('?');
+			result.push_str(self.query().unwrap().as_str());
+		} else {
+			if !relative_path(self.path(), other, &mut result) {
+				return self.into();
+			}
+
+			if let Some(query) = self.query() {
+				result.push('?');
+				result.push_str(query.as_str());
+			}
+		}
+
+		if let Some(fragment) = self.fragment() {
+			result.push('#');
+			result.push_str(fragment.as_str());
+		}
+
+		IriRefBuf::new(&result).unwrap()
+	}
+}
+
+/// Write the given path relatively to the directory of the `base` path.
+///
+/// Returns `false` if the path cannot be expressed as a relative path reference.
+fn relative_path(path: Path, base: IriRef, result: &mut String) -> bool {
+	let base_path = base.path();
+
+	if path.is_empty() && !path.is_absolute() {
+		// an empty path can only be referenced by a same-document reference.
+		return false;
+	}
+
+	let base_directories: Vec<Segment> =
+		if base_path.is_empty() && !base_path.is_absolute() && base.authority().is_some() {
+			// the base path is merged as if it was `/`.
+			if !path.is_absolute() {
+				return false;
+			}
+
+			Vec::new()
+		} else {
+			if path.is_absolute() != base_path.is_absolute() {
+				return false;
+			}
+
+			let mut directories: Vec<Segment> = base_path.normalized_segments().collect();
+			if directories.last().map(|s| !s.is_open()).unwrap_or(false) {
+				directories.pop();
+			}
+
+			directories
+		};
+
+	let segments: Vec<Segment> = path.normalized_segments().collect();
+
+	let is_dot = |s: &Segment| s.as_bytes() == b"." || s.as_bytes() == b"..";
+	if base_directories.iter().any(is_dot) || segments.iter().any(is_dot) {
+		return false;
+	}
+
+	let mut common = 0;
+	while common < base_directories.len()
+		&& common < segments.len()
+		&& base_directories[common].as_pct_str() == segments[common].as_pct_str()
+		&& (common + 1 < segments.len() || segments[common].is_open())
+	{
+		common += 1;
+	}
+
+	let start = result.len();
+	for _ in common..base_directories.len() {
+		result.push_str("../");
+	}
+
+	if let Some(first) = segments.get(common) {
+		// make sure the first segment is not confused with an authority or a scheme.
+		if result.len() == start && (first.is_empty() || first.as_bytes().contains(&b':')) {
+			result.push_str("./");
+		}
+	}
+
+	for segment in &segments[common..] {
+		result.push_str(segment.as_str());
+		if segment.is_open() {
+			result.push('/');
+		}
+	}
+
+	if result.len() == start {
+		result.push_str("./");
+	}
+
+	true
+}
+
+impl<'a> AsRef<[u8]> for IriRef<'a> {
+	#[inline]
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl<'a> AsRef<str> for IriRef<'a> {
+	#[inline]
+	fn as_ref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl<'a> AsIriRef for IriRef<'a> {
+	#[inline]
+	fn as_iri_ref(&self) -> IriRef {
+		*self
+	}
+}
+
+impl<'a> fmt::Display for IriRef<'a> {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		self.as_str().fmt(f)
 	}
 }
 
+impl<'a> IriRef<'a> {
+	/// Write the components of this IRI reference as a struct with the given name,
+	/// as in `IriRef { scheme: Some("http"), authority: None, path: "a", .. }`.
+	pub(crate) fn fmt_components(&self, name: &str, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct(name)
+			.field("scheme", &self.scheme_str())
+			.field("authority", &self.authority())
+			.field("path", &self.path_str())
+			.field("query", &self.query_str())
+			.field("fragment", &self.fragment_str())
+			.finish()
+	}
+}
+
+/// Components of the IRI reference, absent ones being `None`.
+///
+/// Use the [`Display`](fmt::Display) implementation for the plain string form.
 impl<'a> fmt::Debug for IriRef<'a> {
 	#[inline]
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		self.as_str().fmt(f)
+		self.fmt_components("IriRef", f)
 	}
 }
 
 impl<'a> cmp::PartialEq for IriRef<'a> {
 	#[inline]
 	fn eq(&self, other: &IriRef) -> bool {
-		self.scheme() == other.scheme()
-			&& self.fragment() == other.fragment()
-			&& self.authority() == other.authority()
-			&& self.path() == other.path()
-			&& self.query() == other.query()
+		self.fragment() == other.fragment() && self.eq_ignoring_fragment(*other)
 	}
 }
 
@@ -431,9 +1263,12 @@ impl<'a> cmp::PartialEq<IriRefBuf> for IriRef<'a> {
 	}
 }
 
-impl<'a> cmp::PartialEq<Iri<'a>> for IriRef<'a> {
+/// Normalizing comparison with an IRI.
+///
+/// An IRI reference without scheme is never equal to an IRI.
+impl<'a, 'b> cmp::PartialEq<Iri<'b>> for IriRef<'a> {
 	#[inline]
-	fn eq(&self, other: &Iri<'a>) -> bool {
+	fn eq(&self, other: &Iri<'b>) -> bool {
 		*self == other.as_iri_ref()
 	}
 }
@@ -445,17 +1280,57 @@ impl<'a> cmp::PartialEq<IriBuf> for IriRef<'a> {
 	}
 }
 
-impl<'a> cmp::PartialEq<&'a str> for IriRef<'a> {
+/// Normalizing comparison with a string.
+///
+/// The string is parsed as an IRI reference, then compared as with `==` between IRI
+/// references: `IriRef::new("http://a/%7E")? == "http://A/./~"` holds.
+/// A string that is not a valid IRI reference is never equal.
+/// For a byte-exact comparison, compare [`as_str`](IriRef::as_str) instead.
+impl<'a> cmp::PartialEq<str> for IriRef<'a> {
 	#[inline]
-	fn eq(&self, other: &&'a str) -> bool {
-		if let Ok(other) = IriRef::new(other) {
-			self == &other
-		} else {
-			false
+	fn eq(&self, other: &str) -> bool {
+		match IriRef::new(other) {
+			Ok(other) => *self == other,
+			Err(_) => false,
 		}
 	}
 }
 
+impl<'a> cmp::PartialEq<&str> for IriRef<'a> {
+	#[inline]
+	fn eq(&self, other: &&str) -> bool {
+		*self == **other
+	}
+}
+
+impl<'a> cmp::PartialEq<String> for IriRef<'a> {
+	#[inline]
+	fn eq(&self, other: &String) -> bool {
+		*self == *other.as_str()
+	}
+}
+
+impl<'a> cmp::PartialEq<IriRef<'a>> for str {
+	#[inline]
+	fn eq(&self, other: &IriRef<'a>) -> bool {
+		*other == *self
+	}
+}
+
+impl<'a> cmp::PartialEq<IriRef<'a>> for &str {
+	#[inline]
+	fn eq(&self, other: &IriRef<'a>) -> bool {
+		*other == **self
+	}
+}
+
+impl<'a> cmp::PartialEq<IriRef<'a>> for String {
+	#[inline]
+	fn eq(&self, other: &IriRef<'a>) -> bool {
+		*other == *self.as_str()
+	}
+}
+
 impl<'a> PartialOrd for IriRef<'a> {
 	#[inline]
 	fn partial_cmp(&self, other: &IriRef<'a>) -> Option<Ordering> {
@@ -463,6 +1338,18 @@ impl<'a> PartialOrd for IriRef<'a> {
 	}
 }
 
+/// Canonical ordering of IRI references.
+///
+/// Components are compared in the following order, the first difference deciding the result:
+///   1. scheme (no scheme comes first),
+///   2. authority (no authority comes first), itself ordered by user info, host and port,
+///   3. path, relative paths first, then segment by segment after dot segments removal,
+///   4. query (no query comes first),
+///   5. fragment (no fragment comes first).
+///
+/// Percent-encoded characters are decoded before comparison.
+/// This ordering is consistent with [`PartialEq`]:
+/// two equal IRI references always compare to [`Ordering::Equal`].
 impl<'a> Ord for IriRef<'a> {
 	#[inline]
 	fn cmp(&self, other: &IriRef<'a>) -> Ordering {
@@ -542,6 +1429,30 @@ impl<'a> From<&'a PathBuf> for IriRef<'a> {
 	}
 }
 
+/// [`fmt::Write`] adapter to an [`std::io::Write`], keeping the I/O error, if any.
+#[cfg(feature = "std")]
+struct IoAdapter<'w, W> {
+	writer: &'w mut W,
+	len: usize,
+	error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<'w, W: std::io::Write> fmt::Write for IoAdapter<'w, W> {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		match self.writer.write_all(s.as_bytes()) {
+			Ok(()) => {
+				self.len += s.len();
+				Ok(())
+			}
+			Err(e) => {
+				self.error = Some(e);
+				Err(fmt::Error)
+			}
+		}
+	}
+}
+
 impl<'a> Hash for IriRef<'a> {
 	#[inline]
 	fn hash<H: Hasher>(&self, hasher: &mut H) {
@@ -556,6 +1467,625 @@ impl<'a> Hash for IriRef<'a> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::collections::{hash_map::DefaultHasher, HashMap};
+
+	fn hash_of<T: Hash>(t: &T) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		t.hash(&mut hasher);
+		hasher.finish()
+	}
+
+	#[test]
+	fn display_normalized() {
+		let normalized = [
+			("HTTP://U%3a@A.COM:8/a/./b/../c", "http://U%3A@a.com:8/a/c"),
+			("http://%45x%41m.p/%7eu/%c3%a9", "http://exam.p/~u/%C3%A9"),
+			("http://[FE80::1%25Eth0]/", "http://[fe80::1%25eth0]/"),
+			("http://[::FFFF:C000:201]/", "http://[::ffff:192.0.2.1]/"),
+			("//[2001:DB8:0:0::1]:8", "//[2001:db8::1]:8"),
+			("http://a/b/%2e/%2E%2e/c", "http://a/b/%2E/%2E%2E/c"),
+			("http://a?q=%5a%5B#%61%2f", "http://a?q=Z%5B#a%2F"),
+			("http://a/b/..", "http://a/"),
+			("http://a/./", "http://a/"),
+			("http://a", "http://a"),
+			("http://a?q", "http://a?q"),
+			("a:/.//b", "a:/.//b"),
+			("a:b/../../c", "a:../c"),
+			("./a:b", "./a:b"),
+			("../a/./b/", "../a/b/"),
+			("/a/../../b", "/b"),
+			("a/..", ""),
+			("", ""),
+			("#F", "#F"),
+		];
+
+		for (input, expected) in &normalized {
+			let iri_ref = IriRef::new(input).unwrap();
+			let output = iri_ref.display_normalized().to_string();
+			assert_eq!(output, *expected, "normalizing {}", input);
+			assert_eq!(iri_ref.to_string(), *input);
+
+			let output = IriRef::new(&output).unwrap();
+			assert_eq!(output, iri_ref, "normalizing {}", input);
+			assert_eq!(output.display_normalized().to_string(), *expected);
+			assert_eq!(
+				iri_ref.to_owned().display_normalized().to_string(),
+				*expected
+			);
+		}
+	}
+
+	#[test]
+	fn canonical() {
+		let iri_refs = [
+			"HTTP://U%3a@A.COM:8/a/./b/../c",
+			"http://u:@a.com:8/a/c",
+			"http://U:@a.com:8/a/c",
+			"http://%45x%41m.p/%7eu/%c3%a9",
+			"http://exam.p/~u/é",
+			"http://exam.p/~u/%C3%89",
+			"http://[FE80::1%25Eth0]/",
+			"http://[fe80:0::1%25eth0]",
+			"http://[fe80::1%25%C3%A9]/",
+			"http://[fe80::1%25%c3%a9]/",
+			"http://[fe80::1%25%41]/",
+			"http://[fe80::1%25A]/",
+			"http://a/b/%2e/%2E%2e/c",
+			"http://a/b/./../c",
+			"http://a/c",
+			"http://a/c/",
+			"http://a/c%2F",
+			"http://a?q=%5a%5B#%61%2f",
+			"http://a?Z%5B=#a/",
+			"http://a?%26=%3d%3F%23#%3F%25",
+			"http://a?&==?%23#?%25",
+			"http://a?%EE%80%80#%EE%80%80",
+			"http://a?\u{E000}#%EE%80%80",
+			"a%3Ab",
+			"./a:b",
+			"a:b",
+			"%2E/a",
+			"./a",
+			"..",
+			"%2E%2e",
+			"../%2E%2E/a",
+			"%2e%2E/../a",
+			"../a/%2E%2E",
+			"../a/..",
+			"a/%2E%2E",
+			"http:../a",
+			"http:%2E%2E/a",
+			"/%2E%2E/a",
+			"/../a",
+			"a",
+			"",
+			"#F",
+			"#%46",
+		];
+
+		for a in &iri_refs {
+			let a = IriRef::new(a).unwrap();
+			let canonical = a.canonical();
+			assert_eq!(canonical, a, "canonical form of {}", a);
+			assert!(canonical.canonical().eq_exact(&canonical));
+
+			for b in &iri_refs {
+				let b = IriRef::new(b).unwrap();
+				assert_eq!(
+					a == b,
+					canonical.as_bytes() == b.canonical().as_bytes(),
+					"comparing {} and {}",
+					a,
+					b
+				);
+			}
+		}
+
+		let iri = Iri::new("HTTP://a/%7e%2f?%3d").unwrap();
+		assert_eq!(iri.canonical().as_str(), "http://a/~%2F?=");
+		assert_eq!(iri.to_owned().canonical(), iri);
+		assert_eq!(iri.as_iri_ref().to_owned().canonical(), iri);
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn write_to() {
+		use std::io::{self, Write};
+
+		let inputs = [
+			"HTTP://U%3a@A.COM:8/a/./b/../c?%7e#f",
+			"a:b/../../c",
+			"",
+			"#%61",
+		];
+
+		for input in &inputs {
+			let iri_ref = IriRef::new(input).unwrap();
+			let mut buffer = Vec::new();
+			assert_eq!(iri_ref.write_to(&mut buffer).unwrap(), input.len());
+			assert_eq!(buffer, iri_ref.as_bytes());
+
+			let normalized = iri_ref.display_normalized().to_string();
+			let mut buffer = b"prefix ".to_vec();
+			let len = iri_ref.to_owned().write_normalized_to(&mut buffer).unwrap();
+			assert_eq!(len, normalized.len());
+			assert_eq!(&buffer[7..], normalized.as_bytes());
+		}
+
+		struct Full;
+
+		impl Write for Full {
+			fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+				Err(io::ErrorKind::WriteZero.into())
+			}
+
+			fn flush(&mut self) -> io::Result<()> {
+				Ok(())
+			}
+		}
+
+		let iri_ref = IriRef::new("http://a/b").unwrap();
+		let error = iri_ref.write_normalized_to(&mut Full).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+		assert!(iri_ref.to_owned().write_to(&mut Full).is_err());
+	}
+
+	#[test]
+	fn component_ranges() {
+		let iri = Iri::new("s://u@h:1/p?q#f").unwrap();
+		let ranges = iri.component_ranges();
+		assert_eq!(ranges.scheme(), Some(0..1));
+		assert_eq!(ranges.authority(), Some(4..9));
+		assert_eq!(ranges.userinfo(), Some(4..5));
+		assert_eq!(ranges.host(), Some(6..7));
+		assert_eq!(ranges.port(), Some(8..9));
+		assert_eq!(ranges.path(), 9..11);
+		assert_eq!(ranges.query(), Some(12..13));
+		assert_eq!(ranges.fragment(), Some(14..15));
+
+		let iri_ref = IriRefBuf::new("//h:?#").unwrap();
+		let ranges = iri_ref.component_ranges();
+		assert_eq!(ranges.scheme(), None);
+		assert_eq!(ranges.authority(), Some(2..4));
+		assert_eq!(ranges.userinfo(), None);
+		assert_eq!(ranges.host(), Some(2..3));
+		assert_eq!(ranges.port(), Some(4..4));
+		assert_eq!(ranges.path(), 4..4);
+		assert_eq!(ranges.query(), Some(5..5));
+		assert_eq!(ranges.fragment(), Some(6..6));
+
+		let ranges = IriRef::new("a/b").unwrap().component_ranges();
+		assert_eq!(ranges.authority(), None);
+		assert_eq!(ranges.host(), None);
+		assert_eq!(ranges.port(), None);
+		assert_eq!(ranges.path(), 0..3);
+		assert_eq!(ranges.query(), None);
+	}
+
+	#[test]
+	fn component_spans() {
+		let spans = [
+			("http://a/b?q#f", "//a/b", "/b?q", "/b?q#f"),
+			("http://a", "//a", "", ""),
+			("http://a?q", "//a", "?q", "?q"),
+			("urn:a:b#f", "a:b", "a:b", "a:b#f"),
+			("//a/b#f", "//a/b", "/b", "/b#f"),
+			("?q", "", "?q", "?q"),
+			("", "", "", ""),
+		];
+
+		for (input, authority_and_path, path_and_query, suffix) in &spans {
+			let iri_ref = IriRef::new(input).unwrap();
+			assert_eq!(iri_ref.authority_and_path(), *authority_and_path);
+			assert_eq!(iri_ref.path_and_query(), *path_and_query);
+			assert_eq!(iri_ref.suffix_after_authority(), *suffix);
+
+			let buffer = iri_ref.to_owned();
+			assert_eq!(buffer.authority_and_path(), *authority_and_path);
+			assert_eq!(buffer.path_and_query(), *path_and_query);
+			assert_eq!(buffer.suffix_after_authority(), *suffix);
+		}
+	}
+
+	#[test]
+	fn pct_octets() {
+		use crate::parsing::Component;
+
+		let iri = Iri::new("s://u%40s@h%2Eost/p%2f%00?q=%2541#f%C3%A9").unwrap();
+		let octets: Vec<_> = iri
+			.pct_octets()
+			.map(|o| (o.component, o.byte, &iri.as_str()[o.range]))
+			.collect();
+		assert_eq!(
+			octets,
+			[
+				(Component::UserInfo, b'@', "%40"),
+				(Component::Host, b'.', "%2E"),
+				(Component::Path, b'/', "%2f"),
+				(Component::Path, 0, "%00"),
+				(Component::Query, b'%', "%25"),
+				(Component::Fragment, 0xC3, "%C3"),
+				(Component::Fragment, 0xA9, "%A9"),
+			]
+		);
+
+		let iri = Iri::new("http://[fe80::1%25eth0]/a").unwrap();
+		let octets: Vec<_> = iri.pct_octets().map(|o| (o.component, o.range)).collect();
+		assert_eq!(octets, [(Component::Host, 15..18)]);
+
+		assert_eq!(Iri::new("http://a/b?c#d").unwrap().pct_octets().count(), 0);
+		let iri_ref = IriRefBuf::new("%20#%20").unwrap();
+		assert_eq!(iri_ref.pct_octets().count(), 2);
+	}
+
+	#[test]
+	fn eq_exact() {
+		let a = Iri::new("http://example.org").unwrap();
+		let b = Iri::new("http://exa%6dple.org").unwrap();
+		let c = Iri::new("HTTP://example.org/a/../").unwrap();
+		assert!(a.eq_exact(a) && a.eq_exact(IriBuf::from(a).as_iri()));
+		assert!(!a.eq_exact(b) && !a.eq_exact(c));
+		assert!(!a.differs_only_by_normalization(a));
+		assert!(a.differs_only_by_normalization(b));
+		assert!(!a.differs_only_by_normalization(c));
+		assert!(c.differs_only_by_normalization(Iri::new("http://example.org/").unwrap()));
+
+		let buffer = IriRefBuf::new("http://exa%6Dple.org").unwrap();
+		assert!(!buffer.eq_exact(b) && buffer.differs_only_by_normalization(b));
+		assert!(!buffer.differs_only_by_normalization(Iri::new("http://example.com").unwrap()));
+	}
+
+	#[test]
+	fn eq_str() {
+		let iri_ref = IriRef::new("http://a/%7E#f").unwrap();
+		let iri = Iri::new("http://a/%7E#f").unwrap();
+		let equal = "HTTP://a/./~#f";
+		let owned = String::from(equal);
+
+		assert!(iri_ref == equal);
+		assert!(iri_ref == *equal);
+		assert!(iri_ref == owned);
+		assert!(equal == iri_ref);
+		assert!(*equal == iri_ref);
+		assert!(owned == iri_ref);
+		assert!(iri == equal);
+		assert!(equal == iri);
+		assert!(owned == iri);
+		assert!(iri_ref.to_owned() == equal);
+		assert!(equal == iri_ref.to_owned());
+		assert!(iri.to_owned() == owned);
+		assert!(owned == iri.to_owned());
+
+		assert!(iri_ref.as_str() != equal);
+		assert!(iri_ref != "http://a/%7E#g");
+		assert!("http://a/%7E#g" != iri);
+		assert!(iri != "not an IRI");
+		assert!("not an IRI" != iri_ref);
+	}
+
+	#[test]
+	fn debug() {
+		let iri_ref = IriRef::new("https://h:8042/a/b?q").unwrap();
+		assert_eq!(
+			format!("{:?}", iri_ref),
+			r#"IriRef { scheme: Some("https"), authority: Some("h:8042"), path: "/a/b", query: Some("q"), fragment: None }"#
+		);
+		assert_eq!(iri_ref.to_string(), "https://h:8042/a/b?q");
+
+		let iri_ref = IriRefBuf::new("a#").unwrap();
+		assert_eq!(
+			format!("{:?}", iri_ref),
+			r#"IriRefBuf { scheme: None, authority: None, path: "a", query: None, fragment: Some("") }"#
+		);
+
+		let iri = Iri::new("urn:a:b").unwrap();
+		assert_eq!(
+			format!("{:?}", iri),
+			r#"Iri { scheme: "urn", authority: None, path: "a:b", query: None, fragment: None }"#
+		);
+
+		let iri = IriBuf::new("file:///?").unwrap();
+		assert_eq!(
+			format!("{:?}", iri),
+			r#"IriBuf { scheme: "file", authority: Some(""), path: "/", query: Some(""), fragment: None }"#
+		);
+	}
+
+	#[test]
+	fn eq_across_types() {
+		let iri = Iri::new("http://a/b/%7E?q#f").unwrap();
+		let iri_buf = IriBuf::new("HTTP://a/b/./~?q#f").unwrap();
+		let iri_ref = IriRef::new("http://A/b/c/../~?q#f").unwrap();
+		let iri_ref_buf = IriRefBuf::new("http://a/b/%7e?q#f").unwrap();
+
+		assert!(iri == iri_buf);
+		assert!(iri == iri_ref);
+		assert!(iri == iri_ref_buf);
+		assert!(iri_buf == iri);
+		assert!(iri_buf == iri_ref);
+		assert!(iri_buf == iri_ref_buf);
+		assert!(iri_ref == iri);
+		assert!(iri_ref == iri_buf);
+		assert!(iri_ref == iri_ref_buf);
+		assert!(iri_ref_buf == iri);
+		assert!(iri_ref_buf == iri_buf);
+		assert!(iri_ref_buf == iri_ref);
+
+		// Relative references are never equal to an IRI.
+		let relative = IriRef::new("//a/b/%7E?q#f").unwrap();
+		let relative_buf = IriRefBuf::new("/b/%7E?q#f").unwrap();
+		assert!(iri != relative);
+		assert!(iri_buf != relative);
+		assert!(iri != relative_buf);
+		assert!(relative != iri);
+		assert!(relative != iri_buf);
+		assert!(relative_buf != iri_buf);
+
+		let other = Iri::new("http://a/b/%7E?q#g").unwrap();
+		assert!(other != iri_ref);
+		assert!(iri_ref != other);
+		assert!(other != iri_ref_buf);
+
+		// Operands may borrow with different lifetimes.
+		let owned = String::from("http://a/b/~?q#f");
+		let short_lived = IriRef::new(&owned).unwrap();
+		assert!(short_lived == iri);
+		assert!(iri == short_lived);
+	}
+
+	#[test]
+	fn eq_ignoring_fragment() {
+		let a = Iri::new("http://a/b#x").unwrap();
+		let b = Iri::new("http://a/b#y").unwrap();
+		assert!(a.eq_ignoring_fragment(b));
+		assert_ne!(a, b);
+
+		assert!(a.eq_ignoring_fragment(Iri::new("http://a/b").unwrap()));
+		assert!(a.eq_ignoring_fragment(Iri::new("HTTP://A/./b#z").unwrap()));
+		assert!(a.eq_ignoring_fragment(Iri::new("http://a/%62").unwrap()));
+		assert!(!a.eq_ignoring_fragment(Iri::new("http://a/b?#x").unwrap()));
+		assert!(!a.eq_ignoring_fragment(Iri::new("http://a/c#x").unwrap()));
+
+		let buffer = IriBuf::new("http://a/b#z").unwrap();
+		assert!(buffer.eq_ignoring_fragment(a));
+		assert_eq!(a.without_fragment(), b.without_fragment());
+		assert_eq!(buffer.without_fragment().as_str(), "http://a/b");
+	}
+
+	#[test]
+	fn reference_kinds() {
+		// (input, absolute, protocol-relative)
+		let kinds = [
+			("http://example.org/a?q#f", true, false),
+			("http:a", true, false),
+			("urn:isbn:0451450523", true, false),
+			("//example.org/a", false, true),
+			("//", false, true),
+			("/a//b", false, false),
+			("/", false, false),
+			("a/b", false, false),
+			("./a:b", false, false),
+			("?q", false, false),
+			("#f", false, false),
+			("", false, false),
+		];
+
+		for (input, absolute, protocol_relative) in &kinds {
+			let iri_ref = IriRef::new(input).unwrap();
+			assert_eq!(iri_ref.is_absolute(), *absolute);
+			assert_eq!(iri_ref.is_relative(), !*absolute);
+			assert_eq!(iri_ref.is_protocol_relative(), *protocol_relative);
+
+			let buffer = IriRefBuf::new(input).unwrap();
+			assert_eq!(buffer.is_absolute(), *absolute);
+			assert_eq!(buffer.is_relative(), !*absolute);
+			assert_eq!(buffer.is_protocol_relative(), *protocol_relative);
+		}
+	}
+
+	#[test]
+	fn path_escapes_root() {
+		// (input, has dot segments, escapes root)
+		let paths = [
+			("a/b/../../../", true, true),
+			("a/b/../c", true, false),
+			("a/b/../..", true, false),
+			("../a", true, true),
+			("./../a", true, true),
+			("a/./b", true, false),
+			("..a/b..", false, false),
+			("%2E%2E/a", false, false),
+			("/../a", true, false),
+			("//h/../a", true, false),
+			("s:a/../../b", true, true),
+			("a/b?../..#..", false, false),
+			("", false, false),
+		];
+
+		for (input, dots, escapes) in &paths {
+			let iri_ref = IriRef::new(input).unwrap();
+			assert_eq!(iri_ref.path().has_dot_segments(), *dots, "{}", input);
+			assert_eq!(iri_ref.path_escapes_root(), *escapes, "{}", input);
+			assert_eq!(iri_ref.to_owned().path_escapes_root(), *escapes);
+		}
+	}
+
+	#[test]
+	fn borrowed_types_are_copy() {
+		use crate::{Host, Port, UserInfo};
+
+		fn assert_copy<T: Copy>() {}
+		assert_copy::<Iri>();
+		assert_copy::<IriRef>();
+		assert_copy::<Scheme>();
+		assert_copy::<Authority>();
+		assert_copy::<UserInfo>();
+		assert_copy::<Host>();
+		assert_copy::<Port>();
+		assert_copy::<Path>();
+		assert_copy::<Segment>();
+		assert_copy::<Query>();
+		assert_copy::<Fragment>();
+
+		let base = Iri::new("http://a/b/c/d;p?q").unwrap();
+		let g = IriRef::new("g").unwrap().resolved(base);
+		let h = IriRef::new("../h").unwrap().resolved(base);
+		assert_eq!(g, "http://a/b/c/g");
+		assert_eq!(h, "http://a/b/h");
+		assert_eq!(base.authority().unwrap(), base.authority().unwrap());
+	}
+
+	#[test]
+	fn hash_consistent_with_eq() {
+		let pairs = [
+			("http://exa%6dple.org/a/./b", "http://example.org/a/b"),
+			("http://a/b/c/", "http://a/b/c/."),
+			("http://a/b/../c", "http://a/c"),
+			("a/b/../../../", "../"),
+			("?q%75ery#fr%61g", "?query#frag"),
+			("HTTP://Example.COM/", "http://example.com/"),
+			("hTTp://%45xample.com", "http://example.com"),
+			("http://[2001:DB8::1]/", "http://[2001:db8::1]/"),
+		];
+
+		for (a, b) in &pairs {
+			let a = IriRef::new(a).unwrap();
+			let b = IriRef::new(b).unwrap();
+			assert_eq!(a, b);
+			assert_eq!(hash_of(&a), hash_of(&b));
+		}
+	}
+
+	#[test]
+	fn ipv6_normalization() {
+		let iri_ref = IriRef::new("HTTP://U@[2001:DB8:0:0::1%25Eth0]:80/a?q").unwrap();
+		let normalized = iri_ref.normalized();
+		assert_eq!(normalized.as_str(), "http://U@[2001:db8::1%25eth0]:80/a?q");
+		assert_eq!(normalized.authority().unwrap().port().unwrap(), "80");
+		assert_eq!(normalized.path(), "/a");
+		assert_eq!(normalized, iri_ref);
+		assert_eq!(hash_of(&normalized), hash_of(&iri_ref.to_owned()));
+
+		let authority = iri_ref.authority().unwrap();
+		assert_eq!(authority.canonical_host(), "[2001:db8::1%25eth0]");
+	}
+
+	#[test]
+	fn case_normalization() {
+		let iri = Iri::new("HTTP://User@Example.COM:80/Path?Query#Frag").unwrap();
+		assert_eq!(iri, "http://User@example.com:80/Path?Query#Frag");
+		assert_eq!(iri.scheme(), "http");
+		assert_eq!(iri.authority().unwrap().host(), "example.com");
+
+		// User info, path, query and fragment are case-sensitive.
+		assert_ne!(iri, "http://user@example.com:80/Path?Query#Frag");
+		assert_ne!(iri, "http://User@example.com:80/path?Query#Frag");
+		assert_ne!(iri, "http://User@example.com:80/Path?query#Frag");
+		assert_ne!(iri, "http://User@example.com:80/Path?Query#frag");
+
+		let normalized = iri.normalized();
+		assert_eq!(
+			normalized.as_str(),
+			"http://User@example.com:80/Path?Query#Frag"
+		);
+		assert_eq!(normalized, iri);
+		assert_eq!(hash_of(&normalized), hash_of(&iri));
+
+		let iri_ref = IriRef::new("//Caf%C3%A9.COM/A").unwrap();
+		assert_eq!(iri_ref.normalized().as_str(), "//caf%C3%A9.com/A");
+		assert_eq!(IriRef::new("A/B").unwrap().normalized().as_str(), "A/B");
+	}
+
+	#[test]
+	fn normalized() {
+		let iri = Iri::new("http:a/b/../../../").unwrap();
+		assert_eq!(iri.normalized().as_str(), "http:../");
+		assert_eq!(iri.normalized(), iri);
+
+		let inputs = [
+			("HTTP://A/b/./c/../%7e?%41%3d#%3A", "http://a/b/~?A%3D#%3A"),
+			("a/./b/../../../c/", "../c/"),
+			("/a/../../b", "/b"),
+			("./a:b", "./a:b"),
+			("", ""),
+		];
+
+		for (input, expected) in &inputs {
+			let iri_ref = IriRef::new(input).unwrap();
+			let normalized = iri_ref.normalized();
+			assert_eq!(normalized.as_str(), *expected);
+			assert_eq!(normalized, iri_ref);
+			assert_eq!(hash_of(&normalized), hash_of(&iri_ref.to_owned()));
+			assert!(normalized.eq_exact(&normalized.normalized()));
+		}
+	}
+
+	#[test]
+	fn ordering_consistent_with_eq() {
+		let inputs = [
+			"http://exa%6dple.org/a/./b",
+			"http://example.org/a/b",
+			"http://example.org/a/b/",
+			"http://example.org/a/b?q",
+			"http://example.org/a/b#f",
+			"http://a@b%3A1",
+			"http://a@b:1",
+			"https://example.org/",
+			"HTTPS://EXAMPLE.org/",
+			"http://Example.org/a/b",
+			"http://example.org/A/b",
+			"a/b/../../../",
+			"../",
+			"/a",
+			"",
+		];
+
+		for a in &inputs {
+			for b in &inputs {
+				let a = IriRef::new(a).unwrap();
+				let b = IriRef::new(b).unwrap();
+				assert_eq!(a == b, a.cmp(&b) == Ordering::Equal, "{} <> {}", a, b);
+				assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+			}
+		}
+	}
+
+	#[test]
+	fn btree_set() {
+		use std::collections::BTreeSet;
+
+		let set: BTreeSet<IriBuf> = [
+			"https://example.org/b",
+			"http://example.org/a/b",
+			"http://exa%6dple.org/a/./b",
+			"http://example.org/a",
+		]
+		.iter()
+		.map(|s| IriBuf::new(s).unwrap())
+		.collect();
+
+		let sorted: Vec<_> = set.iter().map(|iri| iri.as_str()).collect();
+		assert_eq!(
+			sorted,
+			[
+				"http://example.org/a",
+				"http://exa%6dple.org/a/./b",
+				"https://example.org/b"
+			]
+		);
+	}
+
+	#[test]
+	fn hash_map_lookup() {
+		let mut map = HashMap::new();
+		map.insert(IriBuf::new("http://exa%6dple.org/a/./b").unwrap(), 1);
+
+		let key = IriBuf::new("http://example.org/a/b").unwrap();
+		assert_eq!(map.get(&key), Some(&1));
+
+		let other = IriBuf::new("http://example.org/a/b/").unwrap();
+		assert_eq!(map.get(&other), None);
+	}
 
 	#[test]
 	fn relative_to() {
@@ -600,4 +2130,136 @@ mod tests {
 			assert_eq!(input.relative_to(base), *expected)
 		}
 	}
+
+	#[test]
+	fn to_uri() {
+		let iri = IriBuf::new("http://例え.jp/日本語?€#%E2%82%AC").unwrap();
+		let uri = iri.to_uri();
+		assert_eq!(
+			uri.as_str(),
+			"http://%E4%BE%8B%E3%81%88.jp/%E6%97%A5%E6%9C%AC%E8%AA%9E?%E2%82%AC#%E2%82%AC"
+		);
+		assert!(uri.as_str().is_ascii());
+		assert_eq!(uri, iri);
+
+		assert_eq!(uri.to_iri().unwrap().as_str(), "http://例え.jp/日本語?€#€");
+	}
+
+	#[test]
+	fn to_iri() {
+		let challenges = [
+			("/%41%2F%25", "/%41%2F%25"),
+			("/%c3%a9t%C3%A9", "/été"),
+			("/%FF%C3", "/%FF%C3"),
+			("/%C3%28", "/%C3%28"),
+			("/%C0%AF", "/%C0%AF"),
+			("/%E2%80%8F", "/%E2%80%8F"),
+			(
+				"/%EE%80%80?%EE%80%80#%EE%80%80",
+				"/%EE%80%80?\u{E000}#%EE%80%80",
+			),
+			("//%E4%BE%8B@%E4%BE%8B", "//例@例"),
+			("//[fe80::1%25%C3%A9]/%C3%A9", "//[fe80::1%25%C3%A9]/é"),
+			("//%C3%A9@[::1]:1/%C3%A9", "//é@[::1]:1/é"),
+		];
+
+		for (uri, expected) in &challenges {
+			let uri = IriRef::new(uri).unwrap();
+			assert_eq!(uri.to_iri().unwrap().as_str(), *expected);
+		}
+	}
+
+	#[test]
+	fn relative_to_round_trip() {
+		let bases = [
+			"http://a/b/c/d;p?q",
+			"http://a/b/c/",
+			"http://a/",
+			"http://a",
+			"http://a/b/c/d#f",
+			"http://a/b//c",
+			"file:///x/y",
+			"urn:isbn:1234",
+		];
+		let targets = [
+			"http://a/b/c/d;p?q",
+			"http://a/b/c/d;p?q#f",
+			"http://a/b/c/d;p?y",
+			"http://a/b/c/d;p",
+			"http://a/b/c/d;p/",
+			"http://a/b/c/g",
+			"http://a/b/c/g:h",
+			"http://a/b/c/",
+			"http://a/b/c",
+			"http://a/b/g",
+			"http://a/b",
+			"http://a/g",
+			"http://a/x:y",
+			"http://a/",
+			"http://a",
+			"http://a//x",
+			"http://a/b//c/d",
+			"http://a/b/c/d?#",
+			"http://b/c",
+			"https://a/b",
+			"file:///x/z",
+			"urn:isbn:5678",
+		];
+
+		for base in &bases {
+			let base = Iri::new(base).unwrap();
+			for target in &targets {
+				let target = Iri::new(target).unwrap();
+				let reference = target.relative_to(base);
+				assert_eq!(
+					reference.resolved(base),
+					target,
+					"{} relative to {} gave {}",
+					target,
+					base,
+					reference
+				)
+			}
+		}
+
+		let base = Iri::new("http://a/b/c/d;p?q").unwrap();
+		let challenges = [
+			("http://a/b/c/d;p?q#f", "#f"),
+			("http://a/b/c/d;p?y", "?y"),
+			("http://a/b/c/d;p", "d;p"),
+			("http://a/b/c/", "./"),
+			("http://a/b/c/g:h", "./g:h"),
+			("http://a/g", "../../g"),
+			("http://a", "http://a"),
+			("http://b/c", "http://b/c"),
+		];
+
+		for (target, expected) in &challenges {
+			let target = Iri::new(target).unwrap();
+			assert_eq!(target.relative_to(base), *expected)
+		}
+	}
+
+	#[test]
+	fn empty_components() {
+		let iri_ref = IriRef::new("foo:///path?#").unwrap();
+		assert!(iri_ref.has_empty_authority());
+		assert!(iri_ref.has_empty_query());
+		assert!(iri_ref.has_empty_fragment());
+		assert_eq!(iri_ref.query().unwrap(), "");
+		assert_eq!(iri_ref.fragment().unwrap(), "");
+
+		let iri_ref = IriRef::new("foo:/path").unwrap();
+		assert!(!iri_ref.has_empty_authority());
+		assert!(!iri_ref.has_empty_query());
+		assert!(!iri_ref.has_empty_fragment());
+		assert!(iri_ref.query().is_none());
+		assert!(iri_ref.fragment().is_none());
+
+		let mut buffer = iri_ref.to_owned();
+		buffer.set_query(Some("".try_into().unwrap()));
+		assert_eq!(buffer, "foo:/path?");
+		assert!(buffer.has_empty_query());
+		assert!(!IriRefBuf::new("//a?b#c").unwrap().has_empty_query());
+	}
 }
@@ -0,0 +1,359 @@
+use core::fmt::{self, Write};
+
+use crate::iri::CanonicalIpv6;
+use crate::parsing;
+use crate::{Host, IriRef, Path, Segment};
+
+/// Syntax-normalized display of an IRI reference.
+///
+/// Returned by [`IriRef::display_normalized`](crate::IriRef::display_normalized).
+/// The normalized form is written directly to the formatter, without allocating.
+#[derive(Clone, Copy)]
+pub struct NormalizedDisplay<'a> {
+	iri_ref: IriRef<'a>,
+
+	/// Whether every percent-encoded character allowed in its component is decoded,
+	/// instead of only the unreserved ones.
+	canonical: bool,
+}
+
+impl<'a> NormalizedDisplay<'a> {
+	#[inline]
+	pub(crate) fn new(iri_ref: IriRef<'a>) -> NormalizedDisplay<'a> {
+		NormalizedDisplay {
+			iri_ref,
+			canonical: false,
+		}
+	}
+
+	/// Canonical form, as built by [`IriRef::canonical`](crate::IriRef::canonical).
+	#[inline]
+	pub(crate) fn canonical(iri_ref: IriRef<'a>) -> NormalizedDisplay<'a> {
+		NormalizedDisplay {
+			iri_ref,
+			canonical: true,
+		}
+	}
+}
+
+/// Characters decoded from their percent-encoding, in each component.
+struct Decode {
+	userinfo: fn(char) -> bool,
+	host: fn(char) -> bool,
+	segment: fn(char) -> bool,
+	query: fn(char) -> bool,
+	fragment: fn(char) -> bool,
+}
+
+impl Decode {
+	/// Unreserved characters, as in the syntax-based normalization.
+	const UNRESERVED: Decode = Decode {
+		userinfo: is_unreserved,
+		host: is_unreserved,
+		segment: is_unreserved,
+		query: is_unreserved,
+		fragment: is_unreserved,
+	};
+
+	/// Every character that may appear unencoded in the component.
+	const CANONICAL: Decode = Decode {
+		userinfo: parsing::is_userinfo_char,
+		host: parsing::is_reg_name_char,
+		segment: parsing::is_segment_char,
+		query: parsing::is_query_char,
+		fragment: parsing::is_fragment_char,
+	};
+}
+
+impl<'a> fmt::Display for NormalizedDisplay<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let p = &self.iri_ref.p;
+		let data = self.iri_ref.data;
+		let decode = if self.canonical {
+			&Decode::CANONICAL
+		} else {
+			&Decode::UNRESERVED
+		};
+
+		if let Some(len) = p.scheme_len {
+			write_lowercase(f, &data[..len])?;
+			f.write_char(':')?;
+		}
+
+		if let Some(authority) = p.authority {
+			f.write_str("//")?;
+			let offset = p.authority_offset();
+
+			if let Some(len) = authority.userinfo_len {
+				write_pct_normalized(f, &data[offset..(offset + len)], false, decode.userinfo)?;
+				f.write_char('@')?;
+			}
+
+			let host_offset = offset + authority.host_offset();
+			let host = Host {
+				data: &data[host_offset..(host_offset + authority.host_len)],
+			};
+			write_host(f, host, decode.host)?;
+
+			if let Some(len) = authority.port_len {
+				let port_offset = offset + authority.port_offset();
+				f.write_char(':')?;
+				write_bytes(f, &data[port_offset..(port_offset + len)])?;
+			}
+		}
+
+		let offset = p.path_offset();
+		let path = Path {
+			data: &data[offset..(offset + p.path_len)],
+		};
+
+		let mut writer = PathWriter {
+			f,
+			has_scheme: p.scheme_len.is_some(),
+			has_authority: p.authority.is_some(),
+			decode: decode.segment,
+			absolute: false,
+			parents_only: true,
+			len: 0,
+			ends_with_slash: false,
+		};
+
+		if path.is_absolute() {
+			writer.write(b"/")?;
+		}
+
+		for segment in path.normalized_segments() {
+			writer.push(segment)?;
+		}
+
+		if let Some(len) = p.query_len {
+			let offset = p.query_offset();
+			f.write_char('?')?;
+			write_pct_normalized(f, &data[offset..(offset + len)], false, decode.query)?;
+		}
+
+		if let Some(len) = p.fragment_len {
+			let offset = p.fragment_offset();
+			f.write_char('#')?;
+			write_pct_normalized(f, &data[offset..(offset + len)], false, decode.fragment)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Canonical form of a host, as written in [`IriRef::canonical`](crate::IriRef::canonical).
+pub(crate) struct CanonicalHost<'a>(pub(crate) Host<'a>);
+
+impl<'a> fmt::Display for CanonicalHost<'a> {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write_host(f, self.0, Decode::CANONICAL.host)
+	}
+}
+
+/// Writes the normalized form of a host, decoding the characters accepted by `decode`.
+fn write_host(f: &mut fmt::Formatter, host: Host, decode: fn(char) -> bool) -> fmt::Result {
+	match host.ipv6_literal() {
+		Some((addr, rest)) => {
+			// The zone identifier may only contain unreserved characters.
+			f.write_str(CanonicalIpv6::new(addr).as_str())?;
+			write_pct_normalized(f, rest.as_bytes(), true, is_unreserved)
+		}
+		None => write_pct_normalized(f, host.as_bytes(), true, decode),
+	}
+}
+
+/// Writes the normalized segments of a path,
+/// following the rules of [`PathMut::push`](crate::PathMut::push).
+struct PathWriter<'f, 'g> {
+	f: &'f mut fmt::Formatter<'g>,
+	has_scheme: bool,
+	has_authority: bool,
+	decode: fn(char) -> bool,
+
+	/// Whether the path written so far starts with a `/`.
+	absolute: bool,
+
+	/// Whether the path written so far is only made of `..` segments.
+	parents_only: bool,
+
+	/// Length of the path written so far.
+	len: usize,
+
+	/// Whether the path written so far ends with a `/`.
+	ends_with_slash: bool,
+}
+
+impl<'f, 'g> PathWriter<'f, 'g> {
+	fn is_empty(&self) -> bool {
+		self.len == 0 || (self.len == 1 && self.ends_with_slash)
+	}
+
+	fn is_relative(&self) -> bool {
+		!self.absolute
+	}
+
+	fn write(&mut self, bytes: &[u8]) -> fmt::Result {
+		if self.len == 0 {
+			self.absolute = bytes.starts_with(b"/")
+		}
+
+		self.len += bytes.len();
+		self.ends_with_slash = bytes.ends_with(b"/");
+		write_bytes(self.f, bytes)
+	}
+
+	fn open(&mut self) -> fmt::Result {
+		if !self.is_empty() && !self.ends_with_slash {
+			self.write(b"/")?;
+		}
+
+		Ok(())
+	}
+
+	fn push(&mut self, segment: Segment) -> fmt::Result {
+		if segment.is_empty() {
+			if self.is_empty() && !self.has_authority {
+				self.push(Segment::current())?;
+			}
+
+			self.open()?;
+			self.write(b"/")?;
+			self.parents_only = false;
+		} else {
+			if self.is_relative()
+				&& self.is_empty()
+				&& !self.has_scheme
+				&& !self.has_authority
+				&& (segment.as_bytes().contains(&b':')
+					|| ((self.decode)(':') && segment.as_pct_str().chars().any(|c| c == ':')))
+			{
+				self.push(Segment::current())?;
+			}
+
+			if self.len == 0 && self.has_authority {
+				self.write(b"/")?;
+			}
+
+			self.open()?;
+
+			let data = segment.as_bytes();
+			match dot_segment_len(data) {
+				Some(2) if self.is_relative() && self.parents_only => {
+					// Leading `..` segments of a relative path are kept by the normalization,
+					// and compare equal to their percent-encoded forms.
+					write_bytes(self.f, b"..")?;
+				}
+				Some(_) => {
+					// Decoding `%2E` would turn the segment into a dot segment.
+					write_pct_normalized(self.f, data, false, |_| false)?;
+					self.parents_only = false;
+				}
+				None => {
+					write_pct_normalized(self.f, data, false, self.decode)?;
+					self.parents_only = false;
+				}
+			}
+
+			self.len += data.len();
+			self.ends_with_slash = false;
+		}
+
+		if segment.is_open() {
+			self.open()?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns the number of dots of the segment, if it is a dot segment once its unreserved
+/// characters are decoded.
+fn dot_segment_len(data: &[u8]) -> Option<usize> {
+	let mut len = 0;
+	let mut i = 0;
+	while i < data.len() {
+		match data[i] {
+			b'.' => i += 1,
+			b'%' if data[(i + 1)..(i + 3)].eq_ignore_ascii_case(b"2E") => i += 3,
+			_ => return None,
+		}
+
+		len += 1
+	}
+
+	if len == 1 || len == 2 {
+		Some(len)
+	} else {
+		None
+	}
+}
+
+#[inline]
+fn write_bytes(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+	// Components are always sliced at ASCII delimiters.
+	f.write_str(unsafe { core::str::from_utf8_unchecked(bytes) })
+}
+
+fn write_lowercase(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+	for &b in bytes {
+		f.write_char(b.to_ascii_lowercase() as char)?
+	}
+
+	Ok(())
+}
+
+/// Checks if `c` is an unreserved ASCII character.
+fn is_unreserved(c: char) -> bool {
+	c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+/// Writes `bytes`, decoding the percent-encoded characters accepted by `decode` and
+/// uppercasing the hexadecimal digits of the others.
+/// ASCII letters are lowercased if `lowercase` is true.
+fn write_pct_normalized(
+	f: &mut fmt::Formatter,
+	bytes: &[u8],
+	lowercase: bool,
+	decode: fn(char) -> bool,
+) -> fmt::Result {
+	let mut start = 0;
+	let mut i = 0;
+
+	while i < bytes.len() {
+		let b = bytes[i];
+		if b == b'%' || (lowercase && b.is_ascii_uppercase()) {
+			write_bytes(f, &bytes[start..i])?;
+
+			if b == b'%' {
+				match crate::pct_decode_char(&bytes[i..]) {
+					Some((c, len)) if decode(c) => {
+						if lowercase {
+							f.write_char(c.to_ascii_lowercase())?
+						} else {
+							f.write_char(c)?
+						}
+
+						i += len
+					}
+					_ => {
+						f.write_char('%')?;
+						f.write_char(bytes[i + 1].to_ascii_uppercase() as char)?;
+						f.write_char(bytes[i + 2].to_ascii_uppercase() as char)?;
+						i += 3
+					}
+				}
+			} else {
+				f.write_char(b.to_ascii_lowercase() as char)?;
+				i += 1
+			}
+
+			start = i
+		} else {
+			i += 1
+		}
+	}
+
+	write_bytes(f, &bytes[start..])
+}
@@ -0,0 +1,183 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+
+use crate::{Error, IriBuf, IriRefBuf};
+
+/// Owned components of an IRI reference.
+///
+/// Returned by [`IriRefBuf::into_parts`] and [`IriBuf::into_parts`].
+/// Each component is kept as it appears in the IRI reference, still percent-encoded and
+/// without its delimiters (`:`, `//`, `?` and `#`).
+/// The authority is represented by its raw `userinfo@host:port` string: parse it with
+/// [`Authority::try_from`](crate::Authority) to access its own components.
+///
+/// The parts can be modified, then converted back into an IRI reference with `TryFrom`,
+/// which validates them as [`IriRefBuf::from_parts`] does.
+///
+/// # Example
+/// ```
+/// # use iref::{IriBuf, IriParts};
+/// # use std::convert::TryFrom;
+/// let iri = IriBuf::new("http://user@example.org:8080/a/b?q#f").unwrap();
+///
+/// let mut parts = iri.into_parts();
+/// assert_eq!(parts.scheme.as_deref(), Some("http"));
+/// assert_eq!(parts.authority.as_deref(), Some("user@example.org:8080"));
+/// assert_eq!(parts.path, "/a/b");
+/// assert_eq!(parts.query.as_deref(), Some("q"));
+/// assert_eq!(parts.fragment.as_deref(), Some("f"));
+///
+/// parts.scheme = Some("https".to_string());
+/// parts.fragment = None;
+/// let iri = IriBuf::try_from(parts).unwrap();
+/// assert_eq!(iri, "https://user@example.org:8080/a/b?q");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct IriParts {
+	/// Scheme, if any.
+	pub scheme: Option<String>,
+
+	/// Authority, if any, as a raw `userinfo@host:port` string.
+	pub authority: Option<String>,
+
+	/// Path, possibly empty.
+	pub path: String,
+
+	/// Query, if any.
+	pub query: Option<String>,
+
+	/// Fragment, if any.
+	pub fragment: Option<String>,
+}
+
+impl IriRefBuf {
+	/// Consume the IRI reference and return its components as owned strings.
+	///
+	/// The buffer of the IRI reference is reused for the path,
+	/// so only the other components are copied.
+	/// See [`IriParts`].
+	pub fn into_parts(self) -> IriParts {
+		let ranges = self.component_ranges();
+		let to_string = |range: Option<core::ops::Range<usize>>| {
+			range.map(|range| String::from(&self.as_str()[range]))
+		};
+
+		let scheme = to_string(ranges.scheme());
+		let authority = to_string(ranges.authority());
+		let query = to_string(ranges.query());
+		let fragment = to_string(ranges.fragment());
+
+		let path = ranges.path();
+		let mut data = self.data;
+		data.truncate(path.end);
+		data.drain(..path.start);
+
+		IriParts {
+			scheme,
+			authority,
+			path: unsafe { String::from_utf8_unchecked(data) },
+			query,
+			fragment,
+		}
+	}
+}
+
+impl IriBuf {
+	/// Consume the IRI and return its components as owned strings.
+	///
+	/// The scheme of the returned parts is always defined.
+	/// See [`IriRefBuf::into_parts`].
+	#[inline]
+	pub fn into_parts(self) -> IriParts {
+		self.0.into_parts()
+	}
+}
+
+impl TryFrom<IriParts> for IriRefBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(parts: IriParts) -> Result<IriRefBuf, Error> {
+		IriRefBuf::from_parts(
+			parts.scheme.as_deref(),
+			parts.authority.as_deref(),
+			&parts.path,
+			parts.query.as_deref(),
+			parts.fragment.as_deref(),
+		)
+	}
+}
+
+impl TryFrom<IriParts> for IriBuf {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(parts: IriParts) -> Result<IriBuf, Error> {
+		IriBuf::from_parts(
+			parts.scheme.as_deref(),
+			parts.authority.as_deref(),
+			&parts.path,
+			parts.query.as_deref(),
+			parts.fragment.as_deref(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::IriParts;
+	use crate::{Error, IriBuf, IriRefBuf};
+	use std::convert::TryFrom;
+
+	#[test]
+	fn into_parts() {
+		let parts = |iri_ref| IriRefBuf::new(iri_ref).unwrap().into_parts();
+		let owned = |s: &str| Some(s.to_string());
+
+		assert_eq!(
+			parts("foo://u@h:1/a%20b?q#f"),
+			IriParts {
+				scheme: owned("foo"),
+				authority: owned("u@h:1"),
+				path: "/a%20b".to_string(),
+				query: owned("q"),
+				fragment: owned("f"),
+			}
+		);
+		assert_eq!(
+			parts("//?#"),
+			IriParts {
+				authority: owned(""),
+				query: owned(""),
+				fragment: owned(""),
+				..IriParts::default()
+			}
+		);
+		assert_eq!(parts(""), IriParts::default());
+
+		for iri_ref in &[
+			"foo:a/b",
+			"//h/a?q",
+			"a:b/c#f",
+			"./a:b",
+			"",
+			"http://[::1]:8",
+		] {
+			let recomposed = IriRefBuf::try_from(parts(iri_ref)).unwrap();
+			assert_eq!(recomposed.as_str(), *iri_ref);
+		}
+
+		let iri = IriBuf::new("http://h/a").unwrap();
+		let mut parts = iri.into_parts();
+		parts.scheme = None;
+		assert!(matches!(
+			IriBuf::try_from(parts.clone()),
+			Err(Error::MissingScheme)
+		));
+		parts.path = "a".to_string();
+		assert!(matches!(
+			IriRefBuf::try_from(parts),
+			Err(Error::InvalidPath)
+		));
+	}
+}
@@ -0,0 +1,108 @@
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use crate::parsing::{Component, ParsedIriRef};
+
+/// Percent-encoded octet of an IRI reference.
+///
+/// Returned by the [`IriRef::pct_octets`](crate::IriRef::pct_octets) iterator.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PctOctet {
+	/// Component containing the octet.
+	pub component: Component,
+
+	/// Decoded octet.
+	pub byte: u8,
+
+	/// Byte range of the percent-encoded triplet (such as `%20`) in the IRI reference.
+	pub range: Range<usize>,
+}
+
+/// Iterator over the percent-encoded octets of an IRI reference.
+///
+/// See [`IriRef::pct_octets`](crate::IriRef::pct_octets).
+#[derive(Clone)]
+pub struct PctOctets<'a> {
+	data: &'a [u8],
+
+	/// Components in order, with their start and end offsets.
+	components: [(Component, usize, usize); 6],
+
+	/// Index of the current component.
+	index: usize,
+
+	/// Offset of the next byte to scan.
+	offset: usize,
+}
+
+impl<'a> PctOctets<'a> {
+	pub(crate) fn new(p: &ParsedIriRef, data: &'a [u8]) -> PctOctets<'a> {
+		let span = |component: Component, offset: usize, len: Option<usize>| match len {
+			Some(len) => (component, offset, offset + len),
+			None => (component, offset, offset),
+		};
+
+		let (userinfo, host) = match p.authority {
+			Some(authority) => {
+				let offset = p.authority_offset();
+				(
+					span(Component::UserInfo, offset, authority.userinfo_len),
+					span(
+						Component::Host,
+						offset + authority.host_offset(),
+						Some(authority.host_len),
+					),
+				)
+			}
+			None => (
+				span(Component::UserInfo, 0, None),
+				span(Component::Host, 0, None),
+			),
+		};
+
+		PctOctets {
+			data,
+			components: [
+				span(Component::Scheme, 0, p.scheme_len),
+				userinfo,
+				host,
+				span(Component::Path, p.path_offset(), Some(p.path_len)),
+				span(Component::Query, p.query_offset(), p.query_len),
+				span(Component::Fragment, p.fragment_offset(), p.fragment_len),
+			],
+			index: 0,
+			offset: 0,
+		}
+	}
+}
+
+impl<'a> Iterator for PctOctets<'a> {
+	type Item = PctOctet;
+
+	fn next(&mut self) -> Option<PctOctet> {
+		let hex = |b: u8| (b as char).to_digit(16).unwrap() as u8;
+
+		while let Some(&(component, start, end)) = self.components.get(self.index) {
+			let mut i = core::cmp::max(self.offset, start);
+			while i < end {
+				if self.data[i] == b'%' {
+					// Percent-encoded octets are validated by the parser.
+					self.offset = i + 3;
+					return Some(PctOctet {
+						component,
+						byte: hex(self.data[i + 1]) << 4 | hex(self.data[i + 2]),
+						range: i..(i + 3),
+					});
+				}
+
+				i += 1
+			}
+
+			self.index += 1
+		}
+
+		None
+	}
+}
+
+impl<'a> FusedIterator for PctOctets<'a> {}
@@ -0,0 +1,26 @@
+/// Branch of the [RFC 3986 section 5.2.2](https://tools.ietf.org/html/rfc3986#section-5.2.2)
+/// algorithm taken to resolve an IRI reference.
+///
+/// Returned by [`IriRefBuf::resolve_with_kind`](crate::IriRefBuf::resolve_with_kind)
+/// and [`IriRef::resolved_with_kind`](crate::IriRef::resolved_with_kind).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ResolutionKind {
+	/// The reference has a scheme, such as `http://a/b`, and ignores the base.
+	Absolute,
+
+	/// The reference is a network-path reference, such as `//a/b`,
+	/// only taking the scheme of the base.
+	NetworkPath,
+
+	/// The reference is an absolute-path reference, such as `/a/b`,
+	/// replacing the path of the base.
+	AbsolutePath,
+
+	/// The reference is a relative-path reference, such as `a/b`,
+	/// merged with the path of the base.
+	RelativePath,
+
+	/// The reference has an empty path, such as `?q`, `#f` or the empty reference,
+	/// keeping the path of the base, and its query if the reference has none.
+	Empty,
+}
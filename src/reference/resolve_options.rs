@@ -0,0 +1,17 @@
+/// Options of the [`resolved_with`](crate::IriRef::resolved_with) reference resolution.
+///
+/// The default options give the standard
+/// [RFC 3986 section 5.2](https://tools.ietf.org/html/rfc3986#section-5.2) resolution,
+/// as performed by [`resolved`](crate::IriRef::resolved).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolveOptions {
+	/// Keep the fragment of the base IRI when the reference has none.
+	///
+	/// **This is not standard**: RFC 3986 always discards the fragment of the base,
+	/// so that `g` resolved against `http://a/b#f` gives `http://a/g`.
+	/// With this option, it gives `http://a/g#f` instead, as expected by some
+	/// applications (for instance when handling a JSON-LD `@base` with a fragment).
+	/// A reference with its own fragment, even empty, still replaces the fragment of the base.
+	pub inherit_base_fragment: bool,
+}
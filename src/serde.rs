@@ -0,0 +1,234 @@
+//! [Serde](https://serde.rs) support.
+//!
+//! Every IRI type is serialized as its string representation by human-readable formats
+//! (such as JSON), and as raw bytes by compact formats.
+//! The owned types [`IriBuf`] and [`IriRefBuf`] can be deserialized from any string or bytes,
+//! while the borrowed types [`Iri`] and [`IriRef`] require the deserializer to hand out
+//! borrowed data, which is then validated without any allocation.
+use alloc::{format, string::String};
+use core::fmt;
+
+use ::serde::{
+	de::{self, Deserialize, Deserializer, Visitor},
+	ser::{Serialize, Serializer},
+};
+
+use crate::{Iri, IriBuf, IriRef, IriRefBuf};
+
+#[inline]
+fn serialize_iri<S: Serializer>(iri_ref: IriRef, serializer: S) -> Result<S::Ok, S::Error> {
+	if serializer.is_human_readable() {
+		serializer.serialize_str(iri_ref.as_str())
+	} else {
+		serializer.serialize_bytes(iri_ref.as_bytes())
+	}
+}
+
+/// Build the error reported when the input is not a valid IRI (reference).
+fn invalid<E: de::Error>(expected: &str, data: &[u8], e: crate::Error) -> E {
+	E::custom(format!(
+		"invalid {} `{}`: {}",
+		expected,
+		String::from_utf8_lossy(data),
+		e
+	))
+}
+
+impl<'a> Serialize for IriRef<'a> {
+	#[inline]
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize_iri(*self, serializer)
+	}
+}
+
+impl<'a> Serialize for Iri<'a> {
+	#[inline]
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize_iri(self.as_iri_ref(), serializer)
+	}
+}
+
+impl Serialize for IriRefBuf {
+	#[inline]
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize_iri(self.as_iri_ref(), serializer)
+	}
+}
+
+impl Serialize for IriBuf {
+	#[inline]
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize_iri(self.as_iri_ref(), serializer)
+	}
+}
+
+struct IriRefVisitor;
+
+impl<'de> Visitor<'de> for IriRefVisitor {
+	type Value = IriRef<'de>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a borrowed IRI reference")
+	}
+
+	fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<IriRef<'de>, E> {
+		self.visit_borrowed_bytes(v.as_bytes())
+	}
+
+	fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<IriRef<'de>, E> {
+		IriRef::new(v).map_err(|e| invalid("IRI reference", v, e))
+	}
+}
+
+struct IriVisitor;
+
+impl<'de> Visitor<'de> for IriVisitor {
+	type Value = Iri<'de>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a borrowed IRI")
+	}
+
+	fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Iri<'de>, E> {
+		self.visit_borrowed_bytes(v.as_bytes())
+	}
+
+	fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Iri<'de>, E> {
+		Iri::new(v).map_err(|e| invalid("IRI", v, e))
+	}
+}
+
+struct IriRefBufVisitor;
+
+impl<'de> Visitor<'de> for IriRefBufVisitor {
+	type Value = IriRefBuf;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("an IRI reference")
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<IriRefBuf, E> {
+		self.visit_bytes(v.as_bytes())
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<IriRefBuf, E> {
+		IriRefBuf::new(v).map_err(|e| invalid("IRI reference", v, e))
+	}
+}
+
+struct IriBufVisitor;
+
+impl<'de> Visitor<'de> for IriBufVisitor {
+	type Value = IriBuf;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("an IRI")
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<IriBuf, E> {
+		self.visit_bytes(v.as_bytes())
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<IriBuf, E> {
+		IriBuf::new(v).map_err(|e| invalid("IRI", v, e))
+	}
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for IriRef<'a> {
+	#[inline]
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_str(IriRefVisitor)
+		} else {
+			deserializer.deserialize_bytes(IriRefVisitor)
+		}
+	}
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Iri<'a> {
+	#[inline]
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_str(IriVisitor)
+		} else {
+			deserializer.deserialize_bytes(IriVisitor)
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for IriRefBuf {
+	#[inline]
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_str(IriRefBufVisitor)
+		} else {
+			deserializer.deserialize_byte_buf(IriRefBufVisitor)
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for IriBuf {
+	#[inline]
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_str(IriBufVisitor)
+		} else {
+			deserializer.deserialize_byte_buf(IriBufVisitor)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Iri, IriBuf, IriRef, IriRefBuf};
+	use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+	#[test]
+	fn json_round_trip() {
+		let inputs = [
+			"https://www.rust-lang.org/foo/bar?query#frag",
+			"foo://user@example.com:8042/over/there?name=ferret#nose",
+			"http://exa%6dple.org/a/./b",
+			"file:///etc/hosts",
+			"urn:isbn:0451450523",
+		];
+
+		for input in &inputs {
+			let iri = IriBuf::new(input).unwrap();
+			let json = serde_json::to_string(&iri).unwrap();
+			let back: IriBuf = serde_json::from_str(&json).unwrap();
+			assert_eq!(back.as_str(), iri.as_str());
+
+			let borrowed: Iri = serde_json::from_str(&json).unwrap();
+			assert_eq!(borrowed.as_str(), iri.as_str());
+		}
+	}
+
+	#[test]
+	fn json_invalid() {
+		let err = serde_json::from_str::<IriBuf>("\"no-scheme/path\"").unwrap_err();
+		assert!(err.to_string().contains("no-scheme/path"));
+
+		assert!(serde_json::from_str::<IriRefBuf>("\"a b\"").is_err());
+		assert!(serde_json::from_str::<IriRefBuf>("\"relative/path\"").is_ok());
+	}
+
+	#[test]
+	fn readable_tokens() {
+		let iri = IriBuf::new("http://a/b?c#d").unwrap();
+		assert_tokens(&iri.readable(), &[Token::Str("http://a/b?c#d")]);
+
+		let iri_ref = IriRef::new("../b?c#d").unwrap();
+		assert_tokens(&iri_ref.readable(), &[Token::BorrowedStr("../b?c#d")]);
+	}
+
+	#[test]
+	fn compact_tokens() {
+		let iri = IriBuf::new("http://a/b?c#d").unwrap();
+		assert_tokens(&iri.clone().compact(), &[Token::Bytes(b"http://a/b?c#d")]);
+		assert_de_tokens(&iri.compact(), &[Token::ByteBuf(b"http://a/b?c#d")]);
+
+		let iri = Iri::new("http://a/b?c#d").unwrap();
+		assert_tokens(&iri.compact(), &[Token::BorrowedBytes(b"http://a/b?c#d")]);
+	}
+}
@@ -0,0 +1,1219 @@
+//! URI Templates, as defined by [RFC 6570](https://tools.ietf.org/html/rfc6570).
+//!
+//! A [`Template`] such as `http://example.com/users/{id}{?fields*}` is expanded into an IRI
+//! by replacing its `{...}` expressions with the values of some [`Variables`].
+//! The four levels of the RFC are supported: simple (`{var}`), reserved (`{+var}`) and
+//! fragment (`{#var}`) expansions, label (`{.var}`), path segment (`{/var}`),
+//! path parameter (`{;var}`), query (`{?var}`) and query continuation (`{&var}`) expansions
+//! with several variables, and the prefix (`{var:3}`) and explode (`{var*}`) modifiers.
+//!
+//! Variable values are percent-encoded as the RFC specifies, including non-ASCII characters.
+//! Non-ASCII characters of the template literals are kept as is, so that the expansion of an
+//! IRI template is an IRI.
+//!
+//! # Example
+//! ```
+//! use std::collections::HashMap;
+//! use iref::template::{Template, Value};
+//!
+//! let template = Template::parse("http://example.com/users/{id}{?fields*}").unwrap();
+//!
+//! let mut vars = HashMap::new();
+//! vars.insert("id".to_string(), Value::from("a b"));
+//! vars.insert("fields".to_string(), Value::from(vec!["name", "email"]));
+//!
+//! let iri = template.expand(&vars).unwrap();
+//! assert_eq!(iri, "http://example.com/users/a%20b?fields=name&fields=email");
+//! ```
+use alloc::{
+	collections::BTreeMap,
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::error::Error as StdError;
+use core::fmt;
+use core::ops::Range;
+
+use crate::{Error, IriBuf, IriRefBuf};
+
+/// Value of a template variable.
+///
+/// A list or map without items is undefined, as a missing variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+	/// String value.
+	String(String),
+
+	/// List of strings.
+	List(Vec<String>),
+
+	/// Ordered list of `(key, value)` pairs.
+	Map(Vec<(String, String)>),
+}
+
+impl Value {
+	/// Checks if the value is defined, that is if it is not an empty list or map.
+	#[inline]
+	pub fn is_defined(&self) -> bool {
+		match self {
+			Value::String(_) => true,
+			Value::List(items) => !items.is_empty(),
+			Value::Map(pairs) => !pairs.is_empty(),
+		}
+	}
+}
+
+impl<'a> From<&'a str> for Value {
+	#[inline]
+	fn from(value: &'a str) -> Value {
+		Value::String(value.to_string())
+	}
+}
+
+impl From<String> for Value {
+	#[inline]
+	fn from(value: String) -> Value {
+		Value::String(value)
+	}
+}
+
+impl<'a> From<Vec<&'a str>> for Value {
+	#[inline]
+	fn from(items: Vec<&'a str>) -> Value {
+		Value::List(items.into_iter().map(ToString::to_string).collect())
+	}
+}
+
+impl From<Vec<String>> for Value {
+	#[inline]
+	fn from(items: Vec<String>) -> Value {
+		Value::List(items)
+	}
+}
+
+impl<'a> From<Vec<(&'a str, &'a str)>> for Value {
+	#[inline]
+	fn from(pairs: Vec<(&'a str, &'a str)>) -> Value {
+		Value::Map(
+			pairs
+				.into_iter()
+				.map(|(key, value)| (key.to_string(), value.to_string()))
+				.collect(),
+		)
+	}
+}
+
+impl From<Vec<(String, String)>> for Value {
+	#[inline]
+	fn from(pairs: Vec<(String, String)>) -> Value {
+		Value::Map(pairs)
+	}
+}
+
+/// Variables used to expand a [`Template`].
+///
+/// Implemented by maps from variable names to [`Value`]s.
+pub trait Variables {
+	/// Returns the value of the given variable, if any.
+	fn get(&self, name: &str) -> Option<&Value>;
+}
+
+impl Variables for BTreeMap<String, Value> {
+	#[inline]
+	fn get(&self, name: &str) -> Option<&Value> {
+		BTreeMap::get(self, name)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<S: std::hash::BuildHasher> Variables for std::collections::HashMap<String, Value, S> {
+	#[inline]
+	fn get(&self, name: &str) -> Option<&Value> {
+		std::collections::HashMap::get(self, name)
+	}
+}
+
+/// Error returned when parsing an invalid [`Template`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateError {
+	/// Byte offset of the error in the template.
+	///
+	/// It is equal to the template length if an expression is not closed.
+	pub offset: usize,
+}
+
+impl fmt::Display for TemplateError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid URI template at byte {}", self.offset)
+	}
+}
+
+impl StdError for TemplateError {}
+
+/// Expression operator.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operator {
+	Simple,
+	Reserved,
+	Fragment,
+	Label,
+	PathSegment,
+	PathParameter,
+	Query,
+	QueryContinuation,
+}
+
+impl Operator {
+	fn from_byte(b: u8) -> Option<Operator> {
+		match b {
+			b'+' => Some(Operator::Reserved),
+			b'#' => Some(Operator::Fragment),
+			b'.' => Some(Operator::Label),
+			b'/' => Some(Operator::PathSegment),
+			b';' => Some(Operator::PathParameter),
+			b'?' => Some(Operator::Query),
+			b'&' => Some(Operator::QueryContinuation),
+			_ => None,
+		}
+	}
+
+	/// Prefix of the expansion, if any variable is defined.
+	fn first(self) -> &'static str {
+		match self {
+			Operator::Simple | Operator::Reserved => "",
+			Operator::Fragment => "#",
+			Operator::Label => ".",
+			Operator::PathSegment => "/",
+			Operator::PathParameter => ";",
+			Operator::Query => "?",
+			Operator::QueryContinuation => "&",
+		}
+	}
+
+	/// Separator between the expanded values.
+	fn separator(self) -> char {
+		match self {
+			Operator::Simple | Operator::Reserved | Operator::Fragment => ',',
+			Operator::Label => '.',
+			Operator::PathSegment => '/',
+			Operator::PathParameter => ';',
+			Operator::Query | Operator::QueryContinuation => '&',
+		}
+	}
+
+	/// Checks if values are expanded as `name=value` pairs.
+	fn is_named(self) -> bool {
+		matches!(
+			self,
+			Operator::PathParameter | Operator::Query | Operator::QueryContinuation
+		)
+	}
+
+	/// Expansion of a named empty value, after its name.
+	fn if_empty(self) -> &'static str {
+		match self {
+			Operator::Query | Operator::QueryContinuation => "=",
+			_ => "",
+		}
+	}
+
+	/// Checks if reserved characters and percent-encoded triplets are kept in values.
+	fn allows_reserved(self) -> bool {
+		matches!(self, Operator::Reserved | Operator::Fragment)
+	}
+}
+
+/// Value modifier of a variable.
+#[derive(Clone, Copy)]
+enum Modifier {
+	None,
+
+	/// Only keep the given number of characters of the value.
+	Prefix(usize),
+
+	/// Expand each item of a list or map as a separate value.
+	Explode,
+}
+
+/// Variable of an expression.
+#[derive(Clone)]
+struct VarSpec {
+	/// Range of the variable name in the template.
+	name: Range<usize>,
+	modifier: Modifier,
+}
+
+/// Part of a template.
+#[derive(Clone)]
+enum Part {
+	/// Literal characters, as a range in the template.
+	Literal(Range<usize>),
+	Expression(Operator, Vec<VarSpec>),
+}
+
+/// URI Template.
+///
+/// See the [module documentation](self) for the supported syntax.
+#[derive(Clone)]
+pub struct Template {
+	source: String,
+	parts: Vec<Part>,
+}
+
+impl Template {
+	/// Parse a template.
+	///
+	/// Literal characters that would not be valid in an IRI are percent-encoded by the
+	/// expansion, so only the expressions are validated.
+	pub fn parse(template: &str) -> Result<Template, TemplateError> {
+		let bytes = template.as_bytes();
+		let mut parts = Vec::new();
+		let mut literal_start = 0;
+		let mut i = 0;
+
+		while i < bytes.len() {
+			match bytes[i] {
+				b'{' => {
+					if literal_start < i {
+						parts.push(Part::Literal(literal_start..i))
+					}
+
+					let end = match bytes[i..].iter().position(|b| *b == b'}') {
+						Some(len) => i + len,
+						None => {
+							return Err(TemplateError {
+								offset: bytes.len(),
+							})
+						}
+					};
+
+					parts.push(parse_expression(bytes, (i + 1)..end)?);
+					i = end + 1;
+					literal_start = i;
+				}
+				b'}' => return Err(TemplateError { offset: i }),
+				_ => i += 1,
+			}
+		}
+
+		if literal_start < bytes.len() {
+			parts.push(Part::Literal(literal_start..bytes.len()))
+		}
+
+		Ok(Template {
+			source: template.to_string(),
+			parts,
+		})
+	}
+
+	/// Returns the template, as it was parsed.
+	#[inline]
+	pub fn as_str(&self) -> &str {
+		&self.source
+	}
+
+	/// Iterate over the names of the variables used in the template.
+	///
+	/// A variable used in several expressions is returned each time.
+	#[inline]
+	pub fn variables(&self) -> impl Iterator<Item = &str> {
+		self.parts
+			.iter()
+			.filter_map(|part| match part {
+				Part::Expression(_, vars) => Some(vars),
+				Part::Literal(_) => None,
+			})
+			.flatten()
+			.map(move |var| &self.source[var.name.clone()])
+	}
+
+	/// Expand the template into an IRI, with the given variables.
+	///
+	/// Missing variables are undefined, and expand to nothing.
+	/// Returns an error if the expansion is not a valid IRI, for instance because it has
+	/// no scheme.
+	#[inline]
+	pub fn expand<V: Variables + ?Sized>(&self, vars: &V) -> Result<IriBuf, Error> {
+		IriBuf::new(&self.expand_to_string(vars))
+	}
+
+	/// Expand the template into an IRI reference, with the given variables.
+	///
+	/// See [`expand`](Template::expand).
+	#[inline]
+	pub fn expand_ref<V: Variables + ?Sized>(&self, vars: &V) -> Result<IriRefBuf, Error> {
+		IriRefBuf::new(&self.expand_to_string(vars))
+	}
+
+	fn expand_to_string<V: Variables + ?Sized>(&self, vars: &V) -> String {
+		let mut output = String::with_capacity(self.source.len());
+		for part in &self.parts {
+			match part {
+				Part::Literal(range) => push_literal(&self.source[range.clone()], &mut output),
+				Part::Expression(operator, var_specs) => {
+					let mut first = true;
+					for var in var_specs {
+						let name = &self.source[var.name.clone()];
+						let value = match vars.get(name) {
+							Some(value) if value.is_defined() => value,
+							_ => continue,
+						};
+
+						if first {
+							output.push_str(operator.first());
+							first = false;
+						} else {
+							output.push(operator.separator());
+						}
+
+						expand_value(*operator, name, var.modifier, value, &mut output)
+					}
+				}
+			}
+		}
+
+		output
+	}
+
+	/// Match the given IRI against the template, returning the values of its variables.
+	///
+	/// This is the inverse of [`expand`](Template::expand): the literal parts of the template
+	/// must appear as is in the IRI, and each expression captures the value of its variables,
+	/// percent-decoded.
+	/// Variables of simple expressions (`{var}`) only match unreserved and percent-encoded
+	/// characters, so they never span several path segments.
+	/// Variables of reserved and fragment expressions (`{+var}`, `{#var}`) match as many
+	/// characters as possible.
+	/// Variables that do not appear in the IRI, such as missing query parameters,
+	/// are absent from the result.
+	/// A variable used in several expressions must have the same percent-decoded value in
+	/// each of them, or be undefined in all of them.
+	/// Exploded variables and variables with a prefix modifier are matched as a single value.
+	/// In path parameter, query and query continuation expansions, a name only matches if it
+	/// is not the prefix of a longer name.
+	///
+	/// States of the search that failed are remembered, so without repeated variable names,
+	/// the matching takes a time quadratic in the length of the IRI for each variable.
+	///
+	/// Returns `None` if the IRI does not match the template.
+	/// Requires the `std` feature.
+	///
+	/// # Example
+	/// ```
+	/// use iref::{template::Template, Iri};
+	///
+	/// let template = Template::parse("http://h/users/{id}/posts/{post}{?lang}").unwrap();
+	///
+	/// let vars = template.match_iri(Iri::new("http://h/users/4%202/posts/7?lang=fr").unwrap()).unwrap();
+	/// assert_eq!(vars["id"], "4 2");
+	/// assert_eq!(vars["post"], "7");
+	/// assert_eq!(vars["lang"], "fr");
+	///
+	/// assert!(template.match_iri(Iri::new("http://h/users/4/2/posts/7").unwrap()).is_none());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn match_iri(&self, iri: crate::Iri) -> Option<std::collections::HashMap<String, String>> {
+		let mut matcher = Matcher::new(self, iri.as_str());
+		if matcher.match_parts(0, 0) {
+			Some(
+				matcher
+					.captures
+					.into_iter()
+					.filter_map(|(name, value)| Some((name.to_string(), decode(value?))))
+					.collect(),
+			)
+		} else {
+			None
+		}
+	}
+}
+
+/// State of a [`Matcher`], as `(part, var, first, pos)` with the values captured for the
+/// variables appearing again from `var`, on which the result depends.
+#[cfg(feature = "std")]
+type MatchState<'i> = (usize, usize, bool, usize, Vec<Option<&'i str>>);
+
+/// Matching of an input with the parts of a template, for [`Template::match_iri`].
+///
+/// The search backtracks over the possible values of each variable, remembering the
+/// states that failed so that none is explored twice.
+#[cfg(feature = "std")]
+struct Matcher<'t, 'i> {
+	template: &'t Template,
+	input: &'i str,
+
+	/// Literal parts, as they appear in a matching input, or an empty string for expressions.
+	literals: Vec<String>,
+
+	/// Names of the variables, in the order of the template.
+	names: Vec<&'t str>,
+
+	/// Index in `names` of the first variable of each part.
+	first_var: Vec<usize>,
+
+	/// Failed states.
+	failures: std::collections::HashSet<MatchState<'i>>,
+
+	/// Raw values of the variables matched so far, `None` for undefined variables.
+	captures: Vec<(&'t str, Option<&'i str>)>,
+}
+
+#[cfg(feature = "std")]
+impl<'t, 'i> Matcher<'t, 'i> {
+	fn new(template: &'t Template, input: &'i str) -> Matcher<'t, 'i> {
+		let mut literals = Vec::with_capacity(template.parts.len());
+		let mut names = Vec::new();
+		let mut first_var = Vec::with_capacity(template.parts.len());
+		for part in &template.parts {
+			let mut literal = String::new();
+			first_var.push(names.len());
+			match part {
+				Part::Literal(range) => push_literal(&template.source[range.clone()], &mut literal),
+				Part::Expression(_, vars) => {
+					names.extend(vars.iter().map(|var| &template.source[var.name.clone()]))
+				}
+			}
+
+			literals.push(literal);
+		}
+
+		Matcher {
+			template,
+			input,
+			literals,
+			names,
+			first_var,
+			failures: std::collections::HashSet::new(),
+			captures: Vec::new(),
+		}
+	}
+
+	/// Match the parts of the template starting at `part` with `input[pos..]`.
+	fn match_parts(&mut self, part: usize, pos: usize) -> bool {
+		match self.template.parts.get(part) {
+			None => pos == self.input.len(),
+			Some(Part::Literal(_)) => {
+				let literal = &self.literals[part];
+				let end = pos + literal.len();
+				self.input[pos..].starts_with(literal.as_str()) && self.match_parts(part + 1, end)
+			}
+			Some(Part::Expression(_, _)) => self.match_vars(part, 0, true, pos),
+		}
+	}
+
+	/// Match the variables of the expression `part` starting at `var` with `input[pos..]`,
+	/// then the following parts.
+	///
+	/// `first` is `true` if no variable of the expression is defined yet.
+	fn match_vars(&mut self, part: usize, var: usize, first: bool, pos: usize) -> bool {
+		let vars = match &self.template.parts[part] {
+			Part::Expression(_, vars) => vars,
+			Part::Literal(_) => unreachable!(),
+		};
+
+		if var == vars.len() {
+			return self.match_parts(part + 1, pos);
+		}
+
+		let later = &self.names[(self.first_var[part] + var)..];
+		let bound = self
+			.captures
+			.iter()
+			.filter(|(name, _)| later.contains(name))
+			.map(|(_, value)| *value)
+			.collect();
+		let state = (part, var, first, pos, bound);
+		if self.failures.contains(&state) {
+			return false;
+		}
+
+		let matched = self.match_var(part, var, first, pos);
+		if !matched {
+			self.failures.insert(state);
+		}
+
+		matched
+	}
+
+	/// Match the variable `var` of the expression `part`, then the following ones.
+	///
+	/// The variable is first matched as defined, with its longest possible value,
+	/// then as undefined.
+	fn match_var(&mut self, part: usize, var: usize, first: bool, pos: usize) -> bool {
+		let template = self.template;
+		let input = self.input;
+		let (operator, vars) = match &template.parts[part] {
+			Part::Expression(operator, vars) => (*operator, vars),
+			Part::Literal(_) => unreachable!(),
+		};
+
+		let name = &template.source[vars[var].name.clone()];
+		let mut value_starts: Vec<(usize, bool)> = Vec::new();
+		let mut start = pos;
+
+		let prefix_matched = if first {
+			let prefix = operator.first();
+			start += prefix.len();
+			input[pos..].starts_with(prefix)
+		} else {
+			start += 1;
+			input[pos..].starts_with(operator.separator())
+		};
+
+		if prefix_matched {
+			if operator.is_named() {
+				if input[start..].starts_with(name) {
+					start += name.len();
+					if input[start..].starts_with('=') {
+						value_starts.push((start + 1, true))
+					}
+
+					// Empty value, without `=`.
+					// The name must not be the prefix of a longer one.
+					if operator.if_empty().is_empty()
+						&& !input[start..].starts_with(|c: char| is_unreserved(c) || c == '%')
+					{
+						value_starts.push((start, false))
+					}
+				}
+			} else {
+				value_starts.push((start, true))
+			}
+		}
+
+		let is_last = var + 1 == vars.len();
+		for (start, non_empty) in value_starts {
+			let mut ends = Vec::new();
+			ends.push(start);
+			if non_empty {
+				for (i, c) in input[start..].char_indices() {
+					if !operator.allows_reserved() && !is_unreserved(c) && c != '%' && c.is_ascii()
+					{
+						break;
+					}
+
+					ends.push(start + i + c.len_utf8())
+				}
+			}
+
+			for end in ends.into_iter().rev() {
+				// The value is followed by the next one, or by the next part.
+				if (is_last || !input[end..].starts_with(operator.separator()))
+					&& !self.may_end_expression(part, end)
+				{
+					continue;
+				}
+
+				let value = &input[start..end];
+				if !self.is_consistent(name, Some(value)) {
+					continue;
+				}
+
+				let len = self.captures.len();
+				self.captures.push((name, Some(value)));
+				if self.match_vars(part, var + 1, false, end) {
+					return true;
+				}
+
+				self.captures.truncate(len);
+			}
+		}
+
+		if !self.is_consistent(name, None) {
+			return false;
+		}
+
+		let len = self.captures.len();
+		self.captures.push((name, None));
+		if self.match_vars(part, var + 1, first, pos) {
+			return true;
+		}
+
+		self.captures.truncate(len);
+		false
+	}
+
+	/// Checks if the given raw value of a variable, or `None` if it is undefined,
+	/// is the same as its previous captures.
+	///
+	/// Values are compared percent-decoded, as the operators encode them differently.
+	fn is_consistent(&self, name: &str, value: Option<&str>) -> bool {
+		self.captures
+			.iter()
+			.filter(|(n, _)| *n == name)
+			.all(|(_, v)| match (v, value) {
+				(Some(a), Some(b)) => *a == b || decode(a) == decode(b),
+				(None, None) => true,
+				_ => false,
+			})
+	}
+
+	/// Checks if the expression `part` may end at `pos`, before the next part.
+	fn may_end_expression(&self, part: usize, pos: usize) -> bool {
+		match self.template.parts.get(part + 1) {
+			None => pos == self.input.len(),
+			Some(Part::Literal(_)) => {
+				self.input[pos..].starts_with(self.literals[part + 1].as_str())
+			}
+			Some(Part::Expression(_, _)) => true,
+		}
+	}
+}
+
+impl fmt::Display for Template {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.source.fmt(f)
+	}
+}
+
+impl fmt::Debug for Template {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.source.fmt(f)
+	}
+}
+
+/// Parse the expression in the given range of the template, without its braces.
+fn parse_expression(bytes: &[u8], range: Range<usize>) -> Result<Part, TemplateError> {
+	let end = range.end;
+	let mut i = range.start;
+
+	let operator = match bytes[i..end].first() {
+		Some(b) => match Operator::from_byte(*b) {
+			Some(operator) => {
+				i += 1;
+				operator
+			}
+			// Operators reserved for future extensions.
+			None if matches!(*b, b'=' | b',' | b'!' | b'@' | b'|') => {
+				return Err(TemplateError { offset: i })
+			}
+			None => Operator::Simple,
+		},
+		None => return Err(TemplateError { offset: i }),
+	};
+
+	let mut vars = Vec::new();
+	loop {
+		let name_start = i;
+		while i < end {
+			match bytes[i] {
+				b'%' if i + 2 < end
+					&& bytes[i + 1].is_ascii_hexdigit()
+					&& bytes[i + 2].is_ascii_hexdigit() =>
+				{
+					i += 3
+				}
+				b'.' if i > name_start && bytes[i - 1] != b'.' => i += 1,
+				b if b.is_ascii_alphanumeric() || b == b'_' => i += 1,
+				_ => break,
+			}
+		}
+
+		if i == name_start || bytes[i - 1] == b'.' {
+			return Err(TemplateError { offset: i });
+		}
+
+		let name = name_start..i;
+		let modifier = match bytes.get(i) {
+			Some(b'*') if i < end => {
+				i += 1;
+				Modifier::Explode
+			}
+			Some(b':') if i < end => {
+				i += 1;
+				let digits_start = i;
+				while i < end && i - digits_start < 4 && bytes[i].is_ascii_digit() {
+					i += 1
+				}
+
+				if i == digits_start || bytes[digits_start] == b'0' {
+					return Err(TemplateError {
+						offset: digits_start,
+					});
+				}
+
+				let digits = unsafe { core::str::from_utf8_unchecked(&bytes[digits_start..i]) };
+				Modifier::Prefix(digits.parse().unwrap())
+			}
+			_ => Modifier::None,
+		};
+
+		vars.push(VarSpec { name, modifier });
+
+		if i == end {
+			break Ok(Part::Expression(operator, vars));
+		} else if bytes[i] == b',' {
+			i += 1
+		} else {
+			break Err(TemplateError { offset: i });
+		}
+	}
+}
+
+/// Expand the value of a defined variable, after the operator prefix or separator.
+fn expand_value(
+	operator: Operator,
+	name: &str,
+	modifier: Modifier,
+	value: &Value,
+	output: &mut String,
+) {
+	let reserved = operator.allows_reserved();
+	let push_named_empty = |output: &mut String| {
+		output.push_str(name);
+		output.push_str(operator.if_empty())
+	};
+
+	match (value, modifier) {
+		(Value::String(value), _) if operator.is_named() && value.is_empty() => {
+			push_named_empty(output)
+		}
+		(Value::String(value), modifier) => {
+			if operator.is_named() {
+				output.push_str(name);
+				output.push('=');
+			}
+
+			let value = match modifier {
+				Modifier::Prefix(len) => match value.char_indices().nth(len) {
+					Some((end, _)) => &value[..end],
+					None => value,
+				},
+				_ => value,
+			};
+
+			push_encoded(value, reserved, output)
+		}
+		(Value::List(items), Modifier::Explode) => {
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					output.push(operator.separator());
+				}
+
+				if operator.is_named() {
+					if item.is_empty() {
+						push_named_empty(output);
+						continue;
+					}
+
+					output.push_str(name);
+					output.push('=');
+				}
+
+				push_encoded(item, reserved, output)
+			}
+		}
+		(Value::Map(pairs), Modifier::Explode) => {
+			for (i, (key, value)) in pairs.iter().enumerate() {
+				if i > 0 {
+					output.push(operator.separator());
+				}
+
+				push_encoded(key, reserved, output);
+				if operator.is_named() && value.is_empty() {
+					output.push_str(operator.if_empty());
+				} else {
+					output.push('=');
+					push_encoded(value, reserved, output)
+				}
+			}
+		}
+		(Value::List(_), _) | (Value::Map(_), _) => {
+			// Prefix modifiers do not apply to lists and maps.
+			if operator.is_named() {
+				output.push_str(name);
+				output.push('=');
+			}
+
+			let mut first = true;
+			let mut push_item = |item: &str, output: &mut String| {
+				if !first {
+					output.push(',');
+				}
+
+				first = false;
+				push_encoded(item, reserved, output)
+			};
+
+			match value {
+				Value::List(items) => items.iter().for_each(|item| push_item(item, output)),
+				Value::Map(pairs) => pairs.iter().for_each(|(key, value)| {
+					push_item(key, output);
+					push_item(value, output)
+				}),
+				Value::String(_) => unreachable!(),
+			}
+		}
+	}
+}
+
+/// Percent-decode a raw value matched in an IRI.
+#[cfg(feature = "std")]
+fn decode(value: &str) -> String {
+	crate::pct_decode_lossy(unsafe { pct_str::PctStr::new_unchecked(value) })
+}
+
+/// Checks if the given character is an ASCII unreserved character.
+fn is_unreserved(c: char) -> bool {
+	c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+/// Checks if the given character is a reserved character.
+fn is_reserved(c: char) -> bool {
+	matches!(
+		c,
+		':' | '/'
+			| '?' | '#'
+			| '[' | ']'
+			| '@' | '!'
+			| '$' | '&'
+			| '\'' | '('
+			| ')' | '*'
+			| '+' | ','
+			| ';' | '='
+	)
+}
+
+/// Checks if `s` starts with a percent-encoded triplet.
+fn starts_with_pct_triplet(s: &str) -> bool {
+	let bytes = s.as_bytes();
+	bytes.len() >= 3
+		&& bytes[0] == b'%'
+		&& bytes[1].is_ascii_hexdigit()
+		&& bytes[2].is_ascii_hexdigit()
+}
+
+/// Push the literal characters of a template to `output`.
+///
+/// Characters that cannot appear in an IRI are percent-encoded.
+fn push_literal(literal: &str, output: &mut String) {
+	for (i, c) in literal.char_indices() {
+		if is_unreserved(c)
+			|| is_reserved(c)
+			|| !c.is_ascii()
+			|| (c == '%' && starts_with_pct_triplet(&literal[i..]))
+		{
+			output.push(c)
+		} else {
+			crate::pct_encode_char(c, output)
+		}
+	}
+}
+
+/// Push the percent-encoded value of a variable to `output`.
+///
+/// Only unreserved characters are kept, unless `reserved` is `true`, in which case
+/// reserved characters and percent-encoded triplets are also kept.
+fn push_encoded(value: &str, reserved: bool, output: &mut String) {
+	for (i, c) in value.char_indices() {
+		if is_unreserved(c)
+			|| (reserved && (is_reserved(c) || (c == '%' && starts_with_pct_triplet(&value[i..]))))
+		{
+			output.push(c)
+		} else {
+			crate::pct_encode_char(c, output)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Variables of the examples of RFC 6570.
+	fn variables() -> BTreeMap<String, Value> {
+		let vars: [(&str, Value); 18] = [
+			("count", vec!["one", "two", "three"].into()),
+			("dom", vec!["example", "com"].into()),
+			("dub", "me/too".into()),
+			("hello", "Hello World!".into()),
+			("half", "50%".into()),
+			("var", "value".into()),
+			("who", "fred".into()),
+			("base", "http://example.com/home/".into()),
+			("path", "/foo/bar".into()),
+			("list", vec!["red", "green", "blue"].into()),
+			(
+				"keys",
+				vec![("semi", ";"), ("dot", "."), ("comma", ",")].into(),
+			),
+			("v", "6".into()),
+			("x", "1024".into()),
+			("y", "768".into()),
+			("empty", "".into()),
+			("empty_keys", Value::Map(Vec::new())),
+			("unicode", "é".into()),
+			("empty_list", Value::List(Vec::new())),
+		];
+
+		vars.iter()
+			.map(|(name, value)| (name.to_string(), value.clone()))
+			.collect()
+	}
+
+	/// Examples of RFC 6570, section 3.2.
+	const EXAMPLES: [(&str, &str); 68] = [
+		// Simple string expansion.
+		("{var}", "value"),
+		("{hello}", "Hello%20World%21"),
+		("{half}", "50%25"),
+		("O{empty}X", "OX"),
+		("O{undef}X", "OX"),
+		("{x,y}", "1024,768"),
+		("{x,hello,y}", "1024,Hello%20World%21,768"),
+		("?{x,empty}", "?1024,"),
+		("?{x,undef}", "?1024"),
+		("?{undef,y}", "?768"),
+		("{var:3}", "val"),
+		("{var:30}", "value"),
+		("{list}", "red,green,blue"),
+		("{list*}", "red,green,blue"),
+		("{keys}", "semi,%3B,dot,.,comma,%2C"),
+		("{keys*}", "semi=%3B,dot=.,comma=%2C"),
+		// Reserved expansion.
+		("{+var}", "value"),
+		("{+hello}", "Hello%20World!"),
+		("{+half}", "50%25"),
+		("{base}index", "http%3A%2F%2Fexample.com%2Fhome%2Findex"),
+		("{+base}index", "http://example.com/home/index"),
+		("O{+empty}X", "OX"),
+		("{+path}/here", "/foo/bar/here"),
+		("here?ref={+path}", "here?ref=/foo/bar"),
+		("up{+path}{var}/here", "up/foo/barvalue/here"),
+		("{+x,hello,y}", "1024,Hello%20World!,768"),
+		("{+path:6}/here", "/foo/b/here"),
+		("{+list*}", "red,green,blue"),
+		("{+keys*}", "semi=;,dot=.,comma=,"),
+		// Fragment expansion.
+		("{#var}", "#value"),
+		("{#hello}", "#Hello%20World!"),
+		("{#half}", "#50%25"),
+		("foo{#empty}", "foo#"),
+		("foo{#undef}", "foo"),
+		("{#x,hello,y}", "#1024,Hello%20World!,768"),
+		("{#path,x}/here", "#/foo/bar,1024/here"),
+		("{#path:6}/here", "#/foo/b/here"),
+		("{#list*}", "#red,green,blue"),
+		("{#keys}", "#semi,;,dot,.,comma,,"),
+		// Label expansion.
+		("{.who}", ".fred"),
+		("{.who,who}", ".fred.fred"),
+		("{.half,who}", ".50%25.fred"),
+		("www{.dom*}", "www.example.com"),
+		("X{.var:3}", "X.val"),
+		("X{.empty}", "X."),
+		("X{.undef}", "X"),
+		("X{.list*}", "X.red.green.blue"),
+		("X{.keys*}", "X.semi=%3B.dot=..comma=%2C"),
+		("X{.empty_keys}", "X"),
+		// Path segment expansion.
+		("{/who,who}", "/fred/fred"),
+		("{/half,who}", "/50%25/fred"),
+		("{/who,dub}", "/fred/me%2Ftoo"),
+		("{/var,empty}", "/value/"),
+		("{/var,undef}", "/value"),
+		("{/var,x}/here", "/value/1024/here"),
+		("{/var:1,var}", "/v/value"),
+		("{/list*,path:4}", "/red/green/blue/%2Ffoo"),
+		("{/keys*}", "/semi=%3B/dot=./comma=%2C"),
+		// Path-style parameter expansion.
+		("{;who}", ";who=fred"),
+		("{;v,empty,who}", ";v=6;empty;who=fred"),
+		("{;x,y,undef}", ";x=1024;y=768"),
+		("{;list*}", ";list=red;list=green;list=blue"),
+		("{;keys*}", ";semi=%3B;dot=.;comma=%2C"),
+		// Form-style query expansion.
+		("{?x,y,empty}", "?x=1024&y=768&empty="),
+		("{?list}", "?list=red,green,blue"),
+		("{?keys*}", "?semi=%3B&dot=.&comma=%2C"),
+		// Form-style query continuation.
+		("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+		("{&list*}", "&list=red&list=green&list=blue"),
+	];
+
+	#[test]
+	fn rfc_examples() {
+		let vars = variables();
+		for (template, expected) in &EXAMPLES {
+			let expanded = Template::parse(template).unwrap().expand_to_string(&vars);
+			assert_eq!(expanded, *expected, "{}", template);
+		}
+	}
+
+	#[test]
+	fn expand() {
+		let vars = variables();
+		let template =
+			Template::parse("http://例え.jp/{who}/{unicode}{?list,empty_list}#{var}").unwrap();
+		assert_eq!(
+			template.expand(&vars).unwrap().as_str(),
+			"http://例え.jp/fred/%C3%A9?list=red,green,blue#value"
+		);
+		assert_eq!(template.to_string(), template.as_str());
+
+		let variables: Vec<_> = template.variables().collect();
+		assert_eq!(variables, ["who", "unicode", "list", "empty_list", "var"]);
+
+		let template = Template::parse("{/path}{?x}").unwrap();
+		assert!(matches!(template.expand(&vars), Err(Error::MissingScheme)));
+		assert_eq!(
+			template.expand_ref(&vars).unwrap().as_str(),
+			"/%2Ffoo%2Fbar?x=1024"
+		);
+
+		let template = Template::parse("foo:a b|{+half}").unwrap();
+		assert_eq!(
+			template.expand(&vars).unwrap().as_str(),
+			"foo:a%20b%7C50%25"
+		);
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn match_iri() {
+		let matched = |template, iri| {
+			let template = Template::parse(template).unwrap();
+			let vars = template.match_iri(crate::Iri::new(iri).unwrap())?;
+			let mut vars: Vec<_> = vars.into_iter().collect();
+			vars.sort();
+			Some(vars)
+		};
+		let vars = |vars: &[(&str, &str)]| {
+			Some(
+				vars.iter()
+					.map(|(name, value)| (name.to_string(), value.to_string()))
+					.collect::<Vec<_>>(),
+			)
+		};
+
+		assert_eq!(
+			matched("http://h/users/{id}", "http://h/users/42"),
+			vars(&[("id", "42")])
+		);
+		assert_eq!(
+			matched("http://h/{a}/{b}.json", "http://h/x%2Fy/1.2.json"),
+			vars(&[("a", "x/y"), ("b", "1.2")])
+		);
+		assert_eq!(
+			matched("http://h/{a}{/b,c}", "http://h/é/b/c"),
+			vars(&[("a", "é"), ("b", "b"), ("c", "c")])
+		);
+		assert_eq!(
+			matched("http://h/{+path}/raw{#frag}", "http://h/a/b/raw#f,g"),
+			vars(&[("frag", "f,g"), ("path", "a/b")])
+		);
+		assert_eq!(
+			matched(
+				"http://h/x{;v,empty}{?q,lang}",
+				"http://h/x;v=6;empty?lang=fr"
+			),
+			vars(&[("empty", ""), ("lang", "fr"), ("v", "6")])
+		);
+		assert_eq!(
+			matched("http://h/{x}/{x}", "http://h/1/1"),
+			vars(&[("x", "1")])
+		);
+
+		assert_eq!(matched("http://h/users/{id}", "http://h/users/4/2"), None);
+		assert_eq!(matched("http://h/users/{id}", "http://h/posts/42"), None);
+		assert_eq!(matched("http://h/{x}/{x}", "http://h/1/2"), None);
+		assert_eq!(matched("http://h/{?q}", "http://h/?r=1"), None);
+		assert_eq!(
+			matched("http://h/x{;v}{+rest}", "http://h/x;vx=1"),
+			vars(&[("rest", ";vx=1")])
+		);
+		assert_eq!(
+			matched("http://h/x{;v}{+rest}", "http://h/x;v/y"),
+			vars(&[("rest", "/y"), ("v", "")])
+		);
+		assert_eq!(matched("http://h/{?q}", "http://h/?qq=1"), None);
+
+		// Repeated variables are compared decoded, and stay undefined once undefined.
+		assert_eq!(
+			matched("http://h/{x}{#x}", "http://h/a%2Cb#a,b"),
+			vars(&[("x", "a,b")])
+		);
+		assert_eq!(
+			matched("http://h/{x}/{+x}", "http://h/a%2Fb/a/b"),
+			vars(&[("x", "a/b")])
+		);
+		assert_eq!(matched("http://h/{x}/{+x}", "http://h/a/b"), None);
+		assert_eq!(
+			matched("http://h/p{?x,y}{x}", "http://h/p?y=1"),
+			vars(&[("y", "1")])
+		);
+		assert_eq!(matched("http://h/p{?x,y}/{x}", "http://h/p?y=1/2"), None);
+
+		let templates = [
+			"http://h/{x}{#x}",
+			"http://h/{x}/{+x}",
+			"http://h/{x}{?x}",
+			"http://h/{+x}{;x}{&x}",
+			"http://h/{/x}{.x}",
+		];
+		for template in &templates {
+			let template = Template::parse(template).unwrap();
+			for value in &["a,b", "a/b", "a b", "é", ""] {
+				let mut vars = BTreeMap::new();
+				vars.insert("x".to_string(), Value::from(*value));
+				let iri = template.expand(&vars).unwrap();
+				let matched = template.match_iri(iri.as_iri()).unwrap();
+				assert_eq!(matched["x"], *value, "{} with {}", template, iri);
+			}
+		}
+
+		// Without memoization, these take an exponential time.
+		let iri = format!("http://h/{}", "a".repeat(90));
+		assert_eq!(matched("http://h/{a}{b}{c}{d}{e}x", &iri), None);
+		assert_eq!(matched("http://h/{a}{b}{a}{b}x", &iri), None);
+		let iri = format!("http://h/{}", "a/".repeat(200));
+		assert_eq!(matched("http://h/{+a}{+b}{+c}x", &iri), None);
+		assert_eq!(
+			matched("http://h/{+a}{+b}/{c}", &format!("{}c", iri)),
+			vars(&[("a", &iri[9..iri.len() - 1]), ("b", ""), ("c", "c")])
+		);
+
+		// Expanded IRIs match their template.
+		let vars = variables();
+		for template in &["http://h{/who,dub}{?x,y,empty}{#path}", "foo:{+base}."] {
+			let template = Template::parse(template).unwrap();
+			let iri = template.expand(&vars).unwrap();
+			for (name, value) in template.match_iri(iri.as_iri()).unwrap() {
+				assert_eq!(Value::String(value), vars[&name]);
+			}
+		}
+	}
+
+	#[test]
+	fn invalid_templates() {
+		let invalid = [
+			("{", 1),
+			("a{var", 5),
+			("a}", 1),
+			("{}", 1),
+			("{=var}", 1),
+			("{var:0}", 5),
+			("{var:}", 5),
+			("{var:12345}", 9),
+			("{var,}", 5),
+			("{.}", 2),
+			("{a..b}", 3),
+			("{a.}", 3),
+			("{.a b}", 3),
+			("{var*x}", 5),
+		];
+
+		for (template, offset) in &invalid {
+			assert_eq!(
+				Template::parse(template).unwrap_err(),
+				TemplateError { offset: *offset },
+				"{}",
+				template
+			);
+		}
+
+		assert!(Template::parse("{a.b,%41_1:9999,c*}").is_ok());
+	}
+}
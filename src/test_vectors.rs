@@ -0,0 +1,91 @@
+//! Test vectors, for the conformance tests of downstream crates.
+//!
+//! Enabled by the `test-vectors` feature.
+
+/// Reference resolution examples of
+/// [RFC 3986 section 5.4](https://www.rfc-editor.org/rfc/rfc3986#section-5.4),
+/// as `(base, reference, result)` triples.
+///
+/// It contains every normal (section 5.4.1) and abnormal (section 5.4.2) example,
+/// against the base `http://a/b/c/d;p?q`, with the strict parser result for `http:g`.
+///
+/// It ends with examples of [Errata 4547](https://www.rfc-editor.org/errata/eid4547),
+/// implemented by this crate, against a base with a relative path: the `..` segments that
+/// cannot be removed from a relative path are preserved, so `../../../g` resolved against
+/// `foo:a/b` gives `foo:../../g`.
+pub const RFC3986_RESOLUTION_EXAMPLES: &[(&str, &str, &str)] = &[
+	// Normal examples.
+	("http://a/b/c/d;p?q", "g:h", "g:h"),
+	("http://a/b/c/d;p?q", "g", "http://a/b/c/g"),
+	("http://a/b/c/d;p?q", "./g", "http://a/b/c/g"),
+	("http://a/b/c/d;p?q", "g/", "http://a/b/c/g/"),
+	("http://a/b/c/d;p?q", "/g", "http://a/g"),
+	("http://a/b/c/d;p?q", "//g", "http://g"),
+	("http://a/b/c/d;p?q", "?y", "http://a/b/c/d;p?y"),
+	("http://a/b/c/d;p?q", "g?y", "http://a/b/c/g?y"),
+	("http://a/b/c/d;p?q", "#s", "http://a/b/c/d;p?q#s"),
+	("http://a/b/c/d;p?q", "g#s", "http://a/b/c/g#s"),
+	("http://a/b/c/d;p?q", "g?y#s", "http://a/b/c/g?y#s"),
+	("http://a/b/c/d;p?q", ";x", "http://a/b/c/;x"),
+	("http://a/b/c/d;p?q", "g;x", "http://a/b/c/g;x"),
+	("http://a/b/c/d;p?q", "g;x?y#s", "http://a/b/c/g;x?y#s"),
+	("http://a/b/c/d;p?q", "", "http://a/b/c/d;p?q"),
+	("http://a/b/c/d;p?q", ".", "http://a/b/c/"),
+	("http://a/b/c/d;p?q", "./", "http://a/b/c/"),
+	("http://a/b/c/d;p?q", "..", "http://a/b/"),
+	("http://a/b/c/d;p?q", "../", "http://a/b/"),
+	("http://a/b/c/d;p?q", "../g", "http://a/b/g"),
+	("http://a/b/c/d;p?q", "../..", "http://a/"),
+	("http://a/b/c/d;p?q", "../../", "http://a/"),
+	("http://a/b/c/d;p?q", "../../g", "http://a/g"),
+	// Abnormal examples.
+	("http://a/b/c/d;p?q", "../../../g", "http://a/g"),
+	("http://a/b/c/d;p?q", "../../../../g", "http://a/g"),
+	("http://a/b/c/d;p?q", "/./g", "http://a/g"),
+	("http://a/b/c/d;p?q", "/../g", "http://a/g"),
+	("http://a/b/c/d;p?q", "g.", "http://a/b/c/g."),
+	("http://a/b/c/d;p?q", ".g", "http://a/b/c/.g"),
+	("http://a/b/c/d;p?q", "g..", "http://a/b/c/g.."),
+	("http://a/b/c/d;p?q", "..g", "http://a/b/c/..g"),
+	("http://a/b/c/d;p?q", "./../g", "http://a/b/g"),
+	("http://a/b/c/d;p?q", "./g/.", "http://a/b/c/g/"),
+	("http://a/b/c/d;p?q", "g/./h", "http://a/b/c/g/h"),
+	("http://a/b/c/d;p?q", "g/../h", "http://a/b/c/h"),
+	("http://a/b/c/d;p?q", "g;x=1/./y", "http://a/b/c/g;x=1/y"),
+	("http://a/b/c/d;p?q", "g;x=1/../y", "http://a/b/c/y"),
+	("http://a/b/c/d;p?q", "g?y/./x", "http://a/b/c/g?y/./x"),
+	("http://a/b/c/d;p?q", "g?y/../x", "http://a/b/c/g?y/../x"),
+	("http://a/b/c/d;p?q", "g#s/./x", "http://a/b/c/g#s/./x"),
+	("http://a/b/c/d;p?q", "g#s/../x", "http://a/b/c/g#s/../x"),
+	("http://a/b/c/d;p?q", "http:g", "http:g"),
+	// Errata 4547 examples.
+	("foo:a/b", "../g", "foo:g"),
+	("foo:a/b", "../../g", "foo:../g"),
+	("foo:a/b", "../../../g", "foo:../../g"),
+	("foo:a/b/c", "../../../", "foo:../"),
+	("foo:a/b", "/../g", "foo:/g"),
+	("foo:", "../../g/../h", "foo:../../h"),
+];
+
+#[cfg(test)]
+mod tests {
+	use super::RFC3986_RESOLUTION_EXAMPLES;
+	use crate::{Iri, IriRefBuf};
+
+	#[test]
+	fn rfc3986_resolution() {
+		assert_eq!(RFC3986_RESOLUTION_EXAMPLES.len(), 48);
+
+		for (base, reference, result) in RFC3986_RESOLUTION_EXAMPLES {
+			let base = Iri::new(base).unwrap();
+			let resolved = IriRefBuf::new(reference).unwrap().resolved(base);
+			assert_eq!(
+				resolved.as_str(),
+				*result,
+				"resolving {} against {}",
+				reference,
+				base
+			);
+		}
+	}
+}
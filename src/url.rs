@@ -0,0 +1,136 @@
+//! Conversions with the [`url`](https://docs.rs/url) crate.
+//!
+//! [`url::Url`] implements the [WHATWG URL Standard](https://url.spec.whatwg.org/) rather than
+//! RFC 3986/3987, so conversions in both directions may alter the IRI:
+//!  - a [`Url`] is always an absolute URL, with a scheme;
+//!  - it is always a URI: non-ASCII characters are percent-encoded
+//!    (`http://例え.jp/日本` becomes `http://xn--r8jz45g.jp/%E6%97%A5%E6%9C%AC`),
+//!    and the host of special schemes (`http`, `https`, `ws`, `wss`, `ftp` and `file`)
+//!    is converted to ASCII with IDNA;
+//!  - the scheme, and the host of special schemes, are lowercased;
+//!  - the default port of special schemes is removed (`http://a:80` becomes `http://a/`);
+//!  - the path of special schemes is never empty (`http://a` becomes `http://a/`)
+//!    and its dot segments are removed (`http://a/b/../c` becomes `http://a/c`);
+//!  - for special schemes, empty user info and empty authorities are dropped:
+//!    `http://@a/` becomes `http://a/`, and `http:///a` is read as `http://a/`;
+//!  - `file:` URLs always have an authority: `file:foo` becomes `file:///foo`;
+//!  - characters such as `|` or `^` are allowed unencoded in a [`Url`] path,
+//!    and are percent-encoded when converted into an [`IriBuf`].
+//!
+//! Conversely, some valid IRIs are rejected by [`Url::parse`], such as
+//! IPv6 addresses with a zone identifier or ports greater than 65535.
+//! Use [`Url::parse`] for the reverse conversion with a custom base.
+use alloc::string::String;
+use core::convert::TryFrom;
+
+use ::url::{ParseError, Position, Url};
+
+use crate::{Error, Iri, IriBuf};
+
+/// Convert a [`Url`] into an IRI.
+///
+/// The only characters of the URL that are not allowed in an IRI are percent-encoded.
+/// See the [module documentation](self) for the differences between [`Url`] and IRIs.
+impl<'a> TryFrom<&'a Url> for IriBuf {
+	type Error = Error;
+
+	fn try_from(url: &'a Url) -> Result<IriBuf, Error> {
+		let url_str = url.as_str();
+		let mut buffer = String::with_capacity(url_str.len());
+		buffer.push_str(&url[..Position::BeforePath]);
+		crate::pct_encode_lenient(&url[Position::BeforePath..], &mut buffer);
+		IriBuf::try_from(buffer)
+	}
+}
+
+/// Convert an IRI into a [`Url`], with [`Url::parse`].
+///
+/// See the [module documentation](self) for the differences between [`Url`] and IRIs.
+impl<'a> TryFrom<Iri<'a>> for Url {
+	type Error = ParseError;
+
+	#[inline]
+	fn try_from(iri: Iri<'a>) -> Result<Url, ParseError> {
+		Url::parse(iri.as_str())
+	}
+}
+
+/// Convert an IRI into a [`Url`], with [`Url::parse`].
+///
+/// See the [module documentation](self) for the differences between [`Url`] and IRIs.
+impl<'a> TryFrom<&'a IriBuf> for Url {
+	type Error = ParseError;
+
+	#[inline]
+	fn try_from(iri: &'a IriBuf) -> Result<Url, ParseError> {
+		Url::parse(iri.as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Iri, IriBuf};
+	use std::convert::TryFrom;
+	use url::Url;
+
+	#[test]
+	fn from_url() {
+		let urls = [
+			("https://example.org/a?q#f", "https://example.org/a?q#f"),
+			("HTTP://User@Example.ORG:80", "http://User@example.org/"),
+			("http://a/b/../c/./d", "http://a/c/d"),
+			(
+				"http://例え.jp/日本",
+				"http://xn--r8jz45g.jp/%E6%97%A5%E6%9C%AC",
+			),
+			("file:foo", "file:///foo"),
+			("foo://", "foo://"),
+			("mailto:someone@example.org", "mailto:someone@example.org"),
+			("http://a/b|c^?d{e}#f`g", "http://a/b%7Cc%5E?d%7Be%7D#f%60g"),
+			("foo:a|[b]", "foo:a%7C%5Bb%5D"),
+			("http://[::1]:8080/", "http://[::1]:8080/"),
+			("http://a/%7e", "http://a/%7e"),
+		];
+
+		for (input, expected) in &urls {
+			let url = Url::parse(input).unwrap();
+			assert_eq!(IriBuf::try_from(&url).unwrap().as_str(), *expected);
+		}
+	}
+
+	#[test]
+	fn to_url() {
+		let iris = [
+			("https://example.org/a?q#f", "https://example.org/a?q#f"),
+			("http://example.org", "http://example.org/"),
+			("foo://example.org", "foo://example.org"),
+			("http://a/é?é#é", "http://a/%C3%A9?%C3%A9#%C3%A9"),
+			("urn:isbn:0451450523", "urn:isbn:0451450523"),
+		];
+
+		for (input, expected) in &iris {
+			let iri = Iri::new(input).unwrap();
+			assert_eq!(Url::try_from(iri).unwrap().as_str(), *expected);
+
+			let iri = IriBuf::new(input).unwrap();
+			assert_eq!(Url::try_from(&iri).unwrap().as_str(), *expected);
+		}
+
+		assert!(Url::try_from(Iri::new("http://[fe80::1%25eth0]/").unwrap()).is_err());
+		assert!(Url::try_from(Iri::new("foo://a:99999").unwrap()).is_err());
+	}
+
+	#[test]
+	fn round_trip() {
+		let inputs = [
+			"https://user:pw@example.org:8080/a/b?q=1&r#f",
+			"foo:a/b",
+			"file:///etc/hosts",
+		];
+
+		for input in &inputs {
+			let url = Url::try_from(Iri::new(input).unwrap()).unwrap();
+			assert_eq!(IriBuf::try_from(&url).unwrap().as_str(), *input);
+		}
+	}
+}
@@ -1,6 +1,7 @@
 extern crate iref;
 
-use iref::Iri;
+use iref::{Error, Iri, IriBuf, IriRefBuf};
+use std::convert::TryFrom;
 
 #[test]
 fn test1() {
@@ -39,6 +40,38 @@ fn test4() {
 	Iri::new(buffer).expect("parsing failed");
 }
 
+#[test]
+fn ipv6_literals() {
+	let valid = [
+		"[2001:db8::1]",
+		"[1:2:3:4:5:6:7:8]",
+		"[1::]",
+		"[::1]",
+		"[::ffff:192.0.2.1]",
+		"[fe80::1%25eth0]",
+	];
+
+	for host in &valid {
+		let buffer = format!("http://{}/", host);
+		let iri = Iri::new(&buffer).expect("parsing failed");
+		assert_eq!(iri.authority().unwrap().host(), *host);
+	}
+
+	let invalid = [
+		"[]",
+		"[1:]",
+		"[:1]",
+		"[1:2:3:4:5:6:7:8:9]",
+		"[1::2::3]",
+		"[fe80::1%25]",
+	];
+
+	for host in &invalid {
+		let buffer = format!("http://{}/", host);
+		assert!(Iri::new(&buffer).is_err(), "{} should be invalid", host);
+	}
+}
+
 #[test]
 fn test5() {
 	let buffer = "https:///foo/bar#frag";
@@ -85,3 +118,132 @@ fn test9() {
 
 	assert_eq!(iri1, iri2)
 }
+
+#[test]
+fn from_str() {
+	let iri: IriBuf = "https://www.rust-lang.org/foo/bar#frag".parse().unwrap();
+	assert_eq!(iri.as_str(), "https://www.rust-lang.org/foo/bar#frag");
+
+	let iri_ref: IriRefBuf = "../foo/bar?q".parse().unwrap();
+	assert_eq!(iri_ref.as_str(), "../foo/bar?q");
+
+	assert!("foo/bar".parse::<IriBuf>().is_err());
+	assert!("https:foo/bar space".parse::<IriBuf>().is_err());
+	assert!("https:foo/bar#frag#garbage".parse::<IriRefBuf>().is_err());
+}
+
+#[test]
+fn try_from() {
+	let iri = IriBuf::try_from("https://www.rust-lang.org/foo").unwrap();
+	assert_eq!(iri.as_str(), "https://www.rust-lang.org/foo");
+
+	let iri_ref = IriRefBuf::try_from(&b"../foo?q"[..]).unwrap();
+	assert_eq!(iri_ref.as_str(), "../foo?q");
+
+	let string = "https://www.rust-lang.org/foo".to_string();
+	let ptr = string.as_ptr();
+	let iri = IriBuf::try_from(string).unwrap();
+	assert_eq!(iri.as_str().as_ptr(), ptr);
+
+	assert!(matches!(
+		IriBuf::try_from("foo/bar".to_string()),
+		Err(Error::MissingScheme)
+	));
+	assert!(IriRefBuf::try_from(&b"\xFF"[..]).is_err());
+
+	let iris: Result<Vec<IriBuf>, Error> = vec!["http://a/", "http://b/"]
+		.into_iter()
+		.map(IriBuf::try_from)
+		.collect();
+	assert_eq!(iris.unwrap().len(), 2);
+}
+
+#[test]
+fn parse_detailed() {
+	use iref::parsing::{Component, Expected, ParsedIriRef};
+
+	let cases: [(&[u8], usize, Component, Expected); 10] = [
+		(
+			b"https://example.org/a b",
+			21,
+			Component::Path,
+			Expected::PathChar,
+		),
+		(b":foo", 0, Component::Scheme, Expected::SchemeStart),
+		(b"http://a b/", 8, Component::Host, Expected::HostChar),
+		(b"http://a:80a/", 11, Component::Port, Expected::PortDigit),
+		(b"http://[::g]/", 7, Component::Host, Expected::IpLiteral),
+		(b"/a%4g", 4, Component::Path, Expected::HexDigit),
+		(b"/a%4", 4, Component::Path, Expected::HexDigit),
+		(b"/a?q<", 4, Component::Query, Expected::QueryChar),
+		(b"/a#f#", 4, Component::Fragment, Expected::FragmentChar),
+		(b"/a?\xff", 3, Component::Query, Expected::Utf8),
+	];
+
+	for (input, offset, component, expected) in &cases {
+		let e = ParsedIriRef::parse_detailed(*input).unwrap_err();
+		assert_eq!(e.offset, *offset);
+		assert_eq!(e.component, *component);
+		assert_eq!(e.expected, *expected);
+	}
+
+	let e = ParsedIriRef::parse_detailed("https://example.org/a b").unwrap_err();
+	assert_eq!(
+		e.to_string(),
+		"Invalid path at offset 21 in path: expected a path character, `?`, `#` or the end of the input"
+	);
+	assert!(matches!(Error::from(e), Error::InvalidPath));
+
+	assert!(ParsedIriRef::parse_detailed("http://[::1%25eth0]:80/a?b#c").is_ok());
+}
+
+#[test]
+fn parse_host() {
+	use iref::parsing::parse_host;
+
+	let valid = [
+		"[::]",
+		"[::1]",
+		"[2001:db8::8a2e:370:7334]",
+		"[::ffff:192.0.2.1]",
+		"[fe80::1%25eth0]",
+		"127.0.0.1",
+		"255.255.255.255",
+		"example.org",
+		"caf%C3%A9.fr",
+		"café.fr",
+		"",
+	];
+
+	for host in &valid {
+		assert_eq!(
+			parse_host(host.as_bytes(), 0).unwrap(),
+			host.len(),
+			"{}",
+			host
+		);
+	}
+
+	// Only a prefix is a host.
+	let prefixes = [
+		("[::1]:80", 5),
+		("[::g]", 0),
+		("[1:2:3:4:5:6:7:8:9]", 0),
+		("[::1", 0),
+		("example.org:80", 11),
+		("a b", 1),
+	];
+
+	for (input, len) in &prefixes {
+		assert_eq!(parse_host(input.as_bytes(), 0).unwrap(), *len, "{}", input);
+	}
+
+	assert!(matches!(
+		parse_host(b"caf%C3%A.fr", 0),
+		Err(Error::InvalidPercentEncoding)
+	));
+	assert!(matches!(
+		parse_host(b"caf\xC3.fr", 0),
+		Err(Error::InvalidEncoding)
+	));
+}